@@ -0,0 +1,322 @@
+//! The sources of the agenda.  The source is chosen on start.
+
+pub mod caldav;
+pub mod khal;
+pub mod vdir;
+
+use std::sync::mpsc;
+
+use crate::config::Config;
+use crate::error::FrontendError;
+use crate::state::{AgendaSource, AgendaWriter, EventUpdate, NewEvent, WritableCalendar};
+use crate::zoneinfo;
+
+#[derive(Clone)]
+pub enum Backend {
+    Khal(khal::KhalAgendaSource),
+    Vdir(vdir::VdirAgendaSource),
+    CalDav(caldav::CalDavAgendaSource),
+}
+
+pub enum RequestHandle {
+    Khal(<khal::KhalAgendaSource as AgendaSource>::RequestHandle),
+    Vdir(<vdir::VdirAgendaSource as AgendaSource>::RequestHandle),
+    CalDav(<caldav::CalDavAgendaSource as AgendaSource>::RequestHandle),
+}
+
+pub enum SearchHandle {
+    Khal(<khal::KhalAgendaSource as AgendaSource>::SearchHandle),
+    Vdir(<vdir::VdirAgendaSource as AgendaSource>::SearchHandle),
+    CalDav(<caldav::CalDavAgendaSource as AgendaSource>::SearchHandle),
+}
+
+/// The result of the work which is done on a thread of its own.  See [`AgendaWriter::poll`].
+pub struct Pending<T> {
+    receiver: mpsc::Receiver<Result<T, FrontendError>>,
+}
+
+/// Runs `work` on the thread `name`.  Its result is taken by [`AgendaWriter::poll`].
+fn spawn<T: Send + 'static>(
+    name: &str,
+    work: impl FnOnce() -> Result<T, FrontendError> + Send + 'static,
+) -> Result<Pending<T>, FrontendError> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::Builder::new()
+        .name(name.to_owned())
+        .spawn(move || {
+            // The receiver is gone if nobody waits for the result.
+            _ = sender.send(work());
+        })
+        .map_err(FrontendError::ThreadIsNotSpawned)?;
+    Ok(Pending { receiver })
+}
+
+impl Backend {
+    /// Chooses the agenda source according to the environment.  If the variable `SEMANA_CALDAV`
+    /// is set, the events are requested from the CalDAV server at the URL it holds.  If the
+    /// variable `SEMANA_VDIR` is set, the events are read from the directory it points.
+    /// Otherwise, they are obtained from Khal.  The program Khal is taken from the variable
+    /// `SEMANA_BACKEND_BIN` or from the configuration.
+    pub fn from_env(config: &Config) -> Result<Self, FrontendError> {
+        let local_zone = zoneinfo::load_local();
+        let color = config.default_calendar_color;
+        if let Some(url) = std::env::var_os("SEMANA_CALDAV") {
+            let url = url.to_string_lossy();
+            let source = caldav::CalDavAgendaSource::new(&url, local_zone, color)?;
+            return Ok(Backend::CalDav(source));
+        }
+
+        let backend = match std::env::var_os("SEMANA_VDIR") {
+            Some(path) => {
+                Backend::Vdir(vdir::VdirAgendaSource::new(path.into(), local_zone, color))
+            }
+            None => {
+                let bin = std::env::var("SEMANA_BACKEND_BIN")
+                    .unwrap_or_else(|_| config.backend_bin.clone());
+                Backend::Khal(khal::KhalAgendaSource::new(&local_zone, bin, color))
+            }
+        };
+        Ok(backend)
+    }
+
+    /// The directories where the agenda is stored.  They are watched to refresh the agenda once
+    /// it's changed.
+    // FIXME(alex): the changes on the CalDAV server are not watched.  It can be done by polling
+    // the synchronization token of the calendars.
+    pub fn watched_directories(&self) -> Vec<std::path::PathBuf> {
+        match self {
+            Backend::Khal(source) => source.watched_directories(),
+            Backend::Vdir(source) => source.watched_directories(),
+            Backend::CalDav(_) => Vec::new(),
+        }
+    }
+}
+
+impl AgendaSource for Backend {
+    type RequestHandle = RequestHandle;
+    type SearchHandle = SearchHandle;
+
+    type Error = FrontendError;
+
+    fn request_range(
+        &self,
+        start: &calendar::date::Date,
+        day_count: u16,
+    ) -> Result<Self::RequestHandle, Self::Error> {
+        match self {
+            Backend::Khal(source) => source
+                .request_range(start, day_count)
+                .map(RequestHandle::Khal),
+            Backend::Vdir(source) => source
+                .request_range(start, day_count)
+                .map(RequestHandle::Vdir),
+            Backend::CalDav(source) => source
+                .request_range(start, day_count)
+                .map(RequestHandle::CalDav),
+        }
+    }
+
+    fn cancel(&self, handle: &Self::RequestHandle) {
+        match (self, handle) {
+            (Backend::Khal(source), RequestHandle::Khal(handle)) => source.cancel(handle),
+            (Backend::Vdir(source), RequestHandle::Vdir(handle)) => source.cancel(handle),
+            (Backend::CalDav(source), RequestHandle::CalDav(handle)) => source.cancel(handle),
+            _ => unreachable!("the handle must be created by the same backend"),
+        }
+    }
+
+    fn is_ready(&self, handle: &Self::RequestHandle) -> bool {
+        match (self, handle) {
+            (Backend::Khal(source), RequestHandle::Khal(handle)) => source.is_ready(handle),
+            (Backend::Vdir(source), RequestHandle::Vdir(handle)) => source.is_ready(handle),
+            (Backend::CalDav(source), RequestHandle::CalDav(handle)) => source.is_ready(handle),
+            _ => unreachable!("the handle must be created by the same backend"),
+        }
+    }
+
+    fn free(&self, handle: Self::RequestHandle) {
+        match (self, handle) {
+            (Backend::Khal(source), RequestHandle::Khal(handle)) => source.free(handle),
+            (Backend::Vdir(source), RequestHandle::Vdir(handle)) => source.free(handle),
+            (Backend::CalDav(source), RequestHandle::CalDav(handle)) => source.free(handle),
+            _ => unreachable!("the handle must be created by the same backend"),
+        }
+    }
+
+    fn fetch(
+        &self,
+        handle: &Self::RequestHandle,
+        start: &calendar::date::Date,
+    ) -> Result<calendar::obtain::WeekScheduleWithLanes, Self::Error> {
+        match (self, handle) {
+            (Backend::Khal(source), RequestHandle::Khal(handle)) => source.fetch(handle, start),
+            (Backend::Vdir(source), RequestHandle::Vdir(handle)) => source.fetch(handle, start),
+            (Backend::CalDav(source), RequestHandle::CalDav(handle)) => source.fetch(handle, start),
+            _ => unreachable!("the handle must be created by the same backend"),
+        }
+    }
+
+    fn search(
+        &self,
+        query: &str,
+        around: &calendar::date::Date,
+    ) -> Result<Self::SearchHandle, Self::Error> {
+        match self {
+            Backend::Khal(source) => source.search(query, around).map(SearchHandle::Khal),
+            Backend::Vdir(source) => source.search(query, around).map(SearchHandle::Vdir),
+            Backend::CalDav(source) => source.search(query, around).map(SearchHandle::CalDav),
+        }
+    }
+
+    fn is_search_ready(&self, handle: &Self::SearchHandle) -> bool {
+        match (self, handle) {
+            (Backend::Khal(source), SearchHandle::Khal(handle)) => source.is_search_ready(handle),
+            (Backend::Vdir(source), SearchHandle::Vdir(handle)) => source.is_search_ready(handle),
+            (Backend::CalDav(source), SearchHandle::CalDav(handle)) => {
+                source.is_search_ready(handle)
+            }
+            _ => unreachable!("the handle must be created by the same backend"),
+        }
+    }
+
+    fn fetch_matches(
+        &self,
+        handle: &Self::SearchHandle,
+    ) -> Result<Vec<calendar::obtain::SearchMatch>, Self::Error> {
+        match (self, handle) {
+            (Backend::Khal(source), SearchHandle::Khal(handle)) => source.fetch_matches(handle),
+            (Backend::Vdir(source), SearchHandle::Vdir(handle)) => source.fetch_matches(handle),
+            (Backend::CalDav(source), SearchHandle::CalDav(handle)) => source.fetch_matches(handle),
+            _ => unreachable!("the handle must be created by the same backend"),
+        }
+    }
+}
+
+/// The owned [`NewEvent`] which is moved to the thread writing it.
+struct NewEventBuf {
+    calendar: String,
+    title: String,
+    description: String,
+    location: String,
+    url: String,
+    range: calendar::EventRange,
+    is_all_day: bool,
+}
+
+impl NewEventBuf {
+    fn new(event: &NewEvent) -> Self {
+        Self {
+            calendar: event.calendar.to_owned(),
+            title: event.title.to_owned(),
+            description: event.description.to_owned(),
+            location: event.location.to_owned(),
+            url: event.url.to_owned(),
+            range: event.range.clone(),
+            is_all_day: event.is_all_day,
+        }
+    }
+
+    fn as_event(&self) -> NewEvent<'_> {
+        NewEvent {
+            calendar: &self.calendar,
+            title: &self.title,
+            description: &self.description,
+            location: &self.location,
+            url: &self.url,
+            range: &self.range,
+            is_all_day: self.is_all_day,
+        }
+    }
+}
+
+/// The owned [`EventUpdate`] which is moved to the thread writing it.
+struct EventUpdateBuf {
+    uid: String,
+    calendar: String,
+    title: String,
+    description: String,
+    location: String,
+    url: String,
+    range: Option<calendar::EventRange>,
+}
+
+impl EventUpdateBuf {
+    fn new(event: &EventUpdate) -> Self {
+        Self {
+            uid: event.uid.to_owned(),
+            calendar: event.calendar.to_owned(),
+            title: event.title.to_owned(),
+            description: event.description.to_owned(),
+            location: event.location.to_owned(),
+            url: event.url.to_owned(),
+            range: event.range.cloned(),
+        }
+    }
+
+    fn as_event(&self) -> EventUpdate<'_> {
+        EventUpdate {
+            uid: &self.uid,
+            calendar: &self.calendar,
+            title: &self.title,
+            description: &self.description,
+            location: &self.location,
+            url: &self.url,
+            range: self.range.as_ref(),
+        }
+    }
+}
+
+/// The agenda is written on a thread of its own.  E.g. the CalDAV server might take a while to
+/// answer.
+impl AgendaWriter for Backend {
+    type Error = FrontendError;
+    type Pending<T> = Pending<T>;
+
+    fn calendars(&self) -> Result<Pending<Vec<WritableCalendar>>, Self::Error> {
+        let backend = self.clone();
+        spawn("calendars", move || match &backend {
+            Backend::Khal(source) => source.calendars(),
+            Backend::Vdir(source) => source.calendars(),
+            Backend::CalDav(source) => source.calendars(),
+        })
+    }
+
+    fn create(&self, event: &NewEvent) -> Result<Pending<()>, Self::Error> {
+        let backend = self.clone();
+        let event = NewEventBuf::new(event);
+        spawn("create", move || match &backend {
+            Backend::Khal(source) => source.create(&event.as_event()),
+            Backend::Vdir(source) => source.create(&event.as_event()),
+            Backend::CalDav(source) => source.create(&event.as_event()),
+        })
+    }
+
+    fn update(&self, event: &EventUpdate) -> Result<Pending<()>, Self::Error> {
+        let backend = self.clone();
+        let event = EventUpdateBuf::new(event);
+        spawn("update", move || match &backend {
+            Backend::Khal(source) => source.update(&event.as_event()),
+            Backend::Vdir(source) => source.update(&event.as_event()),
+            Backend::CalDav(source) => source.update(&event.as_event()),
+        })
+    }
+
+    fn delete(&self, uid: &str, calendar: &str) -> Result<Pending<()>, Self::Error> {
+        let backend = self.clone();
+        let (uid, calendar) = (uid.to_owned(), calendar.to_owned());
+        spawn("delete", move || match &backend {
+            Backend::Khal(source) => source.delete(&uid, &calendar),
+            Backend::Vdir(source) => source.delete(&uid, &calendar),
+            Backend::CalDav(source) => source.delete(&uid, &calendar),
+        })
+    }
+
+    fn poll<T>(&self, pending: &Pending<T>) -> Option<Result<T, Self::Error>> {
+        match pending.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            // The thread has gone without the result.  E.g. it has panicked.
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(FrontendError::WorkIsInterrupted)),
+        }
+    }
+}
@@ -0,0 +1,553 @@
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use calendar::obtain::AgendaStream;
+use calendar::tz::{TimeZone, convert};
+
+use crate::error::FrontendError;
+use crate::state::{AgendaSource, EventUpdate, NewEvent, WritableCalendar};
+use crate::zoneinfo;
+
+/// It provides the data from the program Khal.  It provides the data according the trait
+/// AgendaSource.
+#[derive(Clone)]
+pub struct KhalAgendaSource {
+    zone_conversion: Option<ZoneConversion>,
+    /// The zone of the machine.  The changes of the events are given in it.
+    local_zone: Arc<TimeZone>,
+    /// The path of the program Khal.
+    bin: String,
+    /// The color of the events whose calendar has no color.
+    default_color: calendar::Color,
+}
+
+/// Khal provides the time in the zone of its configuration.  If the zone is set, the time is moved
+/// to the zone of the machine.
+#[derive(Clone)]
+struct ZoneConversion {
+    khal: TimeZone,
+    local: TimeZone,
+}
+
+/// Reads the configuration file of Khal.
+fn read_config() -> Option<String> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".config")));
+    let paths = [
+        config_home.map(|dir| dir.join("khal").join("config")),
+        home.map(|home| home.join(".khal").join("khal.conf")),
+    ];
+
+    paths
+        .into_iter()
+        .flatten()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+}
+
+/// Reads the option `local_timezone` from the section `locale` of the configuration of Khal.
+fn read_timezone(content: &str) -> Option<String> {
+    let mut is_locale = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            is_locale = line == "[locale]";
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        if is_locale && key.trim() == "local_timezone" {
+            return Some(value.trim().to_owned());
+        }
+    }
+
+    None
+}
+
+/// Reads the option `path` of every calendar from the section `calendars` of the configuration of
+/// Khal.  The calendar is a subsection.  E.g. `[[work]]`.  Returns the pairs of the name of the
+/// calendar and its path.
+///
+/// FIXME(alex): the calendars of the type `discover` are not supported.  Their path is a pattern.
+fn read_calendar_paths(content: &str) -> Vec<(String, PathBuf)> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut ret = Vec::new();
+    let mut is_calendars = false;
+    let mut calendar: Option<&str> = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(subsection) = line.strip_prefix("[[").and_then(|l| l.strip_suffix("]]")) {
+            calendar = is_calendars.then_some(subsection.trim());
+            continue;
+        }
+
+        if line.starts_with('[') {
+            is_calendars = line == "[calendars]";
+            calendar = None;
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        if let Some(name) = calendar
+            && key.trim() == "path"
+        {
+            let value = value.trim();
+            let path = match (value.strip_prefix("~/"), &home) {
+                (Some(relative), Some(home)) => home.join(relative),
+                (Some(_), None) => continue,
+                (None, _) => PathBuf::from(value),
+            };
+            ret.push((name.to_owned(), path));
+        }
+    }
+
+    ret
+}
+
+/// Reads the option `path` of the calendar `name`.  See [`read_calendar_paths`].
+fn read_calendar_path(content: &str, name: &str) -> Option<PathBuf> {
+    read_calendar_paths(content)
+        .into_iter()
+        .find(|(calendar, _)| calendar == name)
+        .map(|(_, path)| path)
+}
+
+/// The directory where Khal stores the events of the calendar `name`.
+fn find_calendar_directory(name: &str) -> Result<PathBuf, FrontendError> {
+    read_config()
+        .and_then(|config| read_calendar_path(&config, name))
+        .ok_or_else(|| {
+            FrontendError::FileIsNotWritten(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("the path of the calendar {name} is not found"),
+            ))
+        })
+}
+
+/// Converts the arguments of a program to C strings.
+fn to_cstrings(args: &[&str]) -> Result<Vec<CString>, FrontendError> {
+    args.iter()
+        .map(|s| CString::new(*s))
+        .collect::<Result<_, _>>()
+        .map_err(FrontendError::CStringIsNotCreated)
+}
+
+/// Runs Khal with the arguments and waits until it exits.  Returns the standard output of Khal.
+/// The `variables` are added to the environment of Khal.  The function blocks the thread.
+fn run_khal(bin: &str, args: &[&str], variables: &[(&str, &str)]) -> Result<String, FrontendError> {
+    let args: Vec<&str> = std::iter::once(bin).chain(args.iter().copied()).collect();
+    let mut process = spawn_khal(&args, variables)?;
+    let exit_code = process.wait().map_err(FrontendError::AgendaSourceFailed)?;
+    if exit_code != 0 {
+        return Err(FrontendError::AgendaIsNotUpdated(exit_code));
+    }
+
+    Ok(String::from_utf8_lossy(&process.take_stdout()).into_owned())
+}
+
+/// Runs Khal with the arguments.  The first argument is the path to Khal.  The `variables` are
+/// added to the environment of Khal.
+fn spawn_khal(args: &[&str], variables: &[(&str, &str)]) -> Result<sdlext::Process, FrontendError> {
+    let args: Vec<CString> = to_cstrings(args)?;
+    let args: Vec<&CStr> = args.iter().map(CString::as_c_str).collect();
+    let (names, values): (Vec<&str>, Vec<&str>) = variables.iter().copied().unzip();
+    let names: Vec<CString> = to_cstrings(&names)?;
+    let values: Vec<CString> = to_cstrings(&values)?;
+    let variables: Vec<(&CStr, &CStr)> = names
+        .iter()
+        .zip(&values)
+        .map(|(name, value)| (name.as_c_str(), value.as_c_str()))
+        .collect();
+    sdlext::Process::spawn(&args, &variables).map_err(FrontendError::AgendaSourceFailed)
+}
+
+/// The fields of the events which are taken from Khal.  See [`calendar::JsonInputEvent`].
+const EVENT_FIELDS: [&str; 12] = [
+    "title",
+    "start-date",
+    "start-time",
+    "end-date",
+    "end-time",
+    "all-day",
+    "calendar-color",
+    "description",
+    "url",
+    "location",
+    "calendar",
+    "uid",
+];
+
+/// The width of the terminal which is given to Khal by the variable `COLUMNS`.  Khal wraps the
+/// output of the command `search` by the width of the terminal.  Given that, the terminal is wide
+/// enough to keep every event on its line.
+const SEARCH_COLUMNS: &str = "1000000";
+
+/// Formats the moment as Khal takes it in the command `new`.
+fn format_date_time(date: &calendar::date::Date, time: &calendar::date::Time) -> String {
+    format!(
+        "{} {:02}:{:02}",
+        date.iso_8601().as_str(),
+        time.hour,
+        time.minute
+    )
+}
+
+impl KhalAgendaSource {
+    pub fn new(local_zone: &TimeZone, bin: String, default_color: calendar::Color) -> Self {
+        let zone_conversion = read_config()
+            .as_deref()
+            .and_then(read_timezone)
+            .and_then(|name| zoneinfo::load(&name))
+            .map(|khal| ZoneConversion {
+                khal,
+                local: local_zone.clone(),
+            });
+        Self {
+            zone_conversion,
+            local_zone: Arc::new(local_zone.clone()),
+            bin,
+            default_color,
+        }
+    }
+}
+
+impl KhalAgendaSource {
+    /// The directories where Khal stores the events.  They are taken from its configuration.
+    pub fn watched_directories(&self) -> Vec<PathBuf> {
+        read_config()
+            .map(|config| read_calendar_paths(&config))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, path)| path)
+            .collect()
+    }
+}
+
+/// The running command `khal list`.  Its output is parsed day by day as it arrives.
+pub struct RequestHandle {
+    process: RefCell<sdlext::Process>,
+    /// The days parsed so far.  It holds the first error of reading or parsing the output.
+    agenda: RefCell<Result<AgendaStream, FrontendError>>,
+}
+
+/// The running command `khal search`.  Its output is parsed at once when Khal exits.
+pub struct SearchHandle {
+    process: RefCell<sdlext::Process>,
+    /// The failure of reading the output.
+    failure: RefCell<Option<sdlext::Error>>,
+}
+
+impl AgendaSource for KhalAgendaSource {
+    type RequestHandle = RequestHandle;
+    type SearchHandle = SearchHandle;
+
+    type Error = FrontendError;
+
+    fn request_range(
+        &self,
+        start: &calendar::date::Date,
+        day_count: u16,
+    ) -> Result<Self::RequestHandle, Self::Error> {
+        // The conversion of the zone can move the events over the edge of the range.  Given
+        // that, a day before and a day after the range are requested as well.
+        let margin = u16::from(self.zone_conversion.is_some());
+        let first = start.add_days(-(margin as i16));
+        let mut arguments = calendar::obtain::khal::range_arguments(&first, day_count + 2 * margin);
+        arguments.backend_bin_path = &self.bin;
+
+        let from = arguments.from.iso_8601();
+        let duration = format!("{}d", arguments.duration_days);
+        let mut args: Vec<&str> = vec![arguments.backend_bin_path, "list"];
+        args.extend(EVENT_FIELDS.iter().flat_map(|field| ["--json", field]));
+        args.extend([from.as_str(), duration.as_str()]);
+        let process = spawn_khal(&args, &[])?;
+        Ok(RequestHandle {
+            process: RefCell::new(process),
+            agenda: RefCell::new(Ok(AgendaStream::with_margin(start, day_count, margin))),
+        })
+    }
+
+    fn cancel(&self, handle: &Self::RequestHandle) {
+        handle.process.borrow().kill();
+    }
+
+    fn free(&self, handle: Self::RequestHandle) {
+        drop(handle);
+    }
+
+    /// Reads the output which has arrived since the last call and parses its complete lines.
+    /// Khal is never blocked on the full pipe.  Given that, it exits once the output is read.
+    fn is_ready(&self, handle: &Self::RequestHandle) -> bool {
+        let mut process = handle.process.borrow_mut();
+        let mut agenda = handle.agenda.borrow_mut();
+        let is_exited = match process.drain() {
+            Ok(x) => x,
+            Err(e) => {
+                *agenda = Err(FrontendError::AgendaSourceFailed(e));
+                return true;
+            }
+        };
+
+        while let Some(line) = process.take_line() {
+            let Ok(stream) = agenda.as_mut() else {
+                // The rest of the output is not needed.  It's read to let Khal exit.
+                continue;
+            };
+
+            let parsed = std::str::from_utf8(&line)
+                .map_err(calendar::obtain::Error::InvalidUnicode)
+                .and_then(|line| stream.push_line(&calendar::obtain::NanoSerde, line));
+            if let Err(e) = parsed {
+                *agenda = Err(FrontendError::AgendaIsNotObtained(e));
+            }
+        }
+
+        is_exited
+    }
+
+    fn fetch(
+        &self,
+        handle: &Self::RequestHandle,
+        start: &calendar::date::Date,
+    ) -> Result<calendar::obtain::WeekScheduleWithLanes, Self::Error> {
+        let process = handle.process.borrow();
+        // The data is taken once.  The handle is freed right after.
+        let agenda = handle.agenda.replace(Ok(AgendaStream::new(start)));
+        let agenda = match (process.exit_code(), agenda) {
+            (Some(0), Ok(agenda)) => agenda,
+            // The output can't be read before the process has exited.
+            (Some(0) | None, Err(e)) => return Err(e),
+            // The error of Khal explains the rest.
+            (Some(exit_code), _) => {
+                let stderr = String::from_utf8_lossy(process.stderr()).trim().to_owned();
+                return Err(FrontendError::AgendaSourceExited(exit_code, stderr));
+            }
+            (None, Ok(_)) => {
+                return Err(FrontendError::AgendaSourceFailed(
+                    sdlext::Error::ProcessIsNotRead,
+                ));
+            }
+        };
+
+        let color = self.default_color;
+        let schedule = match &self.zone_conversion {
+            None => agenda.finish(color),
+            Some(ZoneConversion { khal, local }) => agenda.finish_in_zone(color, khal, local),
+        };
+        Ok(schedule)
+    }
+
+    /// Runs the command `search` of Khal.  Khal searches all the events.  Given that, `around` is
+    /// not needed.
+    fn search(
+        &self,
+        query: &str,
+        _around: &calendar::date::Date,
+    ) -> Result<Self::SearchHandle, Self::Error> {
+        let mut args: Vec<&str> = vec![&self.bin, "search"];
+        args.extend(EVENT_FIELDS.iter().flat_map(|field| ["--json", field]));
+        // The query which starts with a dash is not an option.
+        args.extend(["--", query]);
+        let process = spawn_khal(&args, &[("COLUMNS", SEARCH_COLUMNS)])?;
+        Ok(SearchHandle {
+            process: RefCell::new(process),
+            failure: RefCell::new(None),
+        })
+    }
+
+    /// Reads the output which has arrived since the last call like [`Self::is_ready`].
+    fn is_search_ready(&self, handle: &Self::SearchHandle) -> bool {
+        match handle.process.borrow_mut().drain() {
+            Ok(is_exited) => is_exited,
+            Err(e) => {
+                *handle.failure.borrow_mut() = Some(e);
+                true
+            }
+        }
+    }
+
+    fn fetch_matches(
+        &self,
+        handle: &Self::SearchHandle,
+    ) -> Result<Vec<calendar::obtain::SearchMatch>, Self::Error> {
+        if let Some(e) = handle.failure.borrow_mut().take() {
+            return Err(FrontendError::AgendaSourceFailed(e));
+        }
+
+        let mut process = handle.process.borrow_mut();
+        match process.exit_code() {
+            Some(0) => (),
+            Some(exit_code) => {
+                let stderr = String::from_utf8_lossy(process.stderr()).trim().to_owned();
+                return Err(FrontendError::AgendaSourceExited(exit_code, stderr));
+            }
+            None => {
+                return Err(FrontendError::AgendaSourceFailed(
+                    sdlext::Error::ProcessIsNotRead,
+                ));
+            }
+        }
+
+        let output = String::from_utf8_lossy(&process.take_stdout()).into_owned();
+        let parser = &calendar::obtain::NanoSerde;
+        let found = match &self.zone_conversion {
+            None => calendar::obtain::parse_search_output(parser, &output),
+            Some(ZoneConversion { khal, local }) => {
+                calendar::obtain::parse_search_output_in_zone(parser, &output, khal, local)
+            }
+        };
+        found.map_err(FrontendError::AgendaIsNotObtained)
+    }
+}
+
+impl KhalAgendaSource {
+    pub fn calendars(&self) -> Result<Vec<WritableCalendar>, FrontendError> {
+        let output = run_khal(&self.bin, &["printcalendars"], &[])?;
+        let calendars = output
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| WritableCalendar {
+                name: name.to_owned(),
+                // FIXME(alex): Khal doesn't print the colors of the calendars.
+                color: self.default_color,
+            })
+            .collect();
+        Ok(calendars)
+    }
+
+    pub fn create(&self, event: &NewEvent) -> Result<(), FrontendError> {
+        let range = event.range;
+        // Khal takes the dates without the time as an all-day event.  Its end is inclusive.
+        if event.is_all_day {
+            let start = range.start_date.iso_8601();
+            let end = range.end_date.iso_8601();
+            return self.run_new(event, start.as_str(), end.as_str());
+        }
+
+        // The time is given in the zone of the machine.  Khal expects the time in its zone.
+        let ((start_date, start_time), (end_date, end_time)) = match &self.zone_conversion {
+            None => (
+                (range.start_date.clone(), range.start_time.clone()),
+                (range.end_date.clone(), range.end_time.clone()),
+            ),
+            Some(ZoneConversion { khal, local }) => (
+                convert(&range.start_date, &range.start_time, local, khal),
+                convert(&range.end_date, &range.end_time, local, khal),
+            ),
+        };
+
+        let start = format_date_time(&start_date, &start_time);
+        let end = format_date_time(&end_date, &end_time);
+        self.run_new(event, &start, &end)
+    }
+
+    /// Runs the command `new` of Khal.  `start` and `end` are formatted like Khal takes them.
+    fn run_new(&self, event: &NewEvent, start: &str, end: &str) -> Result<(), FrontendError> {
+        let mut args: Vec<&str> = vec!["new", "--calendar", event.calendar];
+        if !event.location.is_empty() {
+            args.extend(["--location", event.location]);
+        }
+
+        if !event.url.is_empty() {
+            args.extend(["--url", event.url]);
+        }
+
+        args.extend([start, end, event.title]);
+        if !event.description.is_empty() {
+            args.extend(["::", event.description]);
+        }
+
+        run_khal(&self.bin, &args, &[]).map(|_| ())
+    }
+
+    /// Khal doesn't change the events without the user.  Given that, the file of the event is
+    /// changed directly in the directory of the calendar.
+    pub fn update(&self, event: &EventUpdate) -> Result<(), FrontendError> {
+        let directory = find_calendar_directory(event.calendar)?;
+
+        // Khal observes the floating time in its zone.
+        let floating_zone = match &self.zone_conversion {
+            Some(ZoneConversion { khal, .. }) => khal,
+            None => &self.local_zone,
+        };
+        super::vdir::update_event_file(&directory, event, &self.local_zone, floating_zone)
+    }
+
+    /// Like [`Self::update`], the file of the event is deleted directly.
+    pub fn delete(&self, uid: &str, calendar: &str) -> Result<(), FrontendError> {
+        let directory = find_calendar_directory(calendar)?;
+        super::vdir::delete_event_file(&directory, uid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "
+[calendars]
+  [[work]]
+    path = /home/alex/.calendars/work
+    color = dark blue
+  [[home]]
+    type = calendar
+    path = ~/.calendars/home
+
+[locale]
+  timeformat = %H:%M
+  local_timezone = Europe/Berlin
+  default_timezone = UTC
+
+[default]
+  default_calendar = work
+";
+
+    #[test]
+    fn test_read_timezone() {
+        assert_eq!(read_timezone(CONFIG).as_deref(), Some("Europe/Berlin"));
+        // The option of another section is not taken.
+        let content = "[default]\nlocal_timezone = Asia/Tokyo\n[locale]\ntimeformat = %H:%M\n";
+        assert_eq!(read_timezone(content), None);
+        assert_eq!(read_timezone(""), None);
+    }
+
+    #[test]
+    fn test_read_calendar_paths() {
+        let mut expected = vec![(
+            String::from("work"),
+            PathBuf::from("/home/alex/.calendars/work"),
+        )];
+        // The path in the home directory is skipped without the home.
+        if let Some(home) = std::env::var_os("HOME") {
+            let path = PathBuf::from(home).join(".calendars/home");
+            expected.push((String::from("home"), path));
+        }
+        assert_eq!(read_calendar_paths(CONFIG), expected);
+
+        let work = read_calendar_path(CONFIG, "work");
+        assert_eq!(work, Some(PathBuf::from("/home/alex/.calendars/work")));
+        assert_eq!(read_calendar_path(CONFIG, "default"), None);
+    }
+
+    #[test]
+    fn test_read_calendar_paths_outside_calendars() {
+        // The subsections of the other sections are not calendars.
+        let content = "[calendars]
+[[work]]
+path = /work
+[view]
+[[stray]]
+path = /stray
+";
+        let paths = read_calendar_paths(content);
+        assert_eq!(paths, [(String::from("work"), PathBuf::from("/work"))]);
+    }
+}
@@ -0,0 +1,757 @@
+//! The agenda source which reads the iCalendar files directly.  The files are expected to be
+//! stored like vdirsyncer does it: the root directory contains a directory per calendar, every
+//! calendar directory contains `.ics` files.  The optional files `displayname` and `color` of a
+//! calendar directory provide the name and the color of the calendar.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+
+use calendar::obtain::ics;
+use calendar::obtain::{SearchMatch, WeekScheduleWithLanes};
+use calendar::tz::{TimeZone, TzTime, convert};
+
+use crate::error::FrontendError;
+use crate::state::{AgendaSource, EventUpdate, NewEvent, WritableCalendar};
+use crate::zoneinfo;
+
+#[derive(Clone)]
+pub struct VdirAgendaSource {
+    path: PathBuf,
+    local_zone: Arc<TimeZone>,
+    /// The color of the events whose calendar has no color.
+    default_color: calendar::Color,
+}
+
+impl VdirAgendaSource {
+    pub fn new(path: PathBuf, local_zone: TimeZone, default_color: calendar::Color) -> Self {
+        Self {
+            path,
+            local_zone: Arc::new(local_zone),
+            default_color,
+        }
+    }
+}
+
+impl VdirAgendaSource {
+    /// The root directory along with the directories of the calendars.
+    pub fn watched_directories(&self) -> Vec<PathBuf> {
+        let mut ret = vec![self.path.clone()];
+        ret.extend(calendar_directories(&self.path));
+        ret
+    }
+}
+
+pub struct RequestHandle {
+    receiver: mpsc::Receiver<WeekScheduleWithLanes>,
+    cancelled: Arc<AtomicBool>,
+    // The schedule is received in [`AgendaSource::is_ready`], but it's handed over in
+    // [`AgendaSource::fetch`].  Given that, it's kept here in between.
+    received: RefCell<Option<Result<WeekScheduleWithLanes, FrontendError>>>,
+}
+
+/// The search which scans the events on a thread of its own.  It's shared with
+/// [`super::caldav`].  The thread stops scanning once the handle is dropped.
+pub struct SearchHandle {
+    receiver: mpsc::Receiver<Result<Vec<SearchMatch>, FrontendError>>,
+    cancelled: Arc<AtomicBool>,
+    // See [`RequestHandle`].
+    received: RefCell<Option<Result<Vec<SearchMatch>, FrontendError>>>,
+}
+
+impl SearchHandle {
+    /// Searches the `query` in the schedule which is read by `read` on the thread `name`.  `read`
+    /// stops once the flag it's given is set.
+    pub(super) fn spawn(
+        name: &str,
+        query: &str,
+        read: impl FnOnce(&AtomicBool) -> Result<WeekScheduleWithLanes, FrontendError> + Send + 'static,
+    ) -> Result<Self, FrontendError> {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let thread_cancelled = Arc::clone(&cancelled);
+        let query = query.to_owned();
+        std::thread::Builder::new()
+            .name(name.to_owned())
+            .spawn(move || {
+                let found = read(&thread_cancelled)
+                    .map(|schedule| calendar::obtain::search(&schedule, &query));
+                // The receiver is gone if the search is abandoned.
+                _ = sender.send(found);
+            })
+            .map_err(FrontendError::ThreadIsNotSpawned)?;
+
+        Ok(Self {
+            receiver,
+            cancelled,
+            received: RefCell::new(None),
+        })
+    }
+
+    pub(super) fn is_ready(&self) -> bool {
+        let mut received = self.received.borrow_mut();
+        if received.is_none() {
+            match self.receiver.try_recv() {
+                Ok(found) => *received = Some(found),
+                Err(mpsc::TryRecvError::Empty) => return false,
+                // The thread has gone without the data, e.g. it has panicked.
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    *received = Some(Err(FrontendError::WorkIsInterrupted))
+                }
+            }
+        }
+
+        true
+    }
+
+    pub(super) fn fetch(&self) -> Result<Vec<SearchMatch>, FrontendError> {
+        self.received
+            .borrow_mut()
+            .take()
+            .unwrap_or(Err(FrontendError::WorkIsInterrupted))
+    }
+}
+
+impl Drop for SearchHandle {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The events of a calendar.  The calendar is either a directory of the vdir or a collection of a
+/// CalDAV server.
+pub(super) struct EventCollection {
+    pub name: String,
+    pub color: Option<calendar::Color>,
+    pub events: Vec<ics::Event>,
+}
+
+fn read_calendar_name(path: &Path) -> Option<String> {
+    let name = std::fs::read_to_string(path.join("displayname"))
+        .ok()
+        .or_else(|| path.file_name()?.to_str().map(String::from))?;
+    Some(name.trim().to_owned())
+}
+
+fn read_calendar_color(path: &Path) -> Option<calendar::Color> {
+    std::fs::read_to_string(path.join("color"))
+        .ok()
+        .and_then(|color| calendar::Color::from_hex(color.trim()))
+}
+
+fn calendar_directories(root: &Path) -> impl Iterator<Item = PathBuf> {
+    std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+}
+
+fn read_calendar_directory(path: &Path, cancelled: &AtomicBool) -> Option<EventCollection> {
+    let name = read_calendar_name(path)?;
+    let color = read_calendar_color(path);
+
+    let mut events = Vec::new();
+    for entry in std::fs::read_dir(path).ok()?.flatten() {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let file_path = entry.path();
+        if file_path.extension().is_none_or(|ext| ext != "ics") {
+            continue;
+        }
+
+        // FIXME(alex): the files which can't be read or parsed are skipped silently.  They should
+        // be reported.
+        let Ok(content) = std::fs::read_to_string(&file_path) else {
+            continue;
+        };
+
+        if let Ok(file_events) = ics::parse_events(&content) {
+            events.extend(file_events);
+        }
+    }
+
+    Some(EventCollection {
+        name,
+        color,
+        events,
+    })
+}
+
+// FIXME(alex): every request reads the entire directory tree.  It's fine for a small calendar, but
+// the events should be indexed once the tree gets big.
+fn read_range(
+    root: &Path,
+    start: &calendar::date::Date,
+    day_count: u16,
+    local_zone: &TimeZone,
+    default_color: calendar::Color,
+    cancelled: &AtomicBool,
+) -> WeekScheduleWithLanes {
+    let directories: Vec<EventCollection> = calendar_directories(root)
+        .filter_map(|path| read_calendar_directory(&path, cancelled))
+        .collect();
+    build_range(&directories, start, day_count, local_zone, default_color)
+}
+
+/// Schedules the events of the `collections` taking place during `day_count` days since `start`.
+/// The zones of the events are loaded from the system.  The events of the collections without a
+/// color take `default_color`.
+pub(super) fn build_range(
+    collections: &[EventCollection],
+    start: &calendar::date::Date,
+    day_count: u16,
+    local_zone: &TimeZone,
+    default_color: calendar::Color,
+) -> WeekScheduleWithLanes {
+    let zone_names = collections
+        .iter()
+        .flat_map(|c| &c.events)
+        .flat_map(|event| [&event.start.zone, &event.end.zone])
+        .filter_map(|zone| match zone {
+            ics::Zone::Named(name) => Some(name.as_str()),
+            ics::Zone::Floating | ics::Zone::Utc => None,
+        });
+    let zones = zoneinfo::load_all(zone_names);
+
+    let collections = collections.iter().map(|c| ics::Collection {
+        name: &c.name,
+        color: c.color,
+        events: &c.events,
+    });
+
+    ics::range_schedule(
+        collections,
+        start,
+        day_count,
+        default_color,
+        local_zone,
+        &zones,
+    )
+}
+
+/// The range scanned by the search of the sources which read the events directly: three years
+/// before `around` and a year after it.  Returns the first day and the number of the days.
+pub(super) fn search_range(around: &calendar::date::Date) -> (calendar::date::Date, u16) {
+    (around.add_days(-3 * 365), 4 * 365)
+}
+
+impl AgendaSource for VdirAgendaSource {
+    type RequestHandle = RequestHandle;
+    type SearchHandle = SearchHandle;
+
+    type Error = FrontendError;
+
+    fn request_range(
+        &self,
+        start: &calendar::date::Date,
+        day_count: u16,
+    ) -> Result<Self::RequestHandle, Self::Error> {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let root = self.path.clone();
+        let start = start.clone();
+        let thread_cancelled = Arc::clone(&cancelled);
+        let local_zone = Arc::clone(&self.local_zone);
+        let default_color = self.default_color;
+        std::thread::Builder::new()
+            .name(String::from("vdir"))
+            .spawn(move || {
+                let schedule = read_range(
+                    &root,
+                    &start,
+                    day_count,
+                    &local_zone,
+                    default_color,
+                    &thread_cancelled,
+                );
+                // The receiver is gone if the request is freed.  Nobody waits for the data.
+                _ = sender.send(schedule);
+            })
+            .map_err(FrontendError::ThreadIsNotSpawned)?;
+
+        Ok(RequestHandle {
+            receiver,
+            cancelled,
+            received: RefCell::new(None),
+        })
+    }
+
+    fn cancel(&self, handle: &Self::RequestHandle) {
+        handle.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn is_ready(&self, handle: &Self::RequestHandle) -> bool {
+        let mut received = handle.received.borrow_mut();
+        if received.is_none() {
+            match handle.receiver.try_recv() {
+                Ok(schedule) => *received = Some(Ok(schedule)),
+                Err(mpsc::TryRecvError::Empty) => return false,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    *received = Some(Err(FrontendError::WorkIsInterrupted))
+                }
+            }
+        }
+
+        true
+    }
+
+    fn free(&self, handle: Self::RequestHandle) {
+        self.cancel(&handle);
+    }
+
+    fn fetch(
+        &self,
+        handle: &Self::RequestHandle,
+        _start: &calendar::date::Date,
+    ) -> Result<WeekScheduleWithLanes, Self::Error> {
+        handle
+            .received
+            .borrow_mut()
+            .take()
+            .unwrap_or(Err(FrontendError::WorkIsInterrupted))
+    }
+
+    /// Reads the events of the years around `around`.  See [`search_range`].
+    fn search(
+        &self,
+        query: &str,
+        around: &calendar::date::Date,
+    ) -> Result<Self::SearchHandle, Self::Error> {
+        let (start, day_count) = search_range(around);
+        let root = self.path.clone();
+        let local_zone = Arc::clone(&self.local_zone);
+        let default_color = self.default_color;
+        SearchHandle::spawn("vdir-search", query, move |cancelled| {
+            let schedule = read_range(
+                &root,
+                &start,
+                day_count,
+                &local_zone,
+                default_color,
+                cancelled,
+            );
+            Ok(schedule)
+        })
+    }
+
+    fn is_search_ready(&self, handle: &Self::SearchHandle) -> bool {
+        handle.is_ready()
+    }
+
+    fn fetch_matches(&self, handle: &Self::SearchHandle) -> Result<Vec<SearchMatch>, Self::Error> {
+        handle.fetch()
+    }
+}
+
+fn find_calendar_directory(root: &Path, name: &str) -> Result<PathBuf, FrontendError> {
+    calendar_directories(root)
+        .find(|path| read_calendar_name(path).as_deref() == Some(name))
+        .ok_or_else(|| {
+            FrontendError::FileIsNotWritten(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("the calendar {name} is not found"),
+            ))
+        })
+}
+
+/// Moves the local time of the machine to the zone of the property of an event.  The floating
+/// time is observed in `floating_zone`.  The zones which are not found are treated as the
+/// floating time like [`ics::week_schedule`] does it.
+fn to_event_zone(
+    date: &calendar::date::Date,
+    time: &calendar::date::Time,
+    original: &ics::DateTime,
+    local_zone: &TimeZone,
+    floating_zone: &TimeZone,
+) -> ics::DateTime {
+    let named_zone = match &original.zone {
+        ics::Zone::Named(name) => zoneinfo::load(name),
+        ics::Zone::Floating | ics::Zone::Utc => None,
+    };
+
+    let (zone, target) = match (&original.zone, named_zone) {
+        (ics::Zone::Utc, _) => (ics::Zone::Utc, TimeZone::utc()),
+        (ics::Zone::Named(name), Some(target)) => (ics::Zone::Named(name.clone()), target),
+        (ics::Zone::Floating | ics::Zone::Named(_), _) => {
+            (ics::Zone::Floating, floating_zone.clone())
+        }
+    };
+
+    let (date, time) = convert(date, time, local_zone, &target);
+    ics::DateTime {
+        date,
+        time: Some(time),
+        zone,
+    }
+}
+
+/// Changes the event in the file of the calendar `directory` where the event is stored.  The file
+/// is replaced as a whole.  The range of the update is given in `local_zone`.  See
+/// [`to_event_zone`] for `floating_zone`.
+pub(super) fn update_event_file(
+    directory: &Path,
+    update: &EventUpdate,
+    local_zone: &TimeZone,
+    floating_zone: &TimeZone,
+) -> Result<(), FrontendError> {
+    let (path, content, event) = find_event_file(directory, update.uid)?;
+    let new_content = patch_event(&content, &event, update, local_zone, floating_zone)?;
+    // The file is written next to the original one and renamed.  Given that, the readers never
+    // get a half-written file.
+    let temporary_path = path.with_extension("ics.tmp");
+    std::fs::write(&temporary_path, new_content)
+        .and_then(|_| std::fs::rename(&temporary_path, &path))
+        .map_err(FrontendError::FileIsNotWritten)
+}
+
+/// Applies the update to the `content` of the calendar object storing the `event`.  Returns the
+/// new content of the object.  See [`update_event_file`] for the zones.
+pub(super) fn patch_event(
+    content: &str,
+    event: &ics::Event,
+    update: &EventUpdate,
+    local_zone: &TimeZone,
+    floating_zone: &TimeZone,
+) -> Result<String, FrontendError> {
+    let range = update.range.map(|range| {
+        if event.start.time.is_none() {
+            // The all-day event keeps taking entire days.  Its end is exclusive in the file.
+            let date = |date: calendar::date::Date| ics::DateTime {
+                date,
+                time: None,
+                zone: ics::Zone::Floating,
+            };
+            (
+                date(range.start_date.clone()),
+                date(range.end_date.add_days(1)),
+            )
+        } else {
+            let zone = |date, time, original| {
+                to_event_zone(date, time, original, local_zone, floating_zone)
+            };
+            (
+                zone(&range.start_date, &range.start_time, &event.start),
+                zone(&range.end_date, &range.end_time, &event.end),
+            )
+        }
+    });
+
+    let patch = ics::EventPatch {
+        uid: update.uid,
+        summary: update.title,
+        description: update.description,
+        location: update.location,
+        url: update.url,
+        range,
+    };
+
+    ics::update_event(content, &patch).map_err(FrontendError::EventIsNotUpdated)
+}
+
+/// Finds the file of the calendar `directory` which stores the event `uid`.  Returns the path of
+/// the file, its content and the event.
+fn find_event_file(
+    directory: &Path,
+    uid: &str,
+) -> Result<(PathBuf, String, ics::Event), FrontendError> {
+    let entries = std::fs::read_dir(directory).map_err(FrontendError::FileIsNotWritten)?;
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ics"))
+        .find_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            let event = ics::parse_events(&content)
+                .ok()?
+                .into_iter()
+                .find(|event| event.uid == uid && event.recurrence_id.is_none())?;
+            Some((path, content, event))
+        })
+        .ok_or(FrontendError::EventIsNotUpdated(
+            ics::Error::EventIsNotFound,
+        ))
+}
+
+/// Deletes the file which stores the event `uid`.  Like vdirsyncer expects it, the file is
+/// expected to store only the event along with the occurrences which replace its ones.  Given
+/// that, all the occurrences of a repeating event are deleted.  The confirmation of the deletion
+/// tells it.
+pub(super) fn delete_event_file(directory: &Path, uid: &str) -> Result<(), FrontendError> {
+    let (path, _, _) = find_event_file(directory, uid)?;
+    std::fs::remove_file(path).map_err(FrontendError::FileIsNotWritten)
+}
+
+fn to_utc(
+    date: &calendar::date::Date,
+    time: &calendar::date::Time,
+    zone: &TimeZone,
+) -> ics::DateTime {
+    let moment = TzTime::from_local(date, time, zone).to_zone(&TimeZone::utc());
+    ics::DateTime {
+        date: moment.date,
+        time: Some(moment.time),
+        zone: ics::Zone::Utc,
+    }
+}
+
+/// Makes the calendar object of the new event.  The range of the event is given in `local_zone`.
+/// Returns the generated UID of the event along with the content of the object.
+pub(super) fn new_event_object(event: &NewEvent, local_zone: &TimeZone) -> (String, String) {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let uid = format!("{}-{}@semana", now.as_nanos(), std::process::id());
+    let stamp = TzTime::from_unix(now.as_secs() as i64, &TimeZone::utc());
+    let stamp = ics::DateTime {
+        date: stamp.date,
+        time: Some(stamp.time),
+        zone: ics::Zone::Utc,
+    };
+
+    let range = event.range;
+    // The dates of an all-day event are not bound to any zone.  The end of the event is exclusive.
+    let (start, end) = match event.is_all_day {
+        true => (
+            ics::DateTime {
+                date: range.start_date.clone(),
+                time: None,
+                zone: ics::Zone::Floating,
+            },
+            ics::DateTime {
+                date: range.end_date.add_days(1),
+                time: None,
+                zone: ics::Zone::Floating,
+            },
+        ),
+        false => (
+            to_utc(&range.start_date, &range.start_time, local_zone),
+            to_utc(&range.end_date, &range.end_time, local_zone),
+        ),
+    };
+    let ics_event = ics::Event {
+        uid,
+        summary: event.title.to_owned(),
+        description: event.description.to_owned(),
+        location: event.location.to_owned(),
+        url: event.url.to_owned(),
+        start,
+        end,
+        recurrence: Default::default(),
+        recurrence_id: None,
+    };
+
+    let content = ics::write_event(&ics_event, &stamp);
+    (ics_event.uid, content)
+}
+
+impl VdirAgendaSource {
+    pub fn calendars(&self) -> Result<Vec<WritableCalendar>, FrontendError> {
+        let calendars = calendar_directories(&self.path)
+            .filter_map(|path| {
+                Some(WritableCalendar {
+                    name: read_calendar_name(&path)?,
+                    color: read_calendar_color(&path).unwrap_or(self.default_color),
+                })
+            })
+            .collect();
+        Ok(calendars)
+    }
+
+    pub fn create(&self, event: &NewEvent) -> Result<(), FrontendError> {
+        let directory = find_calendar_directory(&self.path, event.calendar)?;
+
+        let (uid, content) = new_event_object(event, &self.local_zone);
+        let file_path = directory.join(format!("{uid}.ics"));
+        use std::io::Write;
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(file_path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(FrontendError::FileIsNotWritten)
+    }
+
+    pub fn update(&self, event: &EventUpdate) -> Result<(), FrontendError> {
+        let directory = find_calendar_directory(&self.path, event.calendar)?;
+        update_event_file(&directory, event, &self.local_zone, &self.local_zone)
+    }
+
+    pub fn delete(&self, uid: &str, calendar: &str) -> Result<(), FrontendError> {
+        let directory = find_calendar_directory(&self.path, calendar)?;
+        delete_event_file(&directory, uid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//test//test//EN\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTART:20260314T090000\r
+DTEND:20260314T093000\r
+SUMMARY:Standup\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Reminder\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:review@example.com\r
+DTSTART:20260314T120000Z\r
+DTEND:20260314T130000Z\r
+SUMMARY:Review\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:holiday@example.com\r
+DTSTART;VALUE=DATE:20260314\r
+DTEND;VALUE=DATE:20260315\r
+SUMMARY:Holiday\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn range(start: (u8, u8, u8), end: (u8, u8, u8)) -> calendar::EventRange {
+        let day = |day: u8| calendar::date::Date::new::<2026, 3, 1>().add_days(day as i16 - 1);
+        let time = |hour, minute| calendar::date::Time::try_new(hour, minute).unwrap();
+        calendar::EventRange {
+            start_date: day(start.0),
+            start_time: time(start.1, start.2),
+            end_date: day(end.0),
+            end_time: time(end.1, end.2),
+        }
+    }
+
+    /// Patches the event `uid` of [`CALENDAR`].  The machine is an hour ahead of UTC.  The
+    /// floating time is observed in the zone of the machine.
+    fn patch(uid: &str, title: &str, range: Option<&calendar::EventRange>) -> String {
+        let local_zone = TimeZone::fixed(3600);
+        let event = ics::parse_events(CALENDAR)
+            .unwrap()
+            .into_iter()
+            .find(|event| event.uid == uid)
+            .unwrap();
+        let update = EventUpdate {
+            uid,
+            calendar: "work",
+            title,
+            description: "",
+            location: "Room 1",
+            url: "",
+            range,
+        };
+        let patched = patch_event(CALENDAR, &event, &update, &local_zone, &local_zone);
+        let Ok(content) = patched else {
+            panic!("the event {uid} is not patched");
+        };
+        content
+    }
+
+    #[test]
+    fn test_patch_floating_event() {
+        let content = patch(
+            "standup@example.com",
+            "Daily",
+            Some(&range((14, 10, 0), (14, 10, 45))),
+        );
+        assert!(content.contains("DTSTART:20260314T100000\r\n"));
+        assert!(content.contains("DTEND:20260314T104500\r\n"));
+        assert!(content.contains("SUMMARY:Daily\r\n"));
+        assert!(content.contains("LOCATION:Room 1\r\n"));
+        // The rest of the object is kept.
+        assert!(content.contains("TRIGGER:-PT15M\r\n"));
+        assert!(content.contains("SUMMARY:Review\r\n"));
+    }
+
+    #[test]
+    fn test_patch_utc_event() {
+        let content = patch(
+            "review@example.com",
+            "Review",
+            Some(&range((15, 9, 0), (15, 10, 0))),
+        );
+        assert!(content.contains("DTSTART:20260315T080000Z\r\n"));
+        assert!(content.contains("DTEND:20260315T090000Z\r\n"));
+        // The other events keep their time.
+        assert!(content.contains("DTSTART:20260314T090000\r\n"));
+    }
+
+    #[test]
+    fn test_patch_all_day_event() {
+        // The end of the range is inclusive.  The end in the file is exclusive.
+        let content = patch(
+            "holiday@example.com",
+            "Trip",
+            Some(&range((16, 0, 0), (17, 23, 59))),
+        );
+        assert!(content.contains("DTSTART;VALUE=DATE:20260316\r\n"));
+        assert!(content.contains("DTEND;VALUE=DATE:20260318\r\n"));
+        assert!(content.contains("SUMMARY:Trip\r\n"));
+    }
+
+    #[test]
+    fn test_patch_keeps_time() {
+        let content = patch("review@example.com", "Design review", None);
+        assert!(content.contains("DTSTART:20260314T120000Z\r\n"));
+        assert!(content.contains("SUMMARY:Design review\r\n"));
+    }
+
+    #[test]
+    fn test_new_event_object() {
+        let range = range((14, 10, 0), (14, 11, 30));
+        let event = NewEvent {
+            calendar: "work",
+            title: "Lunch, finally",
+            description: "",
+            location: "",
+            url: "https://example.com",
+            range: &range,
+            is_all_day: false,
+        };
+        let (uid, content) = new_event_object(&event, &TimeZone::fixed(3600));
+
+        assert!(uid.ends_with("@semana"));
+        assert!(content.contains(&format!("UID:{uid}\r\n")));
+        // The time is written in UTC.
+        assert!(content.contains("DTSTART:20260314T090000Z\r\n"));
+        assert!(content.contains("DTEND:20260314T103000Z\r\n"));
+        assert!(content.contains("SUMMARY:Lunch\\, finally\r\n"));
+        assert!(content.contains("URL:https://example.com\r\n"));
+        assert!(!content.contains("LOCATION"));
+
+        let events = ics::parse_events(&content).unwrap();
+        let summaries: Vec<&str> = events.iter().map(|x| x.summary.as_str()).collect();
+        assert_eq!(summaries, ["Lunch, finally"]);
+        assert_eq!(events[0].uid, uid);
+    }
+
+    #[test]
+    fn test_new_all_day_event_object() {
+        // The trip takes the entire 14th and 15th.
+        let range = range((14, 0, 0), (15, 23, 59));
+        let event = NewEvent {
+            calendar: "work",
+            title: "Trip",
+            description: "",
+            location: "",
+            url: "",
+            range: &range,
+            is_all_day: true,
+        };
+        let (_, content) = new_event_object(&event, &TimeZone::fixed(3600));
+
+        // The dates are not moved to UTC.  The end is exclusive.
+        assert!(content.contains("DTSTART;VALUE=DATE:20260314\r\n"));
+        assert!(content.contains("DTEND;VALUE=DATE:20260316\r\n"));
+    }
+}
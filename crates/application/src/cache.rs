@@ -0,0 +1,104 @@
+//! Stores the weeks obtained from the agenda source on the disk.  The stored week is shown at once
+//! while the source takes its time to provide the fresh one.  The files are kept in
+//! `$XDG_CACHE_HOME/semana` (or `~/.cache/semana`), a file per week.
+
+use std::path::PathBuf;
+
+use calendar::date::Date;
+use calendar::obtain::WeekScheduleWithLanes;
+use nanoserde::{DeBin, SerBin};
+
+use crate::state;
+
+/// The version of the layout of the files.  It's bumped once [`WeekScheduleWithLanes`] changes.
+/// The files of the other versions are ignored.
+const FORMAT_VERSION: u32 = 3;
+
+pub struct WeekCache {
+    /// The directory of the files.  If it can't be figured out, nothing is stored.
+    directory: Option<PathBuf>,
+    /// The number of the days in a stored range.  The ranges of different lengths are kept in
+    /// different files.
+    day_count: u16,
+}
+
+impl WeekCache {
+    pub fn new(directory: Option<PathBuf>, day_count: u16) -> Self {
+        Self {
+            directory,
+            day_count,
+        }
+    }
+
+    pub fn from_env(day_count: u16) -> Self {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")));
+        Self::new(base.map(|base| base.join("semana")), day_count)
+    }
+
+    fn file_path(&self, week_start: &Date) -> Option<PathBuf> {
+        let Date { year, month, day } = week_start;
+        let name = match self.day_count {
+            7 => format!("{year:04}-{month:02}-{day:02}.week"),
+            n => format!("{year:04}-{month:02}-{day:02}-{n}.week"),
+        };
+        self.directory
+            .as_ref()
+            .map(|directory| directory.join(name))
+    }
+}
+
+impl state::WeekCache for WeekCache {
+    fn load(&self, week_start: &Date) -> Option<WeekScheduleWithLanes> {
+        let bytes = std::fs::read(self.file_path(week_start)?).ok()?;
+        let (version, schedule): (u32, WeekScheduleWithLanes) =
+            DeBin::deserialize_bin(&bytes).ok()?;
+        (version == FORMAT_VERSION).then_some(schedule)
+    }
+
+    // FIXME(alex): the failure to store the week is ignored.  The week is requested from the
+    // source anyway, but the user should know that the cache doesn't work.
+    fn store(&self, week_start: &Date, schedule: &WeekScheduleWithLanes) {
+        let (Some(directory), Some(path)) = (&self.directory, self.file_path(week_start)) else {
+            return;
+        };
+
+        let mut bytes = Vec::new();
+        FORMAT_VERSION.ser_bin(&mut bytes);
+        schedule.ser_bin(&mut bytes);
+        // The file is written next to the original one and renamed.  Given that, a half-written
+        // file is never read.
+        let temporary_path = path.with_extension("week.tmp");
+        _ = std::fs::create_dir_all(directory)
+            .and_then(|_| std::fs::write(&temporary_path, bytes))
+            .and_then(|_| std::fs::rename(&temporary_path, &path));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::WeekCache as _;
+
+    #[test]
+    fn test_store_and_load() {
+        let directory = std::env::temp_dir().join(format!("semana-cache-{}", std::process::id()));
+        let cache = WeekCache::new(Some(directory.clone()), 7);
+        let week_start = Date::new::<2025, 11, 3>();
+        assert!(cache.load(&week_start).is_none());
+
+        let mut schedule = WeekScheduleWithLanes::default();
+        schedule.short.titles.push(String::from("Standup"));
+        cache.store(&week_start, &schedule);
+        let loaded = cache.load(&week_start);
+        let short_loaded = WeekCache::new(Some(directory.clone()), 3).load(&week_start);
+        _ = std::fs::remove_dir_all(&directory);
+
+        let titles: Vec<String> = loaded.map(|x| x.short.titles).unwrap_or_default();
+        assert_eq!(titles, ["Standup"]);
+        assert!(cache.load(&Date::new::<2025, 11, 10>()).is_none());
+        assert!(short_loaded.is_none());
+    }
+}
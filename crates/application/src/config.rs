@@ -0,0 +1,273 @@
+//! The configuration of the application.  It's read on start from `$XDG_CONFIG_HOME/semana/config`
+//! (or `~/.config/semana/config`).  Every line of the file is either an option `key = value` or a
+//! comment starting with `#`.  The options which are absent take the default values.  E.g.
+//!
+//! ```text
+//! font = /usr/share/fonts/TTF/Hack-Regular.ttf
+//! background_color = #202020
+//! grid_scale_step = 25
+//! ```
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use calendar::date::Weekday;
+use sdl3_sys::SDL_FPoint as FPoint;
+
+/// The font which is used unless the option `font` is given.
+const FONT_CONTENT: &[u8] = include_bytes!("../../../assets/DejaVuSansMonoBook.ttf");
+
+/// The longest range of the days shown at once.
+const MAX_DAY_COUNT: u16 = 28;
+
+/// The colors of the interface given as `0xRRGGBB`.
+#[derive(Clone, Copy)]
+pub struct Colors {
+    pub background: u32,
+    /// The selected text and the selected time of a new event.
+    pub text_highlight: u32,
+    pub text_field_background: u32,
+    /// The background of the banner telling about the failure of the agenda source.
+    pub banner: u32,
+    /// The background of the column of the current day in the week view.
+    pub today: u32,
+    /// The background of the columns of Saturday and Sunday in the week view.  They're not
+    /// shaded unless it's given.
+    pub weekend: Option<u32>,
+}
+
+pub struct Config {
+    /// The content of the font file.
+    pub font: Cow<'static, [u8]>,
+    pub title_font_size: f32,
+    pub ui_font_size: f32,
+    pub colors: Colors,
+    /// The color of the events whose calendar has no color.
+    pub default_calendar_color: calendar::Color,
+    /// The change of the height of the grid upon a zoom.
+    pub grid_scale_step: f32,
+    /// The scroll of the grid upon the arrow keys.
+    pub grid_offset_step: f32,
+    /// From where the events are drawn.  The hours are on the left, the dates are above.
+    pub event_offset: FPoint,
+    /// The offset of the title inside of the rectangle of an event.
+    pub event_title_offset: FPoint,
+    /// The day on which the week starts.
+    pub first_weekday: Weekday,
+    /// The number of the days shown at once.  The ranges shorter than five days start on the
+    /// current date, the rest start on `first_weekday`.
+    pub day_count: u16,
+    /// The path of the program Khal.  The environment variable `SEMANA_BACKEND_BIN` takes
+    /// precedence.
+    pub backend_bin: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            font: Cow::Borrowed(FONT_CONTENT),
+            title_font_size: 16.,
+            ui_font_size: 22.,
+            colors: Colors {
+                background: 0x0C0D0C,
+                text_highlight: 0x009900,
+                text_field_background: 0x333333,
+                banner: 0x8B1A1A,
+                today: 0x1C2A1C,
+                weekend: Some(0x060606),
+            },
+            default_calendar_color: calendar::Color(0xffffffff),
+            grid_scale_step: 50.,
+            grid_offset_step: 50.,
+            event_offset: FPoint { x: 100., y: 70. },
+            event_title_offset: FPoint { x: 2., y: 4. },
+            first_weekday: Weekday::Monday,
+            day_count: 7,
+            backend_bin: String::from("khal"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The file exists, but it can't be read.
+    FileIsNotRead(std::io::Error),
+    /// The line is neither an option nor a comment.  The value is the number of the line.
+    LineIsNotOption(usize),
+    /// The values are the number of the line and the option.
+    OptionIsUnknown(usize, String),
+    /// The values are the number of the line, the option and the description of the expected
+    /// value.
+    ValueIsNotValid(usize, String, &'static str),
+    FontIsNotRead(PathBuf, std::io::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::FileIsNotRead(e) => write!(f, "the file can't be read: {e}"),
+            Error::LineIsNotOption(line) => {
+                write!(f, "line {line}: expected `key = value` or a comment")
+            }
+            Error::OptionIsUnknown(line, key) => write!(f, "line {line}: unknown option `{key}`"),
+            Error::ValueIsNotValid(line, key, expected) => {
+                write!(f, "line {line}: the option `{key}` takes {expected}")
+            }
+            Error::FontIsNotRead(path, e) => {
+                write!(f, "the font {} can't be read: {e}", path.display())
+            }
+        }
+    }
+}
+
+impl Config {
+    /// The path of the configuration file.
+    pub fn path_from_env() -> Option<PathBuf> {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|base| base.join("semana").join("config"))
+    }
+
+    /// Reads the configuration file.  The default configuration is taken if the file doesn't
+    /// exist.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::FileIsNotRead(e)),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let mut ret = Self::default();
+        for (index, line) in content.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or(Error::LineIsNotOption(number))?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = |expected| Error::ValueIsNotValid(number, key.to_owned(), expected);
+            let positive = || parse_number(value, 0.).ok_or_else(|| invalid("a positive number"));
+            let non_negative =
+                || parse_number(value, -f32::MIN_POSITIVE).ok_or_else(|| invalid("a number ≥ 0"));
+            let rgb = || parse_rgb(value).ok_or_else(|| invalid("a color like #rrggbb"));
+            match key {
+                "font" => {
+                    let path = PathBuf::from(value);
+                    let content =
+                        std::fs::read(&path).map_err(|e| Error::FontIsNotRead(path, e))?;
+                    ret.font = Cow::Owned(content);
+                }
+                "title_font_size" => ret.title_font_size = positive()?,
+                "ui_font_size" => ret.ui_font_size = positive()?,
+                "background_color" => ret.colors.background = rgb()?,
+                "text_highlight_color" => ret.colors.text_highlight = rgb()?,
+                "text_field_color" => ret.colors.text_field_background = rgb()?,
+                "banner_color" => ret.colors.banner = rgb()?,
+                "today_color" => ret.colors.today = rgb()?,
+                "weekend_color" if value == "none" => ret.colors.weekend = None,
+                "weekend_color" => ret.colors.weekend = Some(rgb()?),
+                "default_calendar_color" => {
+                    ret.default_calendar_color = calendar::Color::from_hex(value)
+                        .ok_or_else(|| invalid("a color like #rrggbb or #rrggbbaa"))?;
+                }
+                "grid_scale_step" => ret.grid_scale_step = positive()?,
+                "grid_offset_step" => ret.grid_offset_step = positive()?,
+                "event_offset_x" => ret.event_offset.x = non_negative()?,
+                "event_offset_y" => ret.event_offset.y = non_negative()?,
+                "event_title_offset_x" => ret.event_title_offset.x = non_negative()?,
+                "event_title_offset_y" => ret.event_title_offset.y = non_negative()?,
+                "first_weekday" => {
+                    ret.first_weekday = Weekday::from_name(value)
+                        .ok_or_else(|| invalid("a weekday like monday or sunday"))?;
+                }
+                "day_count" => {
+                    ret.day_count = value
+                        .parse::<u16>()
+                        .ok()
+                        .filter(|x| (1..=MAX_DAY_COUNT).contains(x))
+                        .ok_or_else(|| invalid("a number from 1 to 28"))?;
+                }
+                "backend_bin" if !value.is_empty() => ret.backend_bin = value.to_owned(),
+                "backend_bin" => return Err(invalid("a path")),
+                _ => return Err(Error::OptionIsUnknown(number, key.to_owned())),
+            }
+        }
+
+        Ok(ret)
+    }
+}
+
+/// Parses the finite number which is bigger than `min`.
+fn parse_number(value: &str, min: f32) -> Option<f32> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|x| x.is_finite() && *x > min)
+}
+
+/// Parses the color like `#rrggbb` into `0xRRGGBB`.
+fn parse_rgb(value: &str) -> Option<u32> {
+    let digits = value.strip_prefix('#').filter(|digits| digits.len() == 6)?;
+    u32::from_str_radix(digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            "# the colors\nbackground_color = #202020\nweekend_color = none\n\ngrid_scale_step=25\nevent_offset_x = 0\nfirst_weekday = Sunday\nday_count = 3\n",
+        );
+        let Ok(config) = config else {
+            panic!("the configuration must be parsed");
+        };
+
+        assert_eq!(config.colors.background, 0x202020);
+        assert_eq!(config.colors.banner, 0x8B1A1A);
+        assert_eq!(config.colors.today, 0x1C2A1C);
+        assert_eq!(config.colors.weekend, None);
+        assert_eq!(config.grid_scale_step, 25.);
+        assert_eq!(config.event_offset.x, 0.);
+        assert_eq!(config.first_weekday, Weekday::Sunday);
+        assert_eq!(config.day_count, 3);
+        assert_eq!(config.backend_bin, "khal");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let parse = |content| Config::parse(content).err();
+        assert!(matches!(parse("zoom"), Some(Error::LineIsNotOption(1))));
+        assert!(matches!(
+            parse("\nfont_color = #ffffff"),
+            Some(Error::OptionIsUnknown(2, key)) if key == "font_color"
+        ));
+        assert!(matches!(
+            parse("background_color = red"),
+            Some(Error::ValueIsNotValid(1, key, _)) if key == "background_color"
+        ));
+        assert!(matches!(
+            parse("weekend_color = #fff"),
+            Some(Error::ValueIsNotValid(1, key, _)) if key == "weekend_color"
+        ));
+        assert!(matches!(
+            parse("grid_scale_step = -5"),
+            Some(Error::ValueIsNotValid(1, _, _))
+        ));
+        assert!(matches!(
+            parse("grid_offset_step = inf"),
+            Some(Error::ValueIsNotValid(1, _, _))
+        ));
+        assert!(matches!(
+            parse("day_count = 0"),
+            Some(Error::ValueIsNotValid(1, _, _))
+        ));
+    }
+}
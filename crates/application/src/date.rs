@@ -1,43 +1,36 @@
 use super::{TimeError, sdl};
-use calendar::date::Date;
+use calendar::date::{Date, Time, Weekday};
 
-pub fn get_week_start(now: sdl::SDL_Time) -> Result<Date, TimeError> {
+/// The current date in the zone of the machine.
+pub fn get_today(now: sdl::SDL_Time) -> Result<Date, TimeError> {
+    get_now(now).map(|(today, _)| today)
+}
+
+/// The current date and time in the zone of the machine.
+pub fn get_now(now: sdl::SDL_Time) -> Result<(Date, Time), TimeError> {
     let local_time = true;
-    unsafe {
-        let today = sdlext::time_to_date_time(now, local_time)?;
-        //let sunday: std::ffi::c_int = 0;
-        // from 0 to 6
-        let current_weekday: std::ffi::c_int =
-            sdl::SDL_GetDayOfWeek(today.year, today.month, today.day);
-        //let mut week_offset: std::ffi::c_int = current_weekday - sunday;
+    let now = sdlext::time_to_date_time(now, local_time)?;
+    let today = Date {
+        year: now.year as u16,
+        month: now.month as u8,
+        day: now.day as u8,
+    };
+    let time = Time {
+        hour: now.hour as u8,
+        minute: now.minute as u8,
+    };
+    Ok((today, time))
+}
 
-        let natural_weekday: i32 = match current_weekday {
-            0 => 7,
-            d @ 1..7 => d,
-            _ => return Err(TimeError::WeekNumberIsNotComputed),
-        };
-        // from 1 to 7
-        //let natural_weekday: i32 = current_weekday + 7 * ((7 - current_weekday) / 7);
-        // TODO: implement for the case Sunday is the first day.
-        // monday => 0
-        // ...
-        // sunday => -6
-        let week_offset_days: i32 = -(natural_weekday - 1);
-        const NANOSECONDS_PER_DAY: i64 = (sdl::SDL_NS_PER_SECOND as i64) * 60 * 60 * 24;
-        let offset_ns: i64 = (week_offset_days as i64) * NANOSECONDS_PER_DAY;
-        let mut first_day_of_week: sdl::SDL_DateTime = std::mem::zeroed();
-        if !sdl::SDL_TimeToDateTime(
-            now + offset_ns,
-            &mut first_day_of_week as *mut _,
-            local_time,
-        ) {
-            return Err(TimeError::FailConvertingNowToDate);
-        }
+/// The first day of the current week in the zone of the machine.  The week starts on
+/// `first_weekday`.
+pub fn get_week_start(now: sdl::SDL_Time, first_weekday: Weekday) -> Result<Date, TimeError> {
+    get_today(now).map(|today| today.week_start(first_weekday))
+}
 
-        Ok(Date {
-            year: first_day_of_week.year as u16,
-            month: first_day_of_week.month as u8,
-            day: first_day_of_week.day as u8,
-        })
-    }
+/// The time left until the next minute of the clock.
+pub fn until_next_minute(now: sdl::SDL_Time) -> std::time::Duration {
+    const NANOSECONDS_PER_MINUTE: i64 = 60_000_000_000;
+    let left = NANOSECONDS_PER_MINUTE - now.rem_euclid(NANOSECONDS_PER_MINUTE);
+    std::time::Duration::from_nanos(left as u64)
 }
@@ -9,19 +9,39 @@ pub enum Error {
     Sdl(sdlext::Error),
     Calendar(CalendarError),
     DataIsNotAvailable(AgendaObtainError),
+    Io(std::io::Error),
+    AgendaIsNotUpdated(i32),
+    AgendaSourceExited(i32, String),
+    EventIsNotUpdated(calendar::obtain::ics::Error),
+    CalDavIsNotAvailable(crate::backend::caldav::Error),
+    WorkIsInterrupted,
 }
 
 impl From<FrontendError> for Error {
     fn from(value: FrontendError) -> Self {
         match value {
-            FrontendError::WeekStartIsNotObtained(e) => Error::from(sdlext::Error::from(e)),
+            FrontendError::WeekStartIsNotObtained(e)
+            | FrontendError::TodayIsNotObtained(e)
+            | FrontendError::TimeIsNotObtained(e) => Error::from(sdlext::Error::from(e)),
             FrontendError::CStringIsNotCreated(_nul_error) => todo!("handle zeroes in UTF-8"),
             FrontendError::TextObjectIsNotRegistered(e)
             | FrontendError::CantGetHeightOfText(e)
             | FrontendError::HighlightSelectionIsNotCalculated(e)
-            | FrontendError::ClipboardIsBroken(e) => Error::from(e),
+            | FrontendError::ClipboardIsBroken(e)
+            | FrontendError::DialogIsBroken(e) => Error::from(e),
             FrontendError::AgendaSourceFailed(e) => Error::from(e),
+            FrontendError::ThreadIsNotSpawned(e) | FrontendError::FileIsNotWritten(e) => {
+                Error::Io(e)
+            }
+            FrontendError::AgendaIsNotUpdated(exit_code) => Error::AgendaIsNotUpdated(exit_code),
+            FrontendError::AgendaSourceExited(exit_code, stderr) => {
+                Error::AgendaSourceExited(exit_code, stderr)
+            }
+            FrontendError::AgendaIsNotObtained(e) => Error::from(e),
+            FrontendError::EventIsNotUpdated(e) => Error::EventIsNotUpdated(e),
+            FrontendError::CalDavIsNotAvailable(e) => Error::CalDavIsNotAvailable(e),
             FrontendError::TextObjectNotFound => todo!("handle the error of absent text object"),
+            FrontendError::WorkIsInterrupted => Error::WorkIsInterrupted,
         }
     }
 }
@@ -47,7 +67,24 @@ impl From<AgendaObtainError> for Error {
 pub enum FrontendError {
     // FIXME(alex): figure out how to store the text of the SDL errors.
     AgendaSourceFailed(sdlext::Error),
+    /// The program which provides the agenda has failed.  The values are its exit code and its
+    /// standard error.
+    AgendaSourceExited(i32, String),
+    /// The output of the agenda source can't be parsed.
+    AgendaIsNotObtained(AgendaObtainError),
+    ThreadIsNotSpawned(std::io::Error),
+    /// The thread has gone without the result of its work.  See [`crate::backend::Pending`].
+    WorkIsInterrupted,
+    /// The program which stores the changes of the agenda has failed.  The value is its exit code.
+    AgendaIsNotUpdated(i32),
+    FileIsNotWritten(std::io::Error),
+    /// The file of the event can't be changed.  E.g. the event is not found in it.
+    EventIsNotUpdated(calendar::obtain::ics::Error),
+    /// The CalDAV server can't be reached or it rejects the request.
+    CalDavIsNotAvailable(crate::backend::caldav::Error),
     WeekStartIsNotObtained(TimeError),
+    TodayIsNotObtained(TimeError),
+    TimeIsNotObtained(TimeError),
     CStringIsNotCreated(std::ffi::NulError),
     // FIXME(alex): this errors don't seem useful.  Sqaush them into something like PlatformIssue
     // which would wrap an SDL error.
@@ -55,9 +92,57 @@ pub enum FrontendError {
     CantGetHeightOfText(sdlext::Error),
     HighlightSelectionIsNotCalculated(sdlext::Error),
     ClipboardIsBroken(sdlext::Error),
+    DialogIsBroken(sdlext::Error),
     TextObjectNotFound,
 }
 
+/// The description of the error shown to the user.
+impl std::fmt::Display for FrontendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrontendError::AgendaSourceFailed(e) => write!(f, "the agenda source failed: {e:?}"),
+            FrontendError::AgendaSourceExited(exit_code, stderr) if stderr.is_empty() => {
+                write!(f, "the agenda source exited with the code {exit_code}")
+            }
+            FrontendError::AgendaSourceExited(exit_code, stderr) => {
+                write!(
+                    f,
+                    "the agenda source exited with the code {exit_code}: {stderr}"
+                )
+            }
+            FrontendError::AgendaIsNotObtained(e) => {
+                write!(f, "the agenda can't be parsed: {e:?}")
+            }
+            FrontendError::ThreadIsNotSpawned(e) => write!(f, "the thread can't be spawned: {e}"),
+            FrontendError::WorkIsInterrupted => write!(f, "the work is interrupted"),
+            FrontendError::AgendaIsNotUpdated(exit_code) => {
+                write!(f, "the agenda isn't updated, the exit code is {exit_code}")
+            }
+            FrontendError::FileIsNotWritten(e) => write!(f, "the file can't be written: {e}"),
+            FrontendError::EventIsNotUpdated(e) => write!(f, "the event isn't updated: {e:?}"),
+            FrontendError::CalDavIsNotAvailable(e) => {
+                write!(f, "the CalDAV server is not available: {e:?}")
+            }
+            FrontendError::WeekStartIsNotObtained(e) => {
+                write!(f, "the start of the week is not obtained: {e:?}")
+            }
+            FrontendError::TodayIsNotObtained(e) => {
+                write!(f, "the current date is not obtained: {e:?}")
+            }
+            FrontendError::TimeIsNotObtained(e) => {
+                write!(f, "the current time is not obtained: {e:?}")
+            }
+            FrontendError::CStringIsNotCreated(e) => write!(f, "the text is invalid: {e}"),
+            FrontendError::TextObjectIsNotRegistered(e)
+            | FrontendError::CantGetHeightOfText(e)
+            | FrontendError::HighlightSelectionIsNotCalculated(e)
+            | FrontendError::ClipboardIsBroken(e)
+            | FrontendError::DialogIsBroken(e) => write!(f, "SDL failed: {e:?}"),
+            FrontendError::TextObjectNotFound => write!(f, "the text object is not found"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct CalendarError {
     _data: String,
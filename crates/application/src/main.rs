@@ -1,7 +1,12 @@
+mod backend;
+mod cache;
+mod config;
 mod date;
 mod error;
 mod render;
 mod state;
+mod watcher;
+mod zoneinfo;
 
 use core::cell::RefCell;
 use core::mem::MaybeUninit;
@@ -14,7 +19,7 @@ use sdlext::Ptr;
 use sdlext::{Color, Font, TimeError, sdl_init, sdl_ttf_init};
 
 use crate::error::{Error, FrontendError};
-use crate::state::{AgendaSource, GetLongEventTextRegistry, GetShortEventTextRegistry};
+use crate::state::{GetLongEventTextRegistry, GetShortEventTextRegistry};
 use state::{App, Frontend};
 
 /// The registry with the textures of the text objects.
@@ -33,18 +38,6 @@ struct TextTextureRegistry<'renderer, 'font> {
     renderer: &'renderer sdlext::Renderer,
 }
 
-mod config {
-    pub const EVENT_TITLE_OFFSET_X: f32 = 2.0;
-    pub const EVENT_TITLE_OFFSET_Y: f32 = 4.0;
-    pub const FONT_CONTENT: &[u8] = include_bytes!("../../../assets/DejaVuSansMonoBook.ttf");
-    pub const COLOR_BACKGROUND: u32 = 0x0C0D0C;
-    pub const COLOR_TEXT_HIGHLIGHT: u32 = 0x009900;
-    pub const GRID_SCALE_STEP: f32 = 50.;
-    pub const GRID_OFFSET_STEP: f32 = 50.;
-    pub const DEFAULT_CALENDAR_COLOR: calendar::Color = calendar::Color(0xffffffff);
-    pub const TEXT_FIELD_BACKGROUND: u32 = 0x333333;
-}
-
 impl<'renderer, 'font> TextTextureRegistry<'renderer, 'font> {
     fn new(renderer: &'renderer sdlext::Renderer, font: &'font RefCell<sdlext::Font>) -> Self {
         Self {
@@ -105,11 +98,17 @@ impl Fonts {
         })
     }
 
-    fn from_bytes(title_font_buffer: &[u8], ui_font_buffer: &[u8]) -> Result<Self, sdlext::Error> {
+    fn from_bytes(
+        title_font_buffer: &[u8],
+        title_font_size: f32,
+        ui_font_buffer: &[u8],
+        ui_font_size: f32,
+    ) -> Result<Self, sdlext::Error> {
         let title_font: RefCell<Font> =
-            Font::from_buffer(title_font_buffer, 16.0).map(RefCell::new)?;
+            Font::from_buffer(title_font_buffer, title_font_size).map(RefCell::new)?;
 
-        let ui_font: RefCell<Font> = Font::from_buffer(ui_font_buffer, 22.0).map(RefCell::new)?;
+        let ui_font: RefCell<Font> =
+            Font::from_buffer(ui_font_buffer, ui_font_size).map(RefCell::new)?;
         Ok(Self {
             title: title_font,
             ui: ui_font,
@@ -291,6 +290,27 @@ impl<'font> state::TextObjectRegistry for TextObjectRegistry<'font> {
         }
     }
 
+    fn set_text(&mut self, index: u32, text: impl Into<Vec<u8>>) -> Result<(), Self::Error> {
+        let Some(text_object) = self.text_objects.get(index as usize) else {
+            return Err(FrontendError::TextObjectNotFound);
+        };
+
+        let cstring = std::ffi::CString::new(text).map_err(FrontendError::CStringIsNotCreated)?;
+        // FIXME(alex): make a wrapper for it.
+        unsafe {
+            if !sdl_ttf::TTF_SetTextString(
+                text_object.ptr(),
+                cstring.as_ptr(),
+                cstring.count_bytes(),
+            ) {
+                return Err(FrontendError::TextObjectIsNotRegistered(
+                    sdlext::Error::TtfError(sdlext::TtfError::TextIsNotCreated),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn create(
         &mut self,
         text: impl Into<Vec<u8>>,
@@ -310,11 +330,21 @@ struct DumbFrontend<'renderer, 'font> {
     hour_text_texture_regirsty: TextTextureRegistry<'renderer, 'font>,
     days_text_texture_regirsty: TextTextureRegistry<'renderer, 'font>,
     dates_text_texture_regirsty: TextTextureRegistry<'renderer, 'font>,
+    banner_text_registry: TextTextureRegistry<'renderer, 'font>,
+    month_text_registry: TextTextureRegistry<'renderer, 'font>,
+    agenda_text_registry: TextTextureRegistry<'renderer, 'font>,
+    year_text_registry: TextTextureRegistry<'renderer, 'font>,
+    search_text_registry: TextTextureRegistry<'renderer, 'font>,
+    prompt_text_registry: TextTextureRegistry<'renderer, 'font>,
 
     long_event_text_registry: TextTextureRegistry<'renderer, 'font>,
     short_event_text_registry: TextTextureRegistry<'renderer, 'font>,
     event_details_text_object_regirsty: RefCell<TextObjectRegistry<'font>>,
     event_details_field_label_regirsty: RefCell<TextTextureRegistry<'renderer, 'font>>,
+    agenda_source: backend::Backend,
+    week_cache: cache::WeekCache,
+    colors: config::Colors,
+    first_weekday: calendar::date::Weekday,
 }
 
 impl<'renderer, 'font> GetLongEventTextRegistry for DumbFrontend<'renderer, 'font> {
@@ -458,7 +488,9 @@ impl<'renderer, 'font> Frontend for DumbFrontend<'renderer, 'font> {
     type TextObject = sdlext::Text;
     type Error = FrontendError;
     type TextTextureRegistry = TextTextureRegistry<'renderer, 'font>;
-    type AgendaSource = KhalAgendaSource;
+    type AgendaSource = backend::Backend;
+    type AgendaWriter = backend::Backend;
+    type WeekCache = cache::WeekCache;
     type TextObjectRegistry = TextObjectRegistry<'font>;
     type TextEngine = TextEngine;
 
@@ -474,12 +506,48 @@ impl<'renderer, 'font> Frontend for DumbFrontend<'renderer, 'font> {
         &mut self.dates_text_texture_regirsty
     }
 
+    fn get_banner_text_registry(&mut self) -> &mut Self::TextTextureRegistry {
+        &mut self.banner_text_registry
+    }
+
+    fn get_month_text_registry(&mut self) -> &mut Self::TextTextureRegistry {
+        &mut self.month_text_registry
+    }
+
+    fn get_agenda_text_registry(&mut self) -> &mut Self::TextTextureRegistry {
+        &mut self.agenda_text_registry
+    }
+
+    fn get_year_text_registry(&mut self) -> &mut Self::TextTextureRegistry {
+        &mut self.year_text_registry
+    }
+
+    fn get_search_text_registry(&mut self) -> &mut Self::TextTextureRegistry {
+        &mut self.search_text_registry
+    }
+
+    fn get_prompt_text_registry(&mut self) -> &mut Self::TextTextureRegistry {
+        &mut self.prompt_text_registry
+    }
+
     fn get_current_week_start(&self) -> Result<calendar::date::Date, Self::Error> {
         sdlext::get_current_time()
-            .and_then(date::get_week_start)
+            .and_then(|now| date::get_week_start(now, self.first_weekday))
             .map_err(FrontendError::WeekStartIsNotObtained)
     }
 
+    fn get_today(&self) -> Result<calendar::date::Date, Self::Error> {
+        sdlext::get_current_time()
+            .and_then(date::get_today)
+            .map_err(FrontendError::TodayIsNotObtained)
+    }
+
+    fn get_now(&self) -> Result<(calendar::date::Date, calendar::date::Time), Self::Error> {
+        sdlext::get_current_time()
+            .and_then(date::get_now)
+            .map_err(FrontendError::TimeIsNotObtained)
+    }
+
     fn get_event_details_text_object_regirsty(&self) -> &RefCell<Self::TextObjectRegistry> {
         &self.event_details_text_object_regirsty
     }
@@ -493,7 +561,15 @@ impl<'renderer, 'font> Frontend for DumbFrontend<'renderer, 'font> {
     }
 
     fn agenda_source(&self) -> &Self::AgendaSource {
-        &KhalAgendaSource
+        &self.agenda_source
+    }
+
+    fn agenda_writer(&self) -> &Self::AgendaWriter {
+        &self.agenda_source
+    }
+
+    fn week_cache(&self) -> &Self::WeekCache {
+        &self.week_cache
     }
 
     fn set_clipboard(&self, text: impl Into<Vec<u8>>) -> Result<(), Self::Error> {
@@ -510,134 +586,53 @@ impl<'renderer, 'font> Frontend for DumbFrontend<'renderer, 'font> {
         }
     }
 
-    fn get_event_details_field_label_regirsty(&self) -> &RefCell<Self::TextTextureRegistry> {
-        &self.event_details_field_label_regirsty
-    }
-}
-
-/// It provides the data from the program Khal.  It provides the data according the trait
-/// AgendaSource.
-struct KhalAgendaSource;
-
-impl AgendaSource for KhalAgendaSource {
-    type RequestHandle = *mut sdl::SDL_Process;
-
-    type Error = FrontendError;
-
-    fn request(
-        &self,
-        week_start: &calendar::date::Date,
-    ) -> Result<Self::RequestHandle, Self::Error> {
-        let mut arguments = calendar::obtain::khal::week_arguments(week_start);
-        let bin: Result<String, _> = std::env::var("SEMANA_BACKEND_BIN");
-        if let Ok(ref v) = bin {
-            arguments.backend_bin_path = v.as_ref();
-        }
+    fn confirm(&self, message: &str) -> Result<bool, Self::Error> {
+        const YES: std::ffi::c_int = 1;
+        const NO: std::ffi::c_int = 0;
+        let message =
+            std::ffi::CString::new(message).map_err(FrontendError::CStringIsNotCreated)?;
+        let buttons = [
+            sdl::SDL_MessageBoxButtonData {
+                flags: sdl::SDL_MESSAGEBOX_BUTTON_RETURNKEY_DEFAULT,
+                buttonID: YES,
+                text: c"Yes".as_ptr(),
+            },
+            sdl::SDL_MessageBoxButtonData {
+                flags: sdl::SDL_MESSAGEBOX_BUTTON_ESCAPEKEY_DEFAULT,
+                buttonID: NO,
+                text: c"No".as_ptr(),
+            },
+        ];
+
+        let data = sdl::SDL_MessageBoxData {
+            flags: sdl::SDL_MESSAGEBOX_WARNING,
+            window: self.text_engine.window,
+            title: c"Semana".as_ptr(),
+            message: message.as_ptr(),
+            numbuttons: buttons.len() as std::ffi::c_int,
+            buttons: buttons.as_ptr(),
+            colorScheme: std::ptr::null(),
+        };
 
-        let from = arguments.from.iso_8601();
+        // The button is -1 if the user closes the dialog.
+        let mut button = NO;
         unsafe {
-            let args: [&str; _] = [
-                arguments.backend_bin_path,
-                "list",
-                "--json",
-                "title",
-                "--json",
-                "start-date",
-                "--json",
-                "start-time",
-                "--json",
-                "end-date",
-                "--json",
-                "end-time",
-                "--json",
-                "all-day",
-                "--json",
-                "calendar-color",
-                "--json",
-                "description",
-                "--json",
-                "url",
-                "--json",
-                "location",
-                "--json",
-                "calendar",
-                from.as_str(),
-                &format!("{}d", arguments.duration_days),
-            ];
-            use std::ffi::CString;
-            let args_cstrings: Vec<CString> =
-                args.iter().map(|s| CString::new(*s).unwrap()).collect();
-            let mut args_ptrs: Vec<*const std::ffi::c_char> =
-                args_cstrings.iter().map(|cs| cs.as_ptr()).collect();
-            args_ptrs.push(std::ptr::null());
-
-            let vec_ptr: *const *const std::ffi::c_char = args_ptrs.as_ptr();
-            let args_ptr: *const *const i8 = vec_ptr.cast();
-            let ret: *mut sdl::SDL_Process = sdl::SDL_CreateProcess(args_ptr.cast(), true);
-            if ret.is_null() {
-                Err(FrontendError::AgendaSourceFailed(
-                    sdlext::Error::ProcessIsNotCreated,
-                ))
-            } else {
-                Ok(ret)
+            if !sdl::SDL_ShowMessageBox(&data, &mut button) {
+                return Err(FrontendError::DialogIsBroken(
+                    sdlext::Error::MessageBoxIsNotShown,
+                ));
             }
         }
-    }
-
-    fn cancel(&self, handle: &Self::RequestHandle) {
-        unsafe {
-            let force: bool = true;
-            sdl::SDL_KillProcess(*handle, force);
-        }
-    }
-
-    fn free(&self, handle: Self::RequestHandle) {
-        unsafe {
-            sdl::SDL_DestroyProcess(handle);
-        }
-    }
 
-    // FIXME(alex): it's potentially a bomb.  The documentation of SDL_WaitProcess says that all of
-    // the output has to be read before calling the function.  Otherwise it can never return true.
-    // Currently, it works but it's luck I guess.
-    fn is_ready(&self, handle: &Self::RequestHandle) -> bool {
-        let block = false;
-        let mut exit_code = 0;
-        unsafe { sdl::SDL_WaitProcess(*handle, block, &mut exit_code) }
+        Ok(button == YES)
     }
 
-    fn fetch(
-        &self,
-        handle: &Self::RequestHandle,
-        week_start: &calendar::date::Date,
-    ) -> calendar::obtain::WeekScheduleWithLanes {
-        let mut exit_code = 0;
-        let mut size = 0;
-        unsafe {
-            let ret: *const std::ffi::c_void =
-                sdl::SDL_ReadProcess(*handle, &mut size, &mut exit_code);
-            let byte_ptr: *const i8 = ret.cast();
-            let output_cstr = std::ffi::CStr::from_ptr(byte_ptr);
-            let output_str: &str = output_cstr.to_str().expect("can't convert to utf-8");
-            calendar::obtain::parse_events(
-                &calendar::obtain::NanoSerde,
-                output_str,
-                week_start,
-                config::DEFAULT_CALENDAR_COLOR,
-            )
-            .map(|events| calendar::obtain::get_lanes(events, week_start))
-            // FIXME(alex): this panics if the process provides unsupported input
-            .expect("fail to parse events")
-        }
+    fn get_event_details_field_label_regirsty(&self) -> &RefCell<Self::TextTextureRegistry> {
+        &self.event_details_field_label_regirsty
     }
 }
 
-fn unsafe_main() {
-    let event_offset = sdl::SDL_FPoint {
-        x: 100f32,
-        y: 70f32,
-    };
-
+fn unsafe_main(config: config::Config) {
     unsafe {
         let ret: Result<(), Error> = sdl_init(
             move |root_window: *mut sdl::SDL_Window, renderer: &sdlext::Renderer| {
@@ -647,7 +642,12 @@ fn unsafe_main() {
                 sdl_ttf_init(
                     renderer,
                     move |engine: NonNull<sdl_ttf::TTF_TextEngine>| -> Result<(), Error> {
-                        let fonts = Fonts::from_bytes(config::FONT_CONTENT, config::FONT_CONTENT)?;
+                        let fonts = Fonts::from_bytes(
+                            &config.font,
+                            config.title_font_size,
+                            &config.font,
+                            config.ui_font_size,
+                        )?;
                         let title_font_height: std::ffi::c_int =
                             sdl_ttf::TTF_GetFontHeight(fonts.title.borrow_mut().ptr());
 
@@ -666,6 +666,18 @@ fn unsafe_main() {
                         // dates (2025-12-16, 2025-12-17 etc)
                         let dates_text_texture_regirsty =
                             TextTextureRegistry::new(renderer, &fonts.ui);
+                        // the failure of the agenda source
+                        let banner_text_registry = TextTextureRegistry::new(renderer, &fonts.ui);
+                        // the month view (the dates, the titles of the events etc.)
+                        let month_text_registry = TextTextureRegistry::new(renderer, &fonts.title);
+                        // the agenda view (the dates and the lines of the events)
+                        let agenda_text_registry = TextTextureRegistry::new(renderer, &fonts.title);
+                        // the year view (the names of the months and the days)
+                        let year_text_registry = TextTextureRegistry::new(renderer, &fonts.title);
+                        // the search view (the prompt and the matches)
+                        let search_text_registry = TextTextureRegistry::new(renderer, &fonts.title);
+                        // the prompt bar of the week view (the date to go to and the filter)
+                        let prompt_text_registry = TextTextureRegistry::new(renderer, &fonts.title);
                         let event_details_text_object_regirsty =
                             RefCell::new(TextObjectRegistry::new(&fonts.ui, engine));
                         let text_engine = TextEngine {
@@ -680,29 +692,61 @@ fn unsafe_main() {
                             hour_text_texture_regirsty,
                             days_text_texture_regirsty,
                             dates_text_texture_regirsty,
+                            banner_text_registry,
+                            month_text_registry,
+                            agenda_text_registry,
+                            year_text_registry,
+                            search_text_registry,
+                            prompt_text_registry,
                             short_event_text_registry,
                             long_event_text_registry,
                             event_details_text_object_regirsty,
                             text_engine,
                             event_details_field_label_regirsty,
-                        };
-
-                        let event_title_offset = sdl::SDL_FPoint {
-                            x: config::EVENT_TITLE_OFFSET_X,
-                            y: config::EVENT_TITLE_OFFSET_Y,
+                            agenda_source: backend::Backend::from_env(&config)?,
+                            week_cache: cache::WeekCache::from_env(config.day_count),
+                            colors: config.colors,
+                            first_weekday: config.first_weekday,
                         };
 
                         let mut app = App::new(
                             &mut frontend,
                             title_font_height,
-                            event_offset,
+                            config.event_offset,
                             mouse,
-                            event_title_offset,
+                            config.event_title_offset,
+                            config.day_count,
                         )?;
 
+                        // The text input is enabled only while a text field takes it.  SDL shows
+                        // the on-screen keyboard on some platforms while it's enabled.
+                        _ = sdl::SDL_StopTextInput(root_window);
+                        let mut is_text_input_started = false;
+
+                        // The agenda is still shown without the watcher.  Its changes are shown
+                        // once the week is switched.
+                        let mut watcher =
+                            watcher::Watcher::new(frontend.agenda_source.watched_directories())
+                                .ok();
+
+                        // The "now" line of the week view moves once a minute.  The ticks follow
+                        // the minutes of the clock.
+                        let mut clock_tick = std::time::Instant::now();
+
                         let mut activity: state::Activity = app.get_root_activity();
                         let mut event: sdl::SDL_Event = std::mem::zeroed();
                         'outer_loop: loop {
+                            // The activity of the previous frame takes the text typed in this one.
+                            let takes_text_input = app.takes_text_input(&activity);
+                            if takes_text_input != is_text_input_started {
+                                is_text_input_started = takes_text_input;
+                                _ = if takes_text_input {
+                                    sdl::SDL_StartTextInput(root_window)
+                                } else {
+                                    sdl::SDL_StopTextInput(root_window)
+                                };
+                            }
+
                             let mut events: Vec<state::Action> = Vec::new();
                             // stage: event handle
                             while sdl::SDL_PollEvent(&mut event as _) {
@@ -726,6 +770,39 @@ fn unsafe_main() {
                                         {
                                             events.push(state::Action::Yank);
                                         }
+                                        sdl::SDLK_S
+                                            if (event.key.mod_ as u32 & sdl::SDL_KMOD_CTRL) > 0 =>
+                                        {
+                                            events.push(state::Action::Save);
+                                        }
+                                        sdl::SDLK_F
+                                            if (event.key.mod_ as u32 & sdl::SDL_KMOD_CTRL) > 0 =>
+                                        {
+                                            events.push(state::Action::Search);
+                                        }
+                                        sdl::SDLK_RETURN | sdl::SDLK_KP_ENTER => {
+                                            events.push(state::Action::Submit)
+                                        }
+                                        sdl::SDLK_BACKSPACE => {
+                                            events.push(state::Action::DeleteBackward)
+                                        }
+                                        sdl::SDLK_DELETE
+                                            if (event.key.mod_ as u32 & sdl::SDL_KMOD_CTRL) > 0 =>
+                                        {
+                                            events.push(state::Action::DeleteEvent)
+                                        }
+                                        sdl::SDLK_D
+                                            if (event.key.mod_ as u32 & sdl::SDL_KMOD_CTRL) > 0 =>
+                                        {
+                                            events.push(state::Action::DuplicateEvent)
+                                        }
+                                        sdl::SDLK_DELETE => {
+                                            events.push(state::Action::DeleteForward)
+                                        }
+                                        sdl::SDLK_LEFT => events.push(state::Action::CursorLeft),
+                                        sdl::SDLK_RIGHT => events.push(state::Action::CursorRight),
+                                        sdl::SDLK_HOME => events.push(state::Action::CursorHome),
+                                        sdl::SDLK_END => events.push(state::Action::CursorEnd),
                                         sdl::SDLK_TAB => {
                                             let cond =
                                                 (event.key.mod_ as u32 & sdl::SDL_KMOD_SHIFT) > 0;
@@ -739,7 +816,7 @@ fn unsafe_main() {
                                         }
                                         sdl::SDLK_UP => {
                                             events.push(state::Action::Scroll {
-                                                offset: -config::GRID_OFFSET_STEP,
+                                                offset: -config.grid_offset_step,
                                                 // FIXME(alex): the event should be redesigned
                                                 x: 0f32,
                                                 y: 0f32,
@@ -748,31 +825,72 @@ fn unsafe_main() {
                                         sdl::SDLK_DOWN => {
                                             events.push(state::Action::Scroll {
                                                 // FIXME(alex): the event should be redesigned
-                                                offset: config::GRID_OFFSET_STEP,
+                                                offset: config.grid_offset_step,
                                                 x: 0f32,
                                                 y: 0f32,
                                             });
                                         }
                                         sdl::SDLK_MINUS => {
-                                            events.push(state::Action::Zoom(
-                                                -config::GRID_SCALE_STEP,
-                                            ));
+                                            events
+                                                .push(state::Action::Zoom(-config.grid_scale_step));
                                         }
                                         sdl::SDLK_EQUALS => {
                                             events
-                                                .push(state::Action::Zoom(config::GRID_SCALE_STEP));
+                                                .push(state::Action::Zoom(config.grid_scale_step));
                                             app.calendar.request_render();
                                         }
                                         _ => (),
                                     },
-                                    sdl::SDL_EVENT_KEY_UP => match event.key.key {
-                                        sdl::SDLK_ESCAPE => events.push(state::Action::Escape),
-                                        sdl::SDLK_PAGEUP => {
-                                            events.push(state::Action::SubtractWeek)
+                                    sdl::SDL_EVENT_TEXT_INPUT => {
+                                        let text = std::ffi::CStr::from_ptr(event.text.text);
+                                        events.push(state::Action::TextInput(
+                                            text.to_string_lossy().into_owned(),
+                                        ));
+                                    }
+                                    sdl::SDL_EVENT_KEY_UP => {
+                                        // The letters are the commands only while Ctrl, Alt and
+                                        // Gui are released.  E.g. Ctrl+A selects all.
+                                        let modifiers = sdl::SDL_KMOD_CTRL
+                                            | sdl::SDL_KMOD_ALT
+                                            | sdl::SDL_KMOD_GUI;
+                                        let is_plain = (event.key.mod_ as u32 & modifiers) == 0;
+                                        match event.key.key {
+                                            sdl::SDLK_ESCAPE => events.push(state::Action::Escape),
+                                            sdl::SDLK_R if is_plain => {
+                                                events.push(state::Action::Retry)
+                                            }
+                                            sdl::SDLK_M if is_plain => {
+                                                events.push(state::Action::ToggleMonthView)
+                                            }
+                                            sdl::SDLK_A if is_plain => {
+                                                events.push(state::Action::ToggleAgendaView)
+                                            }
+                                            sdl::SDLK_Y if is_plain => {
+                                                events.push(state::Action::ToggleYearView)
+                                            }
+                                            sdl::SDLK_SLASH if is_plain => {
+                                                events.push(state::Action::Filter)
+                                            }
+                                            sdl::SDLK_G if is_plain => {
+                                                events.push(state::Action::GoToDate)
+                                            }
+                                            sdl::SDLK_T if is_plain => {
+                                                events.push(state::Action::Today)
+                                            }
+                                            // Ctrl+D duplicates the event rather than opens
+                                            // the day.
+                                            sdl::SDLK_D if is_plain => {
+                                                events.push(state::Action::ToggleDayView)
+                                            }
+                                            sdl::SDLK_PAGEUP => {
+                                                events.push(state::Action::SubtractWeek)
+                                            }
+                                            sdl::SDLK_PAGEDOWN => {
+                                                events.push(state::Action::AddWeek)
+                                            }
+                                            _ => (),
                                         }
-                                        sdl::SDLK_PAGEDOWN => events.push(state::Action::AddWeek),
-                                        _ => (),
-                                    },
+                                    }
                                     sdl::SDL_EVENT_MOUSE_BUTTON_UP => {
                                         events.push(state::Action::MouseButtonUp {
                                             position: sdl::SDL_FPoint {
@@ -823,7 +941,9 @@ fn unsafe_main() {
                                     sdl::SDL_EVENT_MOUSE_WHEEL => {
                                         let mod_state: sdl::SDL_Keymod = sdl::SDL_GetModState();
                                         if mod_state as u32 & sdl::SDL_KMOD_CTRL > 0 {
-                                            events.push(state::Action::Zoom(event.wheel.y * 50.));
+                                            events.push(state::Action::Zoom(
+                                                event.wheel.y * config.grid_scale_step,
+                                            ));
                                         } else if mod_state as u32 & sdl::SDL_KMOD_SHIFT > 0 {
                                             events.push(state::Action::TextScroll {
                                                 offset: event.wheel.y,
@@ -842,6 +962,23 @@ fn unsafe_main() {
                                 }
                             }
 
+                            if watcher
+                                .as_mut()
+                                .is_some_and(|w| w.poll(std::time::Instant::now()))
+                            {
+                                events.push(state::Action::AgendaChanged);
+                            }
+
+                            let now = std::time::Instant::now();
+                            if now >= clock_tick {
+                                events.push(state::Action::ClockTick);
+                                clock_tick = now
+                                    + sdlext::get_current_time().map_or(
+                                        std::time::Duration::from_secs(60),
+                                        date::until_next_minute,
+                                    );
+                            }
+
                             let new_state = app.create_render_data(
                                 activity,
                                 &mut frontend,
@@ -911,7 +1048,15 @@ fn create_sdl_frect(from: &calendar::render::Rectangle) -> sdl::SDL_FRect {
 }
 
 fn main() {
-    unsafe_main();
+    let config = match config::Config::path_from_env() {
+        Some(path) => config::Config::load(&path).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", path.display());
+            std::process::exit(1);
+        }),
+        None => config::Config::default(),
+    };
+
+    unsafe_main(config);
 }
 
 #[cfg(test)]
@@ -935,12 +1080,16 @@ mod tests {
         unsafe {
             let mut now_time: sdl::SDL_Time = std::mem::zeroed();
             assert!(sdl::SDL_DateTimeToTime(&now_date, &mut now_time));
-            let res = date::get_week_start(now_time)
+            let res = date::get_week_start(now_time, calendar::date::Weekday::Monday)
                 .expect("getting the start of the week must not fail");
             let calendar::date::Date { year, month, day } = res;
             assert_eq!(2025, year);
             assert_eq!(10, month);
             assert_eq!(6, day);
+
+            let res = date::get_week_start(now_time, calendar::date::Weekday::Sunday)
+                .expect("getting the start of the week must not fail");
+            assert_eq!(res, calendar::date::Date::new::<2025, 10, 5>());
         }
     }
 }
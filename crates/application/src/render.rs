@@ -5,12 +5,16 @@ use sdl3_sys as sdl;
 use crate::DumbFrontend;
 use crate::RectangleRender;
 
-use super::config;
 use sdlext::Color;
 
+#[allow(clippy::enum_variant_names)]
 pub enum RenderData<'rect, 'frontend, F> {
     WeekView(WeekViewRenderData<'rect, 'frontend, F>),
     EventView(EventViewRenderData<'rect, 'frontend, F>),
+    MonthView(MonthViewRenderData<'rect, 'frontend, F>),
+    AgendaView(AgendaViewRenderData<'frontend, F>),
+    YearView(YearViewRenderData<'frontend, F>),
+    SearchView(SearchViewRenderData<'frontend, F>),
 }
 
 pub struct TextSelection<'rect> {
@@ -32,6 +36,9 @@ pub struct EventViewRenderData<'rect, 'frontend, F> {
     pub calendar_color_rectangle: sdl::SDL_FRect,
     pub calendar_color_border: Color,
     pub calendar_base_rectangle: sdl::SDL_FRect,
+    /// The banner telling about the failure to save the event.  Its text is kept by the
+    /// frontend.
+    pub banner: Option<sdl::SDL_FRect>,
 }
 
 type EventView<'renderer, 'rect, 'frontend, 'font> =
@@ -40,34 +47,214 @@ type EventView<'renderer, 'rect, 'frontend, 'font> =
 pub struct WeekViewRenderData<'rect, 'frontend, F> {
     pub event_viewport: sdl::SDL_Rect,
     pub view: View,
+    /// The area of the long events.  It cuts the events of the days which are out of the day
+    /// view.
+    pub long_event_viewport: sdl::SDL_Rect,
     pub long_event_rectangles: &'rect calendar::render::Rectangles,
     pub short_event_rectangles: &'rect calendar::render::Rectangles,
     pub hours_viewport: sdl::SDL_Rect,
     pub frontend: &'frontend F,
     pub dates_viewport: sdl::SDL_Rect,
+    /// The time being selected to create a new event.  The rectangle is relative to
+    /// `event_viewport`.
+    pub time_selection: Option<sdl::SDL_FRect>,
+    /// The banner telling about the failure of the agenda source.  Its text is kept by the
+    /// frontend.
+    pub banner: Option<sdl::SDL_FRect>,
+    /// The bar at the bottom of the window which tells either the date being typed to go to or the
+    /// query of the filter of the events.  Its text is kept by the frontend.
+    pub prompt_bar: Option<sdl::SDL_FRect>,
+    /// The line across the column of today at the current time.  The rectangle is relative to
+    /// `event_viewport`.
+    pub now_line: Option<sdl::SDL_FRect>,
+    /// The column of the current day along with its caption.  It's relative to the window.
+    pub today_column: Option<sdl::SDL_FRect>,
+    /// The columns of Saturday and Sunday.  They're relative to the window.
+    pub weekend_columns: Vec<sdl::SDL_FRect>,
+    pub mini_month: Option<MiniMonthRenderData>,
+}
+
+/// The compact month in the top-left corner of the week view.  A click on a day shows its week.
+pub struct MiniMonthRenderData {
+    /// The cells of the days of the month which are not shown by the week view.
+    pub days: Vec<sdl::SDL_FRect>,
+    /// The cells of the days shown by the week view.
+    pub shown_days: Vec<sdl::SDL_FRect>,
+    /// The cell of the current day if it belongs to the month.
+    pub today: Option<sdl::SDL_FRect>,
 }
 
 type WeekView<'renderer, 'rect, 'ttc, 'font> =
     WeekViewRenderData<'rect, 'ttc, DumbFrontend<'renderer, 'font>>;
 
+pub struct MonthViewRenderData<'rect, 'frontend, F> {
+    pub frontend: &'frontend F,
+    /// The cells of the days of the grid.
+    pub cells: Vec<sdl::SDL_FRect>,
+    /// The bars of the long events.
+    pub bars: &'rect [calendar::render::MonthEntry],
+    /// The lines of the short events.
+    pub lines: &'rect [calendar::render::MonthEntry],
+    /// The banner telling about the failure of the agenda source.  Its text is kept by the
+    /// frontend.
+    pub banner: Option<sdl::SDL_FRect>,
+}
+
+type MonthView<'renderer, 'rect, 'frontend, 'font> =
+    MonthViewRenderData<'rect, 'frontend, DumbFrontend<'renderer, 'font>>;
+
+pub struct AgendaViewRenderData<'frontend, F> {
+    pub frontend: &'frontend F,
+    /// The marks of the calendar colors of the events.
+    pub swatches: Vec<(Color, sdl::SDL_FRect)>,
+    /// The line of the event under the mouse cursor.
+    pub hovered_line: Option<sdl::SDL_FRect>,
+    /// The banner telling about the failure of the agenda source.  Its text is kept by the
+    /// frontend.
+    pub banner: Option<sdl::SDL_FRect>,
+}
+
+type AgendaView<'renderer, 'frontend, 'font> =
+    AgendaViewRenderData<'frontend, DumbFrontend<'renderer, 'font>>;
+
+pub struct YearViewRenderData<'frontend, F> {
+    pub frontend: &'frontend F,
+    /// The cells of the days of the year along with the share of the day taken by the events.
+    pub days: Vec<(f32, sdl::SDL_FRect)>,
+    /// The banner telling about the failure of the agenda source.  Its text is kept by the
+    /// frontend.
+    pub banner: Option<sdl::SDL_FRect>,
+}
+
+type YearView<'renderer, 'frontend, 'font> =
+    YearViewRenderData<'frontend, DumbFrontend<'renderer, 'font>>;
+
+pub struct SearchViewRenderData<'frontend, F> {
+    pub frontend: &'frontend F,
+    /// The line of the match which is opened by Enter.
+    pub selected_line: Option<sdl::SDL_FRect>,
+    /// The line of the match under the mouse cursor.
+    pub hovered_line: Option<sdl::SDL_FRect>,
+}
+
+type SearchView<'renderer, 'frontend, 'font> =
+    SearchViewRenderData<'frontend, DumbFrontend<'renderer, 'font>>;
+
 pub fn render(renderer: &sdlext::Renderer, data: &RenderData<DumbFrontend>) -> sdlext::Result<()> {
     match data {
         RenderData::WeekView(week_view_render_data) => {
             render_week_view(renderer, week_view_render_data)
         }
         RenderData::EventView(v) => render_event_view(renderer, v),
+        RenderData::MonthView(v) => render_month_view(renderer, v),
+        RenderData::AgendaView(v) => render_agenda_view(renderer, v),
+        RenderData::YearView(v) => render_year_view(renderer, v),
+        RenderData::SearchView(v) => render_search_view(renderer, v),
     }
 }
 
+/// The color between `from` and `to` given as `0xRRGGBB`.  `share` is within `0..=1`.
+fn blend(from: u32, to: u32, share: f32) -> Color {
+    let channel = |shift: u32| {
+        let from = ((from >> shift) & 0xff) as f32;
+        let to = ((to >> shift) & 0xff) as f32;
+        ((from + (to - from) * share) as u32) << shift
+    };
+    Color::from_rgb(channel(16) | channel(8) | channel(0))
+}
+
+fn render_year_view(renderer: &sdlext::Renderer, data: &YearView) -> sdlext::Result<()> {
+    renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.background))?;
+    renderer.clear()?;
+    for (share, cell) in &data.days {
+        let color = blend(0x333333, data.frontend.colors.text_highlight, *share);
+        renderer.set_render_draw_color(color)?;
+        // The gap between the cells draws the grid.
+        let day = sdl::SDL_FRect {
+            x: cell.x + 1.,
+            y: cell.y + 1.,
+            w: cell.w - 2.,
+            h: cell.h - 2.,
+        };
+        renderer.render_fill_rect(&day)?;
+    }
+    data.frontend.year_text_registry.render()?;
+
+    if let Some(banner) = data.banner.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.banner))?;
+        renderer.render_fill_rect(banner)?;
+        data.frontend.banner_text_registry.render()?;
+    }
+    renderer.present()
+}
+
+fn render_agenda_view(renderer: &sdlext::Renderer, data: &AgendaView) -> sdlext::Result<()> {
+    renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.background))?;
+    renderer.clear()?;
+    if let Some(line) = data.hovered_line.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(0x333333))?;
+        renderer.render_fill_rect(line)?;
+    }
+
+    for (color, swatch) in &data.swatches {
+        renderer.set_render_draw_color(*color)?;
+        renderer.render_fill_rect(swatch)?;
+    }
+    data.frontend.agenda_text_registry.render()?;
+
+    if let Some(banner) = data.banner.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.banner))?;
+        renderer.render_fill_rect(banner)?;
+        data.frontend.banner_text_registry.render()?;
+    }
+    renderer.present()
+}
+
+fn render_search_view(renderer: &sdlext::Renderer, data: &SearchView) -> sdlext::Result<()> {
+    renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.background))?;
+    renderer.clear()?;
+    if let Some(line) = data.hovered_line.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(0x333333))?;
+        renderer.render_fill_rect(line)?;
+    }
+
+    if let Some(line) = data.selected_line.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.text_highlight))?;
+        renderer.render_fill_rect(line)?;
+    }
+    data.frontend.search_text_registry.render()?;
+    renderer.present()
+}
+
+fn render_month_view(renderer: &sdlext::Renderer, data: &MonthView) -> sdlext::Result<()> {
+    renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.background))?;
+    renderer.clear()?;
+    renderer.set_render_draw_color(Color::from_rgb(0x333333))?;
+    renderer.render_rects(&data.cells)?;
+
+    let event_render = RectangleRender { renderer };
+    let entries = data.bars.iter().chain(data.lines.iter());
+    calendar::render::render_rectangles(entries.map(|entry| &entry.rectangle), &event_render)?;
+    data.frontend.month_text_registry.render()?;
+
+    if let Some(banner) = data.banner.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.banner))?;
+        renderer.render_fill_rect(banner)?;
+        data.frontend.banner_text_registry.render()?;
+    }
+    renderer.present()
+}
+
 fn render_event_view(renderer: &sdlext::Renderer, data: &EventView) -> sdlext::Result<()> {
-    renderer.set_render_draw_color(Color::from_rgb(config::COLOR_BACKGROUND))?;
+    renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.background))?;
     renderer.clear()?;
     let frontend = data.frontend;
     let regref = frontend.event_details_text_object_regirsty.borrow();
     let text_object_positions = &regref.text_viewports;
 
     '_render_text_fields: {
-        renderer.set_render_draw_color(Color::from_rgb(config::TEXT_FIELD_BACKGROUND))?;
+        renderer
+            .set_render_draw_color(Color::from_rgb(data.frontend.colors.text_field_background))?;
         renderer.render_fill_rects(text_object_positions)?;
         renderer.set_render_draw_color(Color::WHITE)?;
         renderer.render_rects(text_object_positions)?;
@@ -75,7 +262,9 @@ fn render_event_view(renderer: &sdlext::Renderer, data: &EventView) -> sdlext::R
 
     '_render_calendar_field: {
         '_background: {
-            renderer.set_render_draw_color(Color::from_rgb(config::TEXT_FIELD_BACKGROUND))?;
+            renderer.set_render_draw_color(Color::from_rgb(
+                data.frontend.colors.text_field_background,
+            ))?;
             renderer.render_fill_rect(&data.calendar_base_rectangle)?;
             renderer.set_render_draw_color(Color::WHITE)?;
             renderer.render_rect(&data.calendar_base_rectangle)?;
@@ -99,7 +288,7 @@ fn render_event_view(renderer: &sdlext::Renderer, data: &EventView) -> sdlext::R
         if let Some(text_selection) = data.text_selection.as_ref() {
             let viewport = text_object_positions[text_selection.text_field as usize].as_rect();
             set_render_viewport_context(renderer, &viewport, || {
-                let highlight_color = Color::from_rgb(config::COLOR_TEXT_HIGHLIGHT);
+                let highlight_color = Color::from_rgb(data.frontend.colors.text_highlight);
                 renderer.set_render_draw_color(highlight_color)?;
                 for item in &text_selection.highlight {
                     renderer.render_fill_rect(item)?;
@@ -128,12 +317,30 @@ fn render_event_view(renderer: &sdlext::Renderer, data: &EventView) -> sdlext::R
         }
     }
 
+    if let Some(banner) = data.banner.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.banner))?;
+        renderer.render_fill_rect(banner)?;
+        data.frontend.banner_text_registry.render()?;
+    }
+
     renderer.present()
 }
 
 fn render_week_view(renderer: &sdlext::Renderer, data: &WeekView) -> sdlext::Result<()> {
-    renderer.set_render_draw_color(Color::from_rgb(config::COLOR_BACKGROUND))?;
+    renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.background))?;
     renderer.clear()?;
+    if let Some(weekend) = data.frontend.colors.weekend {
+        renderer.set_render_draw_color(Color::from_rgb(weekend))?;
+        for column in data.weekend_columns.iter() {
+            renderer.render_fill_rect(column)?;
+        }
+    }
+
+    if let Some(today_column) = data.today_column.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.today))?;
+        renderer.render_fill_rect(today_column)?;
+    }
+
     render_events(renderer, data)?;
     set_render_viewport_context(renderer, &data.hours_viewport, || {
         data.frontend.hour_text_texture_regirsty.render()
@@ -143,26 +350,74 @@ fn render_week_view(renderer: &sdlext::Renderer, data: &WeekView) -> sdlext::Res
         data.frontend.dates_text_texture_regirsty.render()?;
         data.frontend.days_text_texture_regirsty.render()
     })?;
+
+    if let Some(mini_month) = data.mini_month.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(0x333333))?;
+        for cell in mini_month.days.iter() {
+            renderer.render_fill_rect(cell)?;
+        }
+
+        renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.text_highlight))?;
+        for cell in mini_month.shown_days.iter() {
+            renderer.render_fill_rect(cell)?;
+        }
+
+        if let Some(today) = mini_month.today.as_ref() {
+            renderer.set_render_draw_color(Color::WHITE)?;
+            renderer.render_rect(today)?;
+        }
+    }
+
+    if let Some(prompt_bar) = data.prompt_bar.as_ref() {
+        renderer
+            .set_render_draw_color(Color::from_rgb(data.frontend.colors.text_field_background))?;
+        renderer.render_fill_rect(prompt_bar)?;
+        data.frontend.prompt_text_registry.render()?;
+    }
+
+    if let Some(banner) = data.banner.as_ref() {
+        renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.banner))?;
+        renderer.render_fill_rect(banner)?;
+        data.frontend.banner_text_registry.render()?;
+    }
     renderer.present()
 }
 
 fn render_events(renderer: &sdlext::Renderer, data: &WeekView) -> sdlext::Result<()> {
-    let event_render = RectangleRender { renderer };
-    calendar::render::render_rectangles(data.long_event_rectangles.iter(), &event_render)?;
-    data.frontend.long_event_text_registry.render()?;
+    set_render_clip_context(renderer, &data.long_event_viewport, || {
+        let event_render = RectangleRender { renderer };
+        calendar::render::render_rectangles(data.long_event_rectangles.iter(), &event_render)?;
+        data.frontend.long_event_text_registry.render()
+    })?;
 
     let event_viewport = data.event_viewport;
     set_render_viewport_context(renderer, &event_viewport, || {
-        render_short_events(renderer, &data.view.short_event_surface)?;
+        render_short_events(
+            renderer,
+            &data.view.short_event_surface,
+            data.view.day_count,
+        )?;
         let event_render = RectangleRender { renderer };
         calendar::render::render_rectangles(data.short_event_rectangles.iter(), &event_render)?;
-        data.frontend.short_event_text_registry.render()
+        data.frontend.short_event_text_registry.render()?;
+        if let Some(now_line) = data.now_line.as_ref() {
+            renderer.set_render_draw_color(Color::from_rgb(0xff0000))?;
+            renderer.render_fill_rect(now_line)?;
+        }
+        if let Some(time_selection) = data.time_selection.as_ref() {
+            renderer.set_render_draw_color(Color::from_rgb(data.frontend.colors.text_highlight))?;
+            renderer.render_fill_rect(time_selection)?;
+            renderer.set_render_draw_color(Color::WHITE)?;
+            renderer.render_rect(time_selection)?;
+        }
+        Ok(())
     })
 }
 
 fn render_short_events(
     renderer: &sdlext::Renderer,
     short_event_surface: &sdl::SDL_FRect,
+    day_count: u16,
 ) -> Result<(), sdlext::Error> {
     renderer.set_render_draw_color(Color::from_rgb(0x333333))?;
     let row_ratio: f32 = short_event_surface.h / 24.0;
@@ -176,8 +431,8 @@ fn render_short_events(
         )?;
     }
 
-    let col_ratio: f32 = short_event_surface.w / 7.;
-    for i in 0..7 {
+    let col_ratio: f32 = short_event_surface.w / day_count as f32;
+    for i in 0..day_count {
         let absciss: f32 = i as f32 * col_ratio + short_event_surface.x;
         renderer.render_line(
             absciss,
@@ -202,3 +457,17 @@ where
     sdlext::set_render_viewport(renderer, None)?;
     r
 }
+
+pub fn set_render_clip_context<'a, F>(
+    renderer: &sdlext::Renderer,
+    rect: impl Into<Option<&'a sdl::SDL_Rect>>,
+    callback: F,
+) -> sdlext::Result<()>
+where
+    F: Fn() -> sdlext::Result<()>,
+{
+    sdlext::set_render_clip_rect(renderer, rect)?;
+    let r = callback();
+    sdlext::set_render_clip_rect(renderer, None)?;
+    r
+}
@@ -1,15 +1,28 @@
 use crate::render::TextSelection;
-use crate::render::{EventViewRenderData, RenderData, WeekViewRenderData};
+use crate::render::{
+    AgendaViewRenderData, EventViewRenderData, MiniMonthRenderData, MonthViewRenderData,
+    RenderData, SearchViewRenderData, WeekViewRenderData, YearViewRenderData,
+};
 use core::cell::RefCell;
 
+mod agenda_view;
 mod calendar_state;
+mod month_view;
+mod range_request;
+mod search_view;
+mod year_view;
 use calendar::types::{
     AddFPoint, AddXy, AsFPoint, CoversPoint, MoveFRect, SubFPoint, SubXy, is_fpoint_between_points,
 };
 
+use agenda_view::AgendaView;
 use calendar_state::CalendarState;
 use calendar_state::EventRectangles;
-use calendar_state::WeekData;
+use calendar_state::{PrefetchedWeek, WeekRing};
+use month_view::MonthView;
+use range_request::RangeRequest;
+use search_view::{SearchState, SearchView};
+use year_view::YearView;
 
 use calendar::{
     date::DateStream,
@@ -20,12 +33,46 @@ use sdl3_sys::{SDL_FPoint as FPoint, SDL_FRect as FRect, SDL_Point as Point, SDL
 use sdlext::Color;
 
 // FIXME(alex): remove this ASAP
+const TITLE_TEXT_INDEX: usize = 0;
+const FROM_TEXT_INDEX: usize = 1;
+const UNTIL_TEXT_INDEX: usize = 2;
+const URL_TEXT_INDEX: usize = 3;
+const LOCATION_TEXT_INDEX: usize = 4;
 const DESCRIPTION_TEXT_INDEX: usize = 5;
 const TEXT_SCROLL_AMPLIFIER: f32 = 15.0;
 const EVENT_DETAILS_VIEW_PADDING: FPoint = FPoint { x: 3., y: 2. };
 const EVENT_DETAILS_RIGHT_OFFSET: f32 = 300.;
 const BOTTOM_SPACE: f32 = 50f32;
 const DESCRIPTION_MIN_SIZE: f32 = 200f32;
+const BANNER_PADDING: f32 = 10.;
+/// The new events are created with the precision of the quarter of an hour.
+const TIME_SELECTION_STEP: u16 = 15;
+/// The thickness of the line which tells the current time in the week view.
+const NOW_LINE_HEIGHT: f32 = 2.;
+/// The space around the mini-month in the top-left corner of the week view.
+const MINI_MONTH_PADDING: f32 = 5.;
+/// The mini-month is not shown if the corner can't fit the cells of this size.
+const MINI_MONTH_MIN_CELL: f32 = 3.;
+/// The ranges of the days shorter than that don't start on the first day of the week.  E.g. the
+/// three days view starts today.
+const SHORT_RANGE: u16 = 5;
+/// The month view has the name of the month and the names of the days above the grid.
+const MONTH_TITLE_HEIGHT: f32 = 35.;
+const MONTH_HEADER_HEIGHT: f32 = 70.;
+/// The space between the lines of the events in a cell of the month view.
+const MONTH_LINE_PADDING: f32 = 4.;
+/// The color of the dates of the month view which belong to the neighbouring months.
+const MONTH_OTHER_DATE_COLOR: u32 = 0x777777;
+/// The space around the months of the year view.
+const YEAR_PADDING: f32 = 10.;
+/// The busy minutes which shade a day of the year view fully.
+const YEAR_BUSY_MINUTES: u16 = 8 * 60;
+/// The space around the list of the agenda view.
+const AGENDA_PADDING: f32 = 10.;
+/// The space between the lines of the agenda view.
+const AGENDA_LINE_PADDING: f32 = 6.;
+/// The space around the prompt and the matches of the search view.
+const SEARCH_PADDING: f32 = 10.;
 
 mod captions {
     pub mod event_details_view {
@@ -37,6 +84,40 @@ mod captions {
         pub const LOCATION: &str = "Location:";
         pub const CALENDAR: &str = "Calendar:";
     }
+
+    pub mod confirmation {
+        pub const DELETE_EVENT: &str = "Delete the event";
+        pub const ALL_OCCURRENCES: &str = "A repeating event is deleted with all its occurrences.";
+    }
+
+    pub mod search {
+        pub const PROMPT: &str = "Search:";
+        pub const NOT_FOUND: &str = "No events found";
+        pub const SEARCHING: &str = "Searching";
+    }
+
+    pub mod filter {
+        pub const PROMPT: &str = "Filter:";
+    }
+
+    pub mod failure {
+        pub const SAVE: &str = "The event is not saved";
+        pub const DELETE: &str = "The event is not deleted";
+        pub const DUPLICATE: &str = "The event is not duplicated";
+        pub const CHANGE_IS_PENDING: &str = "The previous change is still being written";
+        pub const CALENDARS: &str = "The calendars are not loaded";
+        pub const CALENDARS_ARE_LOADING: &str = "The calendars are still loading, try again";
+        pub const NO_CALENDAR: &str = "There is no calendar to store the event";
+        pub const INVALID_TIME: &str =
+            "The time is invalid, try 2026-03-14 10:00 and don't end before the start";
+        pub const EVENT_IS_NOT_FOUND: &str = "The event is not found, the week might be changed";
+        pub const NO_UID: &str = "The event has no UID to be found by";
+    }
+
+    pub mod date_prompt {
+        pub const PROMPT: &str = "Go to:";
+        pub const IS_NOT_DATE: &str = "Try 2026-03-14, +3w, next monday or 2026-W11.";
+    }
 }
 
 /// An event changes its color upon being clicked.  The function computes the difference between
@@ -87,6 +168,27 @@ fn compute_clicked_calendar_event_color(color: &calendar::Color) -> calendar::Co
     calendar::ColorDiff(adjustment_color)
 }
 
+/// The difference which moves the `color` of an event towards white if the event holds the query
+/// of the filter, or towards black otherwise.  Unlike [`compute_clicked_calendar_event_color`],
+/// the shift is proportional to the distance to white or black, so the channels stay within their
+/// range.
+fn compute_filtered_calendar_event_color(
+    color: &calendar::Color,
+    is_match: bool,
+) -> calendar::ColorDiff {
+    // The share of the distance to white which the matching events go.
+    const HIGHLIGHT: f32 = 0.3;
+    // The share of the distance to black which the rest go.
+    const DIM: f32 = 0.7;
+    let max = 255f32;
+    let channel = |shift: u32| ((color.0 >> shift) & 0xff) as f32 / max;
+    let rgb = [channel(24), channel(16), channel(8)];
+    calendar::ColorDiff(core::array::from_fn(|i| match is_match {
+        true => (1. - rgb[i]) * HIGHLIGHT,
+        false => -rgb[i] * DIM,
+    }))
+}
+
 struct ClickedCalendarEvent {
     /// When the user click an event, the color of the event is the sum of this and
     /// `original_color`.
@@ -107,47 +209,233 @@ struct ClickedCalendarEvent {
 // to WeekView.
 pub struct Calendar<F: Frontend> {
     _frontend: std::marker::PhantomData<F>,
+    /// The first shown day.  The name is kept from the days when the view always showed a week.
     pub week_start: calendar::date::Date,
+    /// The number of the shown days.
+    pub day_count: u16,
+    /// The day on which the weeks start.
+    pub first_weekday: calendar::date::Weekday,
     pub is_week_switched: bool,
-    state: CalendarState<<F::AgendaSource as AgendaSource>::RequestHandle>,
+    /// The agenda has been changed outside of the application.  The shown week is requested again.
+    pub is_agenda_changed: bool,
+    state: CalendarState<<F::AgendaSource as AgendaSource>::RequestHandle, F::Error>,
+    /// The request of the fresh data while the data of the state is taken from [`WeekCache`] or
+    /// it's outdated.  The data of the state is replaced once the request is ready.
+    refresh_handle: Option<<F::AgendaSource as AgendaSource>::RequestHandle>,
+    /// The weeks next to the shown one.  They are obtained in advance to switch the week at once.
+    prefetched: WeekRing<<F::AgendaSource as AgendaSource>::RequestHandle, F::Error>,
+    /// The week which the data of `state` belongs to.  It differs from `week_start` until the
+    /// switched week is requested.
+    shown_week_start: calendar::date::Date,
     /// Holds the information about the event which was under the mouse cursor upen the left click.
     clicked_event: Option<ClickedCalendarEvent>,
+    /// The time range being selected to create a new event.
+    time_selection: Option<TimeSelection>,
+    /// The day of the week shown alone by the day view.  The week is laid out as usual, but the
+    /// day takes the width of the viewport.  See [`View::focus_day`].
+    pub focused_day: Option<u8>,
+    /// The filter which dims the events of the shown days.  It's kept while the week is switched.
+    filter: Option<EventFilter>,
+    /// The prompt of the date to go to.  It takes the typed text while it's open.
+    date_prompt: Option<DatePrompt>,
+    /// The current date and time.  They're updated once a minute.  See [`Action::ClockTick`].
+    now: (calendar::date::Date, calendar::date::Time),
+    /// The state has been replaced by a failed one.  The banner which tells the previous failure
+    /// is created again.
+    is_failure_changed: bool,
 }
 
 impl<F: Frontend> Calendar<F> {
-    fn new(frontend: &F) -> Result<Self, F::Error> {
-        let week_start: calendar::date::Date = frontend.get_current_week_start()?;
+    fn new(frontend: &F, day_count: u16) -> Result<Self, F::Error> {
+        let current_week_start: calendar::date::Date = frontend.get_current_week_start()?;
+        let week_start = if day_count < SHORT_RANGE {
+            frontend.get_today()?
+        } else {
+            current_week_start.clone()
+        };
         let is_week_switched = false;
-        let agenda_source_handle = frontend.agenda_source().request(&week_start)?;
+        let agenda_source_handle = frontend
+            .agenda_source()
+            .request_range(&week_start, day_count)?;
+        let now = frontend.get_now()?;
+        let cached = frontend.week_cache().load(&week_start);
+        let (state, refresh_handle) = CalendarState::from_cache(agenda_source_handle, cached);
         Ok(Self {
             _frontend: std::marker::PhantomData,
+            shown_week_start: week_start.clone(),
             week_start,
-            state: CalendarState::Loading {
-                agenda_source_handle,
-            },
+            day_count,
+            first_weekday: current_week_start.weekday(),
+            state,
+            refresh_handle,
+            prefetched: WeekRing::new(),
             is_week_switched,
+            is_agenda_changed: false,
             clicked_event: None,
+            time_selection: None,
+            focused_day: None,
+            filter: None,
+            date_prompt: None,
+            now,
+            is_failure_changed: false,
         })
     }
 
+    /// The number of the days by which the shown days are switched.  The ranges which start on
+    /// the first day of the week (e.g. the work week) are switched by whole weeks.  The shorter
+    /// ones are switched by their length.
+    fn step(&self) -> i16 {
+        let day_count = self.day_count as i16;
+        if self.day_count < SHORT_RANGE {
+            day_count
+        } else {
+            (day_count + 6) / 7 * 7
+        }
+    }
+
+    /// The first shown day once `date` is to be shown.
+    pub fn range_start(&self, date: &calendar::date::Date) -> calendar::date::Date {
+        if self.day_count < SHORT_RANGE {
+            date.clone()
+        } else {
+            date.week_start(self.first_weekday)
+        }
+    }
+
+    /// Switches to the days which hold `date`.
+    pub fn show_date(&mut self, date: &calendar::date::Date) {
+        self.week_start = self.range_start(date);
+        self.is_week_switched = true;
+    }
+
     pub fn add_week(&mut self) {
-        self.week_start = self.week_start.add_week();
+        self.week_start = self.week_start.add_days(self.step());
         self.is_week_switched = true;
     }
 
     pub fn subtract_week(&mut self) {
-        self.week_start = self.week_start.subtract_week();
+        self.week_start = self.week_start.add_days(-self.step());
         self.is_week_switched = true;
     }
 
+    /// Moves the day view by `days`.  The shown days are switched once the day leaves them.
+    pub fn shift_focused_day(&mut self, days: i8) {
+        let Some(day) = self.focused_day else {
+            return;
+        };
+
+        let day = day as i16 + days as i16;
+        let day_count = self.day_count as i16;
+        let day = if day < 0 {
+            self.subtract_week();
+            day_count - 1
+        } else if day >= day_count {
+            self.add_week();
+            0
+        } else {
+            day
+        };
+        self.focused_day = Some(day as u8);
+        self.request_render();
+    }
+
+    /// Requests the data of the week.  If the week is switched, its data is taken from the weeks
+    /// obtained in advance.  If the week hasn't been obtained yet, the data stored in [`WeekCache`]
+    /// is shown until the request is ready.  The week which is switched from is kept in the ring
+    /// of the prefetched weeks.
+    ///
+    /// If the week is not switched or `is_agenda_changed` is set, the agenda has been changed.
+    /// The current data is kept on the screen until the fresh one is ready.
     fn update_week_data(&mut self, frontend: &F) -> Result<(), F::Error> {
+        let is_outdated = !self.is_week_switched || self.is_agenda_changed;
+        if is_outdated {
+            // The weeks obtained before don't have the changes.
+            let src = frontend.agenda_source();
+            for week in self.prefetched.drain() {
+                release_prefetched(src, Some(week));
+            }
+        }
+
+        // The week can be switched forth and back before the data is updated.
+        if self.week_start != self.shown_week_start {
+            self.switch_week(frontend)?;
+        } else if is_outdated {
+            self.refresh_week(frontend)?;
+        }
+
+        self.shown_week_start = self.week_start.clone();
+        self.is_week_switched = false;
+        self.is_agenda_changed = false;
+        self.is_failure_changed |= matches!(self.state, CalendarState::Failed { .. });
+        Ok(())
+    }
+
+    fn switch_week(&mut self, frontend: &F) -> Result<(), F::Error> {
+        let src = frontend.agenda_source();
+        let next = match self.prefetched.take(&self.week_start) {
+            Some(week) => week,
+            // The request which fails is shown the same way as the failed week.
+            None => match src.request_range(&self.week_start, self.day_count) {
+                Ok(handle) => PrefetchedWeek::Requested(handle),
+                Err(error) => PrefetchedWeek::Failed(error),
+            },
+        };
+
+        let (next_state, next_refresh_handle) = match next {
+            PrefetchedWeek::Received(agenda) => (CalendarState::rendering(*agenda), None),
+            PrefetchedWeek::Requested(handle) => {
+                let cached = frontend.week_cache().load(&self.week_start);
+                CalendarState::from_cache(handle, cached)
+            }
+            PrefetchedWeek::Failed(error) => (CalendarState::Failed { error }, None),
+        };
+
+        let previous_state = std::mem::replace(&mut self.state, next_state);
+        let previous_refresh_handle =
+            std::mem::replace(&mut self.refresh_handle, next_refresh_handle);
+        let previous = match (previous_state, previous_refresh_handle) {
+            (
+                CalendarState::Loading {
+                    agenda_source_handle,
+                },
+                refresh_handle,
+            ) => {
+                release_prefetched(src, refresh_handle.map(PrefetchedWeek::Requested));
+                Some(PrefetchedWeek::Requested(agenda_source_handle))
+            }
+            // The cached data is not kept.  The week is going to be replaced by the fresh data.
+            (_, Some(refresh_handle)) => Some(PrefetchedWeek::Requested(refresh_handle)),
+            (
+                CalendarState::Ready { week_data, .. } | CalendarState::Rendering { week_data, .. },
+                None,
+            ) => Some(PrefetchedWeek::Received(Box::new(week_data.agenda))),
+            // The failed week is requested again once it's switched back.
+            (CalendarState::Failed { .. }, None) => None,
+        };
+
+        if let Some(previous) = previous {
+            let dropped = self
+                .prefetched
+                .push(self.shown_week_start.clone(), previous);
+            release_prefetched(src, dropped);
+        }
+        Ok(())
+    }
+
+    fn refresh_week(&mut self, frontend: &F) -> Result<(), F::Error> {
+        let src = frontend.agenda_source();
+        let refresh_handle = self.refresh_handle.take();
+        release_prefetched(src, refresh_handle.map(PrefetchedWeek::Requested));
+
+        let week_start = &self.week_start;
+        let day_count = self.day_count;
+        let refresh_handle = &mut self.refresh_handle;
         self.state.switch(|current_state| match current_state {
             CalendarState::Loading {
                 agenda_source_handle,
             } => {
-                let src = frontend.agenda_source();
                 src.cancel(&agenda_source_handle);
-                let ret = frontend.agenda_source().request(&self.week_start);
+                let ret = src.request_range(week_start, day_count);
                 match ret {
                     Ok(x) => {
                         src.free(agenda_source_handle);
@@ -157,22 +445,66 @@ impl<F: Frontend> Calendar<F> {
                 }
             }
             CalendarState::Ready { .. } | CalendarState::Rendering { .. } => {
-                let ret = frontend.agenda_source().request(&self.week_start);
+                let ret = src.request_range(week_start, day_count);
                 match ret {
-                    Ok(x) => CalendarState::loading(x, None),
+                    Ok(x) => {
+                        *refresh_handle = Some(x);
+                        (current_state, None)
+                    }
                     Err(e) => (current_state, Some(e)),
                 }
             }
-        })?;
+            // The retry which fails is shown the same way as the failed request.
+            CalendarState::Failed { .. } => match src.request_range(week_start, day_count) {
+                Ok(x) => {
+                    let cached = frontend.week_cache().load(week_start);
+                    let (state, x) = CalendarState::from_cache(x, cached);
+                    *refresh_handle = x;
+                    (state, None)
+                }
+                Err(error) => (CalendarState::Failed { error }, None),
+            },
+        })
+    }
+
+    /// Requests the weeks next to the current one once the current one is obtained.
+    fn prefetch(&mut self, frontend: &F) {
+        let is_obtained = self.refresh_handle.is_none()
+            && matches!(
+                self.state,
+                CalendarState::Ready { .. } | CalendarState::Rendering { .. }
+            );
+        if !is_obtained {
+            return;
+        }
+
+        let src = frontend.agenda_source();
+        let step = self.step();
+        for week_start in [
+            self.week_start.add_days(-step),
+            self.week_start.add_days(step),
+        ] {
+            if self.prefetched.contains(&week_start) {
+                continue;
+            }
 
-        self.is_week_switched = false;
-        Ok(())
+            // The failed request is not repeated until the week is switched.  Then the week is
+            // requested again and the error is reported.
+            let Ok(handle) = src.request_range(&week_start, self.day_count) else {
+                continue;
+            };
+
+            let dropped = self
+                .prefetched
+                .push(week_start, PrefetchedWeek::Requested(handle));
+            release_prefetched(src, dropped);
+        }
     }
 
     pub fn request_render(&mut self) {
         use CalendarState::*;
         self.state.switch_infallible(|state| match state {
-            x @ (Loading { .. } | Rendering { .. }) => x,
+            x @ (Loading { .. } | Rendering { .. } | Failed { .. }) => x,
             Ready {
                 week_data,
                 long_event_clash_size,
@@ -199,8 +531,11 @@ impl<F: Frontend> Calendar<F> {
     {
         use CalendarState::*;
         let week_start = &self.week_start;
+        let is_day_focused = self.focused_day.is_some();
+        // The long events are moved along with the days of the short events.
+        let long_event_offset = event_offset.add_xy(view.short_event_surface.x, 0.);
         self.state.switch(move |current_state| match current_state {
-            Loading { .. } | Ready { .. } => (current_state, None),
+            Loading { .. } | Ready { .. } | Failed { .. } => (current_state, None),
             Rendering {
                 week_data,
                 long_event_clash_size,
@@ -210,8 +545,14 @@ impl<F: Frontend> Calendar<F> {
                     text_registry: frontend.get_short_event_text_registry(),
                 };
 
-                let short_event_rectangles_opt =
-                    create_short_events(&week_data.agenda.short, week_start, reg, view);
+                // The day view has the room for the time and the location of the events.
+                let short_event_rectangles_opt = create_short_events(
+                    &week_data.agenda.short,
+                    week_start,
+                    reg,
+                    view,
+                    is_day_focused,
+                );
 
                 let short_event_rectangles_opt = match short_event_rectangles_opt {
                     Ok(x) => x,
@@ -234,7 +575,7 @@ impl<F: Frontend> Calendar<F> {
                     &week_data.agenda.long,
                     week_start,
                     reg,
-                    event_offset,
+                    &long_event_offset,
                     view.cell_width,
                     top_panel_height,
                 );
@@ -271,35 +612,111 @@ impl<F: Frontend> Calendar<F> {
             CalendarState::Loading {
                 agenda_source_handle,
             } => frontend.agenda_source().is_ready(agenda_source_handle),
-            CalendarState::Ready { .. } | CalendarState::Rendering { .. } => false,
+            CalendarState::Ready { .. }
+            | CalendarState::Rendering { .. }
+            | CalendarState::Failed { .. } => false,
         }
     }
 
-    fn get_rendering(&mut self, frontend: &F) {
-        self.state
-            .switch_infallible(|current_state| match current_state {
-                CalendarState::Loading {
-                    agenda_source_handle,
-                } => {
-                    let src = frontend.agenda_source();
-                    if src.is_ready(&agenda_source_handle) {
-                        let agenda: calendar::obtain::WeekScheduleWithLanes =
-                            src.fetch(&agenda_source_handle, &self.week_start);
-                        let week_data = WeekData { agenda };
-                        let long_event_clash_size = week_data.agenda.long.calculate_biggest_clash();
-                        src.free(agenda_source_handle);
-                        CalendarState::Rendering {
-                            week_data,
-                            long_event_clash_size,
-                        }
-                    } else {
-                        CalendarState::Loading {
-                            agenda_source_handle,
-                        }
-                    }
-                }
-                x => x,
-            });
+    /// Colors the events by the filter.  The events which hold the query are highlighted, the
+    /// rest are dimmed.  The events get the colors of their calendars once the filter is empty.
+    /// The colors are kept until the rectangles of the events are created again.
+    fn apply_filter(&mut self) {
+        let query = self
+            .filter
+            .as_ref()
+            .map(|filter| filter.query.to_lowercase())
+            .filter(|query| !query.is_empty());
+        for is_long in [false, true] {
+            let Some(table) = self.state.get_event_table(is_long) else {
+                continue;
+            };
+
+            // NOTE(alex): the colors are collected first since the table is borrowed from the
+            // state which is changed by them.
+            let colors = EventFilter::colors(table, query.as_deref());
+
+            for (event, color) in colors.into_iter().enumerate() {
+                self.state.set_color(event as u32, is_long, color);
+            }
+        }
+    }
+
+    /// The shown data is not going to be replaced.  I.e. it's neither loading nor taken from
+    /// [`WeekCache`].
+    fn is_settled(&self) -> bool {
+        let is_loading = matches!(self.state, CalendarState::Loading { .. });
+        self.refresh_handle.is_none() && !is_loading
+    }
+
+    /// Takes the data of the ready requests.  Returns `true` if the shown data is replaced.
+    fn get_rendering(&mut self, frontend: &F) -> bool {
+        let src = frontend.agenda_source();
+        let day_count = self.day_count;
+        // The data is obtained from the source.  It's stored to be shown at once next time.
+        let receive = |week_start: &calendar::date::Date, handle| {
+            let agenda = src
+                .fetch(&handle, week_start)
+                .map(|agenda| calendar::obtain::get_lanes(agenda, week_start, day_count));
+            src.free(handle);
+            if let Ok(agenda) = agenda.as_ref() {
+                frontend.week_cache().store(week_start, agenda);
+            }
+            agenda
+        };
+
+        self.prefetched.receive(|week_start, handle| {
+            if !src.is_ready(&handle) {
+                return PrefetchedWeek::Requested(handle);
+            }
+
+            match receive(week_start, handle) {
+                Ok(agenda) => PrefetchedWeek::Received(Box::new(agenda)),
+                Err(e) => PrefetchedWeek::Failed(e),
+            }
+        });
+
+        let week_start = &self.week_start;
+        let mut is_received = false;
+        // The fresh data replaces the data which is shown.
+        if self
+            .refresh_handle
+            .as_ref()
+            .is_some_and(|handle| src.is_ready(handle))
+            && let Some(handle) = self.refresh_handle.take()
+        {
+            self.state = CalendarState::fetched(receive(week_start, handle));
+            is_received = true;
+        } else if let CalendarState::Loading {
+            agenda_source_handle,
+        } = &self.state
+            && src.is_ready(agenda_source_handle)
+        {
+            self.state
+                .switch_infallible(|current_state| match current_state {
+                    CalendarState::Loading {
+                        agenda_source_handle,
+                    } => CalendarState::fetched(receive(week_start, agenda_source_handle)),
+                    _ => unreachable!("the week must be loading"),
+                });
+            is_received = true;
+        }
+
+        self.is_failure_changed |=
+            is_received && matches!(self.state, CalendarState::Failed { .. });
+        self.prefetch(frontend);
+        is_received
+    }
+}
+
+/// Cancels the request of the week if it's still running.
+fn release_prefetched<S: AgendaSource>(
+    src: &S,
+    week: Option<PrefetchedWeek<S::RequestHandle, S::Error>>,
+) {
+    if let Some(PrefetchedWeek::Requested(handle)) = week {
+        src.cancel(&handle);
+        src.free(handle);
     }
 }
 
@@ -396,6 +813,25 @@ impl UserInterface {
         self.adjustment.vertical_offset = new_value.clamp(-self.adjustment.vertical_scale, 0f32);
     }
 
+    /// Scrolls the surface with the short events so that `time` is close to the top of its
+    /// viewport.  The hour before `time` stays visible.
+    fn reveal_time(
+        &mut self,
+        time: &calendar::date::Time,
+        long_event_surface_height: f32,
+        window_size: &Point,
+    ) {
+        use calendar::date::{MINUTES_PER_DAY, MINUTES_PER_HOUR};
+        let viewport =
+            ShortEventViewport::new(&self.event_offset, window_size, long_event_surface_height);
+        let surface_height = viewport.size.y + self.adjustment.vertical_scale;
+        let minutes = time
+            .minutes_from_midnight()
+            .saturating_sub(MINUTES_PER_HOUR as u16);
+        let offset = -(minutes as f32) / MINUTES_PER_DAY as f32 * surface_height;
+        self.adjustment.vertical_offset = offset.clamp(-self.adjustment.vertical_scale, 0f32);
+    }
+
     /// To scale the surface with the short events, its vertical offset is to be adjusted as well
     /// for two reasons:
     /// 1. The event under the cursor must stay under the cursor.
@@ -442,6 +878,49 @@ pub struct App<F: Frontend> {
     pub calendar: Calendar<F>,
     pub ui: UserInterface,
     event_details_view: Option<EventDetailsView>,
+    month_view: Option<MonthView<<F::AgendaSource as AgendaSource>::RequestHandle, F::Error>>,
+    agenda_view: Option<AgendaView<<F::AgendaSource as AgendaSource>::RequestHandle, F::Error>>,
+    year_view: Option<YearView<<F::AgendaSource as AgendaSource>::RequestHandle, F::Error>>,
+    search_view: Option<SearchView<<F::AgendaSource as AgendaSource>::SearchHandle, F::Error>>,
+    /// The match of the search which is opened once the week which holds it is obtained.
+    pending_match: Option<calendar::obtain::SearchMatch>,
+    /// The failure of the last change of the agenda, e.g. the event which isn't saved.  It's told
+    /// by the banner of the week view or of the event view.  See [`App::tell_failure`].
+    failure: Option<String>,
+    calendars: WritableCalendars<F>,
+    /// The change of the agenda which is being written.  See [`App::receive_change`].
+    pending_change: Option<PendingChange<F>>,
+}
+
+type PendingWrite<F> = <<F as Frontend>::AgendaWriter as AgendaWriter>::Pending<()>;
+
+/// The change of the agenda which is being written in the background.
+struct PendingChange<F: Frontend> {
+    pending: PendingWrite<F>,
+    /// Tells the failure to write the change.  E.g. [`captions::failure::SAVE`].
+    failure: &'static str,
+    /// The event view is left once the change is written.  It's reset if the view is left before.
+    leaves_event_view: bool,
+}
+
+type PendingCalendars<F> =
+    <<F as Frontend>::AgendaWriter as AgendaWriter>::Pending<Vec<WritableCalendar>>;
+
+/// The calendars which accept the new events.  They are loaded once on start.  See
+/// [`App::obtain_calendars`].
+enum WritableCalendars<F: Frontend> {
+    Loading(PendingCalendars<F>),
+    Loaded(Vec<WritableCalendar>),
+    Failed(F::Error),
+}
+
+impl<F: Frontend> WritableCalendars<F> {
+    fn request(frontend: &F) -> Self {
+        match frontend.agenda_writer().calendars() {
+            Ok(pending) => Self::Loading(pending),
+            Err(error) => Self::Failed(error),
+        }
+    }
 }
 
 struct SelectionHighlight {
@@ -475,10 +954,112 @@ impl SelectionHighlight {
     }
 }
 
+/// The query which the events of the week view are filtered by.  See [`Calendar::apply_filter`].
+struct EventFilter {
+    query: String,
+    /// The typed text goes to the query rather than acts as the keys of the week view.
+    is_typing: bool,
+}
+
+impl EventFilter {
+    /// The title, the description, the location or the calendar of the `event` holds the `query`.
+    /// The `query` is expected in the lower case.
+    fn is_match(table: &calendar::EventTable, event: u32, query: &str) -> bool {
+        [
+            table.obtain_title(event),
+            table.obtain_description(event),
+            table.obtain_location(event),
+            table.obtain_calendar(event),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(query))
+    }
+
+    /// The colors of the events of the `table`.  The events which hold the `query` are
+    /// highlighted, the rest are dimmed.  The events keep the colors of their calendars without
+    /// the query.
+    fn colors(table: &calendar::EventTable, query: Option<&str>) -> Vec<calendar::Color> {
+        (0..table.calendar_colors.len() as u32)
+            .map(|event| {
+                let color = table.calendar_colors[event as usize];
+                match query {
+                    Some(query) => {
+                        let is_match = Self::is_match(table, event, query);
+                        color.adjust(&compute_filtered_calendar_event_color(&color, is_match))
+                    }
+                    None => color,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The prompt of the date to go to.  See [`calendar::date::Date::from_jump`].
+struct DatePrompt {
+    input: String,
+    /// The input is not a date.  The prompt tells the accepted forms until the input changes.
+    is_failed: bool,
+}
+
+/// The time range which is selected by dragging the mouse over the empty space of the short
+/// event surface.  The selection stays within the day where it has started.  The minutes are
+/// counted from the midnight.
+struct TimeSelection {
+    day: u8,
+    /// The moment where the dragging has started.
+    anchor: u16,
+    /// The moment under the mouse cursor.
+    cursor: u16,
+}
+
+impl TimeSelection {
+    fn minutes(&self) -> (u16, u16) {
+        (self.anchor.min(self.cursor), self.anchor.max(self.cursor))
+    }
+
+    /// Returns `None` if the selection is empty.  It happens when the user clicks without
+    /// dragging.
+    fn to_range(&self, week_start: &calendar::date::Date) -> Option<calendar::EventRange> {
+        use calendar::date::{Time, shift_minutes};
+        let (start, end) = self.minutes();
+        if start == end {
+            return None;
+        }
+
+        let date = week_start.add_days(self.day as i16);
+        let (start_date, start_time) = shift_minutes(&date, &Time::midnight(), start as i64);
+        let (end_date, end_time) = shift_minutes(&date, &Time::midnight(), end as i64);
+        Some(calendar::EventRange {
+            start_date,
+            start_time,
+            end_date,
+            end_time,
+        })
+    }
+}
+
+/// The new event which is not stored yet.  See [`AgendaWriter`].
+struct EventDraft {
+    range: calendar::EventRange,
+    calendars: Vec<WritableCalendar>,
+    /// The index of the calendar within `calendars` where the event is going to be stored.
+    calendar: usize,
+}
+
+/// The event which is shown by [`EventDetailsView`].
+enum EventOrigin {
+    /// The event is stored in the table of [`CalendarState`].
+    Table {
+        index: u32,
+        kind: CalendarEventKind,
+    },
+    Draft(EventDraft),
+}
+
 struct EventDetailsView {
     selection_highlight: Option<SelectionHighlight>,
-    event_index: u32,
-    event_kind: CalendarEventKind,
+    origin: EventOrigin,
     /// The fields which stretch as the window is resized.
     flexible_fields: Box<[u32]>,
     /// The strings which are rendered inside the text fields.
@@ -520,6 +1101,7 @@ impl<F: Frontend> App<F> {
         event_offset: FPoint,
         mouse_position: FPoint,
         event_title_offset: FPoint,
+        day_count: u16,
     ) -> Result<Self, F::Error> {
         let ui = UserInterface::new(
             title_font_height,
@@ -527,17 +1109,25 @@ impl<F: Frontend> App<F> {
             event_title_offset,
             mouse_position,
         );
-        let calendar = Calendar::new(frontend)?;
+        let calendar = Calendar::new(frontend, day_count)?;
         App::create_hours_text_objects(frontend, ui.event_offset.x)?;
 
         let cell_width = DUMB_CELL_WIDTH; // FIXME: the value must be calculated
-        App::create_days_text_objects(frontend, cell_width)?;
+        App::create_days_text_objects(frontend, cell_width, &calendar.week_start, day_count)?;
 
-        App::create_dates_text_objects(frontend, cell_width, &calendar.week_start)?;
+        App::create_dates_text_objects(frontend, cell_width, &calendar.week_start, day_count)?;
         Ok(Self {
             calendar,
             ui,
             event_details_view: None,
+            month_view: None,
+            agenda_view: None,
+            year_view: None,
+            search_view: None,
+            pending_match: None,
+            failure: None,
+            calendars: WritableCalendars::request(frontend),
+            pending_change: None,
         })
     }
 
@@ -546,13 +1136,12 @@ impl<F: Frontend> App<F> {
             .as_ref()
             .and_then(|view: &EventDetailsView| {
                 let s = &self.calendar.state;
-                let is_long = match view.event_kind {
-                    CalendarEventKind::Long => true,
-                    CalendarEventKind::Short => false,
+                let EventOrigin::Table { index, kind } = &view.origin else {
+                    return view.texts.get(DESCRIPTION_TEXT_INDEX).map(AsRef::as_ref);
                 };
 
-                s.get_event_table(is_long)
-                    .and_then(|t| t.obtain_description(view.event_index))
+                s.get_event_table(kind.is_long())
+                    .and_then(|t| t.obtain_description(*index))
             })
     }
 
@@ -571,10 +1160,16 @@ impl<F: Frontend> App<F> {
         adjustment: &SurfaceAdjustment,
         window_size: &Point,
         long_event_surface_height: f32,
+        day_count: u16,
+        focused_day: Option<u8>,
     ) -> View {
         let size =
             Self::compute_viewport_size(event_offset, window_size, long_event_surface_height);
-        View::new(size, adjustment)
+        let view = View::new(size, adjustment, day_count);
+        match focused_day {
+            Some(day) => view.focus_day(day),
+            None => view,
+        }
     }
 
     fn reposition_hours_text_objects(frontend: &mut F, width: f32, view: &View) {
@@ -616,8 +1211,8 @@ impl<F: Frontend> App<F> {
     fn reposition_days_text_objects(frontend: &mut F, offset: f32, view: &View) {
         let cell_width = view.cell_width;
         let cell_height = view.cell_height;
-        let positions = (0..7).map(|day| FRect {
-            x: cell_width * day as f32,
+        let positions = (0..view.day_count).map(|day| FRect {
+            x: view.short_event_surface.x + cell_width * day as f32,
             y: offset,
             w: cell_width,
             h: cell_height,
@@ -631,8 +1226,8 @@ impl<F: Frontend> App<F> {
     fn reposition_dates_text_objects(frontend: &mut F, offset: f32, view: &View) {
         let cell_width = view.cell_width;
         let cell_height = view.cell_height;
-        let positions = (0..7).map(|day| FRect {
-            x: cell_width * day as f32,
+        let positions = (0..view.day_count).map(|day| FRect {
+            x: view.short_event_surface.x + cell_width * day as f32,
             y: offset,
             w: cell_width,
             h: cell_height,
@@ -643,18 +1238,17 @@ impl<F: Frontend> App<F> {
             .update_positions(positions);
     }
 
-    fn create_days_text_objects(frontend: &mut F, cell_width: f32) -> Result<(), F::Error> {
+    /// The names of `day_count` days starting from the weekday of `week_start`.
+    fn create_days_text_objects(
+        frontend: &mut F,
+        cell_width: f32,
+        week_start: &calendar::date::Date,
+        day_count: u16,
+    ) -> Result<(), F::Error> {
         let days_registry = frontend.get_days_text_registry();
-        let weekdays = [
-            "Monday",
-            "Tuesday",
-            "Wednesday",
-            "Thursday",
-            "Friday",
-            "Saturday",
-            "Sunday",
-        ];
-
+        let weekdays = DateStream::new(week_start.clone())
+            .take(day_count as usize)
+            .map(|date| date.weekday().name());
         for day in weekdays {
             // FIXME: avoid this empty rectangles.  They are needed only to define the text wrap
             // length
@@ -673,14 +1267,15 @@ impl<F: Frontend> App<F> {
         frontend: &mut F,
         cell_width: f32,
         week_start: &calendar::date::Date,
+        day_count: u16,
     ) -> Result<(), F::Error> {
         let dates_registry = frontend.get_dates_text_registry();
 
         let mut dates = DateStream::new(week_start.clone());
-        for _ in 0..7 {
+        for _ in 0..day_count {
             let date = dates
                 .next()
-                .expect("the date stream must provide at least 7 dates");
+                .expect("the date stream must provide the dates of the range");
             let text = format!("{:04}-{:02}-{:02}", date.year, date.month, date.day);
             // FIXME: avoid this empty rectangles.  They are needed only to define the text wrap
             // length
@@ -711,6 +1306,56 @@ impl<F: Frontend> App<F> {
         )
     }
 
+    /// Requests the events of the week once it's switched or the agenda is changed.
+    fn update_week(&mut self, frontend: &mut F) -> Result<(), F::Error> {
+        // If the user switches the week, the events for the week are requested from Khal.
+        if self.calendar.is_week_switched {
+            self.calendar.update_week_data(frontend)?;
+            let cell_width = DUMB_CELL_WIDTH;
+            // The range which is shorter than a week doesn't start on the same weekday every
+            // time.  Given that, the names of the days are created again as well.
+            frontend.get_days_text_registry().clear();
+            App::create_days_text_objects(
+                frontend,
+                cell_width,
+                &self.calendar.week_start,
+                self.calendar.day_count,
+            )?;
+            frontend.get_dates_text_registry().clear();
+            App::create_dates_text_objects(
+                frontend,
+                cell_width,
+                &self.calendar.week_start,
+                self.calendar.day_count,
+            )?;
+        } else if self.calendar.is_agenda_changed {
+            self.calendar.update_week_data(frontend)?;
+        }
+        Ok(())
+    }
+
+    /// The view of the short events as it's laid out for the current window.
+    fn compute_view(&self, window_size: &Point) -> View {
+        Self::create_view(
+            &self.ui.event_offset,
+            &self.ui.adjustment,
+            window_size,
+            self.compute_long_event_height(),
+            self.calendar.day_count,
+            self.calendar.focused_day,
+        )
+    }
+
+    /// The day of the column which holds the abscissa `x`.
+    fn find_day_column(&self, x: f32, window_size: &Point) -> Option<u8> {
+        let view = self.compute_view(window_size);
+        let x = x - self.ui.event_offset.x - view.short_event_surface.x;
+        let day = (x / view.cell_width).floor();
+        (0. ..view.day_count as f32)
+            .contains(&day)
+            .then_some(day as u8)
+    }
+
     pub fn create_week_view_render_data<'wdrect, 'frontend>(
         &'wdrect mut self,
         frontend: &'frontend mut F,
@@ -718,9 +1363,80 @@ impl<F: Frontend> App<F> {
         events: impl IntoIterator<Item = Action>,
     ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
         let mut event_mouse_click: Option<MouseEventClick> = None;
+        // The range selected to create a new event.
+        let mut new_event_range: Option<calendar::EventRange> = None;
+        // The events are colored again once the query of the filter changes.
+        let mut is_filter_changed = false;
+        // The text of the prompt bar is created again once the date prompt changes.
+        let mut is_prompt_changed = false;
         // :userInputHandling
         for event in events {
             use Action::*;
+            // The typed text goes to the date prompt.  The keys which type it don't act.
+            if let Some(prompt) = self.calendar.date_prompt.as_mut() {
+                let is_typed = match &event {
+                    TextInput(text) => {
+                        prompt.input.push_str(text);
+                        prompt.is_failed = false;
+                        true
+                    }
+                    DeleteBackward => {
+                        prompt.input.pop();
+                        prompt.is_failed = false;
+                        true
+                    }
+                    Submit => {
+                        let today = frontend.get_today()?;
+                        match calendar::date::Date::from_jump(&prompt.input, &today) {
+                            Ok(date) => {
+                                self.calendar.date_prompt = None;
+                                self.calendar.show_date(&date);
+                            }
+                            Err(_) => prompt.is_failed = true,
+                        }
+                        true
+                    }
+                    Escape => {
+                        self.calendar.date_prompt = None;
+                        true
+                    }
+                    event => is_typing_key(event),
+                };
+                if is_typed {
+                    is_prompt_changed = true;
+                    continue;
+                }
+            }
+
+            // The typed text goes to the filter.  The keys which type it don't act.
+            if let Some(filter) = self.calendar.filter.as_mut()
+                && filter.is_typing
+            {
+                let is_typed = match &event {
+                    TextInput(text) => {
+                        filter.query.push_str(text);
+                        true
+                    }
+                    DeleteBackward => {
+                        filter.query.pop();
+                        true
+                    }
+                    Submit => {
+                        filter.is_typing = false;
+                        true
+                    }
+                    Escape => {
+                        self.calendar.filter = None;
+                        true
+                    }
+                    event => is_typing_key(event),
+                };
+                if is_typed {
+                    is_filter_changed = true;
+                    continue;
+                }
+            }
+
             match event {
                 MouseButtonUp {
                     position: mouse_position,
@@ -744,9 +1460,57 @@ impl<F: Frontend> App<F> {
                             );
                         }
                     }
+
+                    if let Some(time_selection) = self.calendar.time_selection.take() {
+                        new_event_range = time_selection.to_range(&self.calendar.week_start);
+                    }
+
+                    // The click on a day of the mini-month shows its week.  The click on the
+                    // caption of a day opens the day view.  The click on the caption in the day
+                    // view returns to the week.
+                    if let Some(date) = self
+                        .create_mini_month()
+                        .and_then(|month| month.find_date(&mouse_position))
+                    {
+                        self.calendar.show_date(&date);
+                    } else if mouse_position.y < self.ui.event_offset.y
+                        && let Some(day) = self.find_day_column(mouse_position.x, &window_size)
+                    {
+                        self.calendar.focused_day = match self.calendar.focused_day {
+                            Some(_) => None,
+                            None => Some(day),
+                        };
+                        self.calendar.request_render();
+                    }
+                }
+                Escape if self.failure.is_some() => self.dismiss_failure(frontend),
+                Escape if self.calendar.filter.is_some() => {
+                    self.calendar.filter = None;
+                    is_filter_changed = true;
+                }
+                Escape if self.calendar.focused_day.is_some() => {
+                    self.calendar.focused_day = None;
+                    self.calendar.request_render();
                 }
                 Escape => (),
-                WindowResize => self.calendar.request_render(),
+                ToggleDayView => {
+                    self.calendar.focused_day = match self.calendar.focused_day {
+                        Some(_) => None,
+                        // The day under the mouse cursor is opened.
+                        None => Some(
+                            self.find_day_column(self.ui.mouse_position.x, &window_size)
+                                .unwrap_or(0),
+                        ),
+                    };
+                    self.calendar.request_render();
+                }
+                WindowResize => {
+                    // The text of the banner is wrapped by the width of the window.
+                    frontend.get_banner_text_registry().clear();
+                    // The prompt bar is at the bottom of the window.
+                    frontend.get_prompt_text_registry().clear();
+                    self.calendar.request_render();
+                }
                 Scroll { offset: value, .. } => {
                     self.ui.add_adjustment(value * -50.);
                     self.calendar.request_render();
@@ -789,9 +1553,109 @@ impl<F: Frontend> App<F> {
                             color,
                         );
                     }
+
+                    // The selection is stretched within its day as the cursor moves.
+                    if let Some(MouseButton::Left) = pressed_button
+                        && self.calendar.time_selection.is_some()
+                    {
+                        let long_event_surface = self.compute_long_event_surface(&window_size);
+                        let viewport_offset = ShortEventViewport::from_long_event_surface(
+                            &long_event_surface,
+                            &window_size,
+                        )
+                        .offset;
+                        let position = self.ui.mouse_position.sub_fpoint(viewport_offset);
+                        let view = self.compute_view(&window_size);
+                        let time = view.find_grid_time(&position, TIME_SELECTION_STEP);
+                        if let Some(time_selection) = self.calendar.time_selection.as_mut() {
+                            time_selection.cursor = time.minutes;
+                        }
+                    }
+                }
+                // The day view switches the day rather than the week.
+                SubtractWeek if self.calendar.focused_day.is_some() => {
+                    self.calendar.shift_focused_day(-1)
+                }
+                AddWeek if self.calendar.focused_day.is_some() => {
+                    self.calendar.shift_focused_day(1)
                 }
                 SubtractWeek => self.calendar.subtract_week(),
                 AddWeek => self.calendar.add_week(),
+                AgendaChanged => self.calendar.is_agenda_changed = true,
+                Retry => {
+                    if let CalendarState::Failed { .. } = self.calendar.state {
+                        self.calendar.is_agenda_changed = true;
+                    }
+                }
+                modification @ (DeleteEvent | DuplicateEvent) => {
+                    let Some((index, kind)) = self.find_event_under_cursor(&window_size) else {
+                        continue;
+                    };
+
+                    let modification = match modification {
+                        DeleteEvent => EventModification::Delete,
+                        DuplicateEvent => EventModification::Duplicate,
+                        _ => unreachable!(),
+                    };
+                    let leaves_event_view = false;
+                    self.modify_event(frontend, index, &kind, modification, leaves_event_view)?;
+                }
+                ToggleMonthView => {
+                    // The month is the one which holds the middle day of the shown ones.
+                    let date = self
+                        .calendar
+                        .week_start
+                        .add_days(self.calendar.day_count as i16 / 2);
+                    self.request_month(frontend, &date);
+                    return self.create_month_view_render_data(frontend, window_size, Vec::new());
+                }
+                ToggleYearView => {
+                    let date = self
+                        .calendar
+                        .week_start
+                        .add_days(self.calendar.day_count as i16 / 2);
+                    self.request_year(frontend, &date);
+                    return self.create_year_view_render_data(frontend, window_size, Vec::new());
+                }
+                ToggleAgendaView => {
+                    let today = frontend.get_today()?;
+                    self.request_agenda(frontend, today);
+                    return self.create_agenda_view_render_data(frontend, window_size, Vec::new());
+                }
+                Filter => {
+                    let filter = self.calendar.filter.get_or_insert_with(|| EventFilter {
+                        query: String::new(),
+                        is_typing: true,
+                    });
+                    filter.is_typing = true;
+                    is_filter_changed = true;
+                }
+                Today => {
+                    self.calendar.now = frontend.get_now()?;
+                    let (today, time) = self.calendar.now.clone();
+                    self.calendar.show_date(&today);
+                    // The day view shows today.
+                    if self.calendar.focused_day.is_some() {
+                        let day = today.subtract(&self.calendar.week_start);
+                        self.calendar.focused_day = Some(day as u8);
+                    }
+                    let long_event_surface_height = self.compute_long_event_height();
+                    self.ui
+                        .reveal_time(&time, long_event_surface_height, &window_size);
+                    self.calendar.request_render();
+                }
+                ClockTick => self.calendar.now = frontend.get_now()?,
+                GoToDate => {
+                    self.calendar.date_prompt = Some(DatePrompt {
+                        input: String::new(),
+                        is_failed: false,
+                    });
+                    is_prompt_changed = true;
+                }
+                Search => {
+                    self.search_view = Some(SearchView::new());
+                    return self.create_search_view_render_data(frontend, window_size, Vec::new());
+                }
                 MouseButtonDown {
                     position: mouse_position,
                     button: MouseButton::Left,
@@ -799,6 +1663,10 @@ impl<F: Frontend> App<F> {
                     let long_event_surface = self.compute_long_event_surface(&window_size);
                     let mouse_click =
                         try_register_mouse_click(mouse_position, &long_event_surface, &window_size);
+                    let short_event_surface_position: Option<FPoint> = mouse_click
+                        .as_ref()
+                        .filter(|click| !click.event_kind.is_long())
+                        .map(|click| click.position);
 
                     // This is "tagging" of the clicked event.  The saved information allows us to:
                     // 1. Change the color of the event based on the cursor position.  The cursor
@@ -870,6 +1738,24 @@ impl<F: Frontend> App<F> {
                                 .adjust(&clicked_event.color_diff),
                         );
                     }
+
+                    // The click on the empty space of the short event surface starts the
+                    // selection of the time for a new event.
+                    self.calendar.time_selection = match (
+                        self.calendar.clicked_event.as_ref(),
+                        short_event_surface_position,
+                    ) {
+                        (None, Some(position)) => {
+                            let view = self.compute_view(&window_size);
+                            let time = view.find_grid_time(&position, TIME_SELECTION_STEP);
+                            Some(TimeSelection {
+                                day: time.day,
+                                anchor: time.minutes,
+                                cursor: time.minutes,
+                            })
+                        }
+                        _ => None,
+                    };
                 }
                 _ => (),
             }
@@ -886,57 +1772,51 @@ impl<F: Frontend> App<F> {
                 position,
             } = mouse_click;
             let rectangles: EventRectangles = self.calendar.state.obtain_events();
-            let (is_long, rectangles): (_, _) = match event_kind {
-                CalendarEventKind::Long => (true, rectangles.long),
-                CalendarEventKind::Short => (false, rectangles.short),
+            let rectangles = match event_kind {
+                CalendarEventKind::Long => rectangles.long,
+                CalendarEventKind::Short => rectangles.short,
             };
 
-            find_clicked_event(&position, rectangles).and_then(|event: usize| {
-                let table = self.calendar.state.get_event_table(is_long)?;
-                let title = table.obtain_title(event as u32)?;
-                let range = table.obtain_range(event as u32)?;
-                let description = table.obtain_description(event as u32)?;
-                let url = table.obtain_url(event as u32)?;
-                let location = table.obtain_location(event as u32)?;
-                let calendar_name = table.obtain_calendar(event as u32)?;
-                Some(EventDetails {
-                    title,
-                    description,
-                    event_kind,
-                    // FIXME(alex): make a special type for the indexes of events.
-                    index: event as u32,
-                    range,
-                    url,
-                    location,
-                    calendar_name,
-                })
-            })
+            find_clicked_event(&position, rectangles)
+                .and_then(|event: usize| self.find_event_details(event as u32, event_kind))
         });
 
-        match maybe_clicked_event {
+        // The click on a calendar event takes precedence over the selected time.
+        let maybe_details_view: Option<EventDetailsView> = match maybe_clicked_event {
             Some(event_details) => {
                 let form_field_content_registry: &RefCell<F::TextObjectRegistry> =
                     frontend.get_event_details_text_object_regirsty();
                 let form_field_label_registry: &RefCell<F::TextTextureRegistry> =
                     frontend.get_event_details_field_label_regirsty();
-                self.event_details_view = Activities::<F>::create_event_details_text_objects(
+                Activities::<F>::create_event_details_text_objects(
                     event_details,
                     &window_size,
                     &mut form_field_content_registry.borrow_mut(),
                     &mut form_field_label_registry.borrow_mut(),
                     Color::WHITE,
                 )?
-                .into();
+                .into()
+            }
+            None => match new_event_range {
+                Some(range) => match self.obtain_calendars(frontend) {
+                    Some(calendars) => Activities::create_event_draft_view(
+                        frontend,
+                        range,
+                        calendars,
+                        &window_size,
+                    )?,
+                    None => None,
+                },
+                None => self.open_pending_match(frontend, &window_size)?,
+            },
+        };
 
+        match maybe_details_view {
+            Some(details_view) => {
+                self.dismiss_failure(frontend);
+                self.event_details_view = Some(details_view);
                 if let Some(view) = self.event_details_view.as_ref() {
-                    let calendar_color = self
-                        .calendar
-                        .state
-                        .get_event_table(view.event_kind.is_long())
-                        .and_then(|table| table.calendar_colors.get(view.event_index as usize))
-                        .expect(
-                            "fail to get the event table.  An invalid data within the EventDetailsView?",
-                        );
+                    let calendar_color = obtain_calendar_color(&self.calendar.state, view);
 
                     Ok(NewState {
                         activity: Activity::EventView,
@@ -955,6 +1835,7 @@ impl<F: Frontend> App<F> {
                             calendar_color_rectangle: view.calendar_color_rectangle,
                             calendar_color_border: Color::WHITE,
                             calendar_base_rectangle: view.calendar_base_rectangle,
+                            banner: None,
                         }),
                     })
                 } else {
@@ -962,20 +1843,38 @@ impl<F: Frontend> App<F> {
                 }
             }
             None => {
-                // If the user switches the week, the events for the week are requested from Khal.
-                if self.calendar.is_week_switched {
-                    self.calendar.update_week_data(frontend)?;
-                    let cell_width = DUMB_CELL_WIDTH;
-                    frontend.get_dates_text_registry().clear();
-                    App::create_dates_text_objects(
-                        frontend,
-                        cell_width,
-                        &self.calendar.week_start,
-                    )?;
-                }
+                self.update_week(frontend)?;
 
                 // The events has been delivered, get ready to render them!
                 self.calendar.get_rendering(frontend);
+                if std::mem::take(&mut self.calendar.is_failure_changed) {
+                    frontend.get_banner_text_registry().clear();
+                }
+
+                // The failure of the agenda source hides the failure of the last change.
+                let banner: Option<FRect> = match (&self.calendar.state, &self.failure) {
+                    (CalendarState::Failed { error }, failure) => {
+                        if failure.is_some() {
+                            self.failure = None;
+                            frontend.get_banner_text_registry().clear();
+                        }
+
+                        Some(Self::create_banner_text_object(
+                            frontend,
+                            format_args!("{error}. Press R to retry."),
+                            &window_size,
+                        )?)
+                    }
+                    (_, Some(failure)) => Some(Self::create_banner_text_object(
+                        frontend,
+                        format_args!("{failure}. Press Escape to dismiss."),
+                        &window_size,
+                    )?),
+                    (_, None) => {
+                        frontend.get_banner_text_registry().clear();
+                        None
+                    }
+                };
 
                 let long_event_clash_size: calendar::Lane =
                     self.calendar.state.get_long_event_clash_size();
@@ -1004,6 +1903,8 @@ impl<F: Frontend> App<F> {
                     &self.ui.adjustment,
                     &window_size,
                     long_event_surface_height,
+                    self.calendar.day_count,
+                    self.calendar.focused_day,
                 );
 
                 let hours_viewport = Rect {
@@ -1033,6 +1934,8 @@ impl<F: Frontend> App<F> {
                     self.ui.title_font_height,
                     long_event_clash_size,
                 );
+                // The rectangles created by the transition get the colors of their calendars.
+                let is_laid_out = matches!(self.calendar.state, CalendarState::Ready { .. });
                 self.calendar.get_ready(
                     &view,
                     top_panel_height,
@@ -1040,7 +1943,20 @@ impl<F: Frontend> App<F> {
                     &self.ui.event_title_offset,
                     &self.ui.event_offset,
                 )?;
+                if is_filter_changed || !is_laid_out {
+                    self.calendar.apply_filter();
+                }
 
+                if is_filter_changed || is_prompt_changed {
+                    frontend.get_prompt_text_registry().clear();
+                }
+                let prompt_bar: Option<FRect> =
+                    self.create_prompt_text_object(frontend, &window_size)?;
+
+                let now_line: Option<FRect> = self.compute_now_line(&view);
+                let (today_column, weekend_columns) =
+                    self.compute_day_columns(&view, &short_event_viewport, &window_size);
+                let mini_month: Option<MiniMonthRenderData> = self.create_mini_month_render_data();
                 let rectangles: EventRectangles = self.calendar.state.obtain_events();
                 let horizontal_offset = self.ui.event_offset.x as i32;
                 let dates_viewport = Rect {
@@ -1050,41 +1966,1493 @@ impl<F: Frontend> App<F> {
                     h: 200,
                 };
 
-                let render_data = WeekViewRenderData {
-                    view,
-                    long_event_rectangles: rectangles.long,
-                    hours_viewport,
-                    dates_viewport,
-                    short_event_rectangles: rectangles.short,
-                    event_viewport: short_event_viewport,
-                    frontend,
-                };
+                let time_selection: Option<FRect> =
+                    self.calendar.time_selection.as_ref().map(|selection| {
+                        let (start, end) = selection.minutes();
+                        view.compute_time_range_rectangle(selection.day, start, end)
+                    });
+
+                let long_event_viewport = {
+                    let surface = self.compute_long_event_surface(&window_size);
+                    Rect {
+                        x: surface.offset.x as i32,
+                        y: surface.offset.y as i32,
+                        w: surface.size.x as i32,
+                        h: surface.size.y as i32,
+                    }
+                };
+
+                let render_data = WeekViewRenderData {
+                    view,
+                    time_selection,
+                    banner,
+                    prompt_bar,
+                    now_line,
+                    today_column,
+                    weekend_columns,
+                    mini_month,
+                    long_event_viewport,
+                    long_event_rectangles: rectangles.long,
+                    hours_viewport,
+                    dates_viewport,
+                    short_event_rectangles: rectangles.short,
+                    event_viewport: short_event_viewport,
+                    frontend,
+                };
+
+                Ok(NewState {
+                    activity: Activity::WeekView,
+                    render_data: RenderData::WeekView(render_data),
+                })
+            }
+        }
+    }
+
+    /// The calendars for the new event.  `None` if they are not loaded yet or if they have failed
+    /// to load.  Either is told by the banner.  The calendars which have failed are requested
+    /// again.
+    fn obtain_calendars(&mut self, frontend: &mut F) -> Option<Vec<WritableCalendar>> {
+        if let WritableCalendars::Loading(pending) = &self.calendars
+            && let Some(result) = frontend.agenda_writer().poll(pending)
+        {
+            self.calendars = match result {
+                Ok(calendars) => WritableCalendars::Loaded(calendars),
+                Err(error) => WritableCalendars::Failed(error),
+            };
+        }
+
+        let failure = match &self.calendars {
+            WritableCalendars::Loaded(calendars) if calendars.is_empty() => {
+                String::from(captions::failure::NO_CALENDAR)
+            }
+            WritableCalendars::Loaded(calendars) => return Some(calendars.clone()),
+            WritableCalendars::Loading(_) => String::from(captions::failure::CALENDARS_ARE_LOADING),
+            WritableCalendars::Failed(error) => {
+                let failure = format!("{}: {error}", captions::failure::CALENDARS);
+                self.calendars = WritableCalendars::request(frontend);
+                failure
+            }
+        };
+
+        self.tell_failure(frontend, failure);
+        None
+    }
+
+    /// Tells `failure` by the banner instead of the one told already.
+    fn tell_failure(&mut self, frontend: &mut F, failure: String) {
+        self.failure = Some(failure);
+        frontend.get_banner_text_registry().clear();
+    }
+
+    fn dismiss_failure(&mut self, frontend: &mut F) {
+        if self.failure.take().is_some() {
+            frontend.get_banner_text_registry().clear();
+        }
+    }
+
+    /// Creates the text telling about the failure unless it's created already.  Returns the
+    /// rectangle of the banner which holds the text.
+    fn create_banner_text_object(
+        frontend: &mut F,
+        text: impl core::fmt::Display,
+        window_size: &Point,
+    ) -> Result<FRect, F::Error> {
+        let registry = frontend.get_banner_text_registry();
+        if registry.get_positions().is_empty() {
+            let position = FRect {
+                x: BANNER_PADDING,
+                y: BANNER_PADDING,
+                w: window_size.x as f32 - BANNER_PADDING * 2.,
+                h: window_size.y as f32,
+            };
+            registry.create(text.to_string(), Color::WHITE, position)?;
+        }
+
+        let text_height = registry.get_positions().first().map_or(0., |text| text.h);
+        Ok(FRect {
+            x: 0.,
+            y: 0.,
+            w: window_size.x as f32,
+            h: text_height + BANNER_PADDING * 2.,
+        })
+    }
+
+    /// The columns of today and of the weekend which are shaded in the week view.  They go from the
+    /// top to the bottom of the window and they're cut by `viewport` of the short events, e.g. in
+    /// the day view.  The weekend doesn't include today.
+    fn compute_day_columns(
+        &self,
+        view: &View,
+        viewport: &Rect,
+        window_size: &Point,
+    ) -> (Option<FRect>, Vec<FRect>) {
+        use calendar::date::Weekday;
+        let week_start = &self.calendar.week_start;
+        let day_count = self.calendar.day_count;
+        let column = |day: usize| -> Option<FRect> {
+            let viewport_left = viewport.x as f32;
+            let viewport_right = (viewport.x + viewport.w) as f32;
+            let left = viewport_left + view.short_event_surface.x + day as f32 * view.cell_width;
+            let right = (left + view.cell_width).min(viewport_right);
+            let left = left.max(viewport_left);
+            (left < right).then_some(FRect {
+                x: left,
+                y: 0.,
+                w: right - left,
+                h: window_size.y as f32,
+            })
+        };
+
+        let today = self.calendar.now.0.subtract(week_start);
+        let today_column = usize::try_from(today)
+            .ok()
+            .filter(|today| *today < day_count as usize)
+            .and_then(column);
+        let weekend_columns = DateStream::new(week_start.clone())
+            .take(day_count as usize)
+            .enumerate()
+            .filter(|(day, date)| {
+                let is_weekend = matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday);
+                is_weekend && *day as i32 != today
+            })
+            .filter_map(|(day, _)| column(day))
+            .collect();
+        (today_column, weekend_columns)
+    }
+
+    /// The compact month in the top-left corner of the week view.  It's the month of the middle
+    /// shown day.  `None` if the corner is too small for it.
+    fn create_mini_month(&self) -> Option<calendar::render::MiniMonth> {
+        use calendar::render::MiniMonth;
+        let month_start = self
+            .calendar
+            .week_start
+            .add_days(self.calendar.day_count as i16 / 2)
+            .month_start();
+        let first_weekday = self.calendar.first_weekday;
+        let week_count = MiniMonth::week_count(&month_start, first_weekday);
+        let cell_width = (self.ui.event_offset.x - MINI_MONTH_PADDING * 2.) / 7.;
+        let cell_height = (self.ui.event_offset.y - MINI_MONTH_PADDING * 2.) / week_count as f32;
+        let is_fit = cell_width >= MINI_MONTH_MIN_CELL && cell_height >= MINI_MONTH_MIN_CELL;
+        is_fit.then_some(MiniMonth {
+            month_start,
+            first_weekday,
+            cell_width,
+            cell_height,
+            offset_x: MINI_MONTH_PADDING,
+            offset_y: MINI_MONTH_PADDING,
+        })
+    }
+
+    /// The cells of the mini-month split by the shown days and the rest.  The cells are narrowed
+    /// to leave a gap between them.
+    fn create_mini_month_render_data(&self) -> Option<MiniMonthRenderData> {
+        let month = self.create_mini_month()?;
+        let shown_start = &self.calendar.week_start;
+        let shown_end = shown_start.add_days(self.calendar.day_count as i16);
+        let today = &self.calendar.now.0;
+        let mut data = MiniMonthRenderData {
+            days: Vec::new(),
+            shown_days: Vec::new(),
+            today: None,
+        };
+
+        for (date, cell) in month.cells() {
+            let cell = FRect {
+                x: cell.x,
+                y: cell.y,
+                w: cell.w - 1.,
+                h: cell.h - 1.,
+            };
+            if date == *today {
+                data.today = Some(cell);
+            }
+
+            if *shown_start <= date && date < shown_end {
+                data.shown_days.push(cell);
+            } else {
+                data.days.push(cell);
+            }
+        }
+
+        Some(data)
+    }
+
+    /// The line across the column of today at the current time.  It's relative to the viewport of
+    /// the short events.  `None` if today is not shown.
+    fn compute_now_line(&self, view: &View) -> Option<FRect> {
+        let (today, time) = &self.calendar.now;
+        let day = today.subtract(&self.calendar.week_start);
+        let is_shown = match self.calendar.focused_day {
+            Some(focused_day) => day == focused_day as i32,
+            None => (0..self.calendar.day_count as i32).contains(&day),
+        };
+        if !is_shown {
+            return None;
+        }
+
+        let minutes = time.minutes_from_midnight();
+        let line = view.compute_time_range_rectangle(day as u8, minutes, minutes);
+        Some(FRect {
+            y: line.y - NOW_LINE_HEIGHT / 2.,
+            h: NOW_LINE_HEIGHT,
+            ..line
+        })
+    }
+
+    /// Creates the text of the prompt bar unless it's created already.  The bar tells either the
+    /// input of the date prompt or the query of the filter.  The text ends with a cursor while
+    /// it's typed.  Returns the rectangle of the bar which holds the text.  `None` if neither is
+    /// open.
+    fn create_prompt_text_object(
+        &self,
+        frontend: &mut F,
+        window_size: &Point,
+    ) -> Result<Option<FRect>, F::Error> {
+        let text = match (&self.calendar.date_prompt, &self.calendar.filter) {
+            (Some(prompt), _) if prompt.is_failed => {
+                use captions::date_prompt::{IS_NOT_DATE, PROMPT};
+                format!("{PROMPT} {}  {IS_NOT_DATE}", prompt.input)
+            }
+            (Some(prompt), _) => format!("{} {}_", captions::date_prompt::PROMPT, prompt.input),
+            (None, Some(filter)) => {
+                let cursor = if filter.is_typing { "_" } else { "" };
+                format!("{} {}{cursor}", captions::filter::PROMPT, filter.query)
+            }
+            (None, None) => {
+                frontend.get_prompt_text_registry().clear();
+                return Ok(None);
+            }
+        };
+
+        let text_height = self.ui.title_font_height as f32;
+        let height = text_height + BANNER_PADDING * 2.;
+        let bar = FRect {
+            x: 0.,
+            y: window_size.y as f32 - height,
+            w: window_size.x as f32,
+            h: height,
+        };
+
+        let registry = frontend.get_prompt_text_registry();
+        if registry.get_positions().is_empty() {
+            let position = FRect {
+                x: BANNER_PADDING,
+                y: bar.y + BANNER_PADDING,
+                w: bar.w - BANNER_PADDING * 2.,
+                h: text_height,
+            };
+            registry.create(text, Color::WHITE, position)?;
+        }
+
+        Ok(Some(bar))
+    }
+
+    /// The fields of the `event` from the table of the week.
+    fn find_event_details(&self, event: u32, kind: CalendarEventKind) -> Option<EventDetails<'_>> {
+        let table = self.calendar.state.get_event_table(kind.is_long())?;
+        Some(EventDetails {
+            title: table.obtain_title(event)?,
+            description: table.obtain_description(event)?,
+            // FIXME(alex): make a special type for the indexes of events.
+            origin: EventOrigin::Table { kind, index: event },
+            // The event is shown as it's stored rather than cropped.  Given that, the range which
+            // is written on saving is not cropped either.
+            range: table.obtain_original_range(event)?,
+            url: table.obtain_url(event)?,
+            location: table.obtain_location(event)?,
+            calendar_name: table.obtain_calendar(event)?,
+        })
+    }
+
+    /// The event under the mouse cursor in the week view.
+    fn find_event_under_cursor(&self, window_size: &Point) -> Option<(u32, CalendarEventKind)> {
+        let long_event_surface = self.compute_long_event_surface(window_size);
+        let MouseEventClick {
+            event_kind,
+            position,
+        } = try_register_mouse_click(self.ui.mouse_position, &long_event_surface, window_size)?;
+        let rectangles: EventRectangles = self.calendar.state.obtain_events();
+        let rectangles = match event_kind {
+            CalendarEventKind::Long => rectangles.long,
+            CalendarEventKind::Short => rectangles.short,
+        };
+
+        find_clicked_event(&position, rectangles).map(|index| (index as u32, event_kind))
+    }
+
+    /// Starts storing the event shown by [`EventDetailsView`].  Returns the failure told to the
+    /// user.
+    fn save_event(&self, frontend: &F) -> Result<PendingWrite<F>, String> {
+        let Some(view) = self.event_details_view.as_ref() else {
+            return Err(String::from(captions::failure::EVENT_IS_NOT_FOUND));
+        };
+
+        let text = |index: usize| view.texts.get(index).map(AsRef::as_ref).unwrap_or_default();
+
+        let Some(range) = parse_event_range(text(FROM_TEXT_INDEX), text(UNTIL_TEXT_INDEX)) else {
+            return Err(String::from(captions::failure::INVALID_TIME));
+        };
+
+        let started = match &view.origin {
+            EventOrigin::Draft(draft) => {
+                let Some(calendar) = draft.calendars.get(draft.calendar) else {
+                    return Err(String::from(captions::failure::NO_CALENDAR));
+                };
+
+                let event = NewEvent {
+                    calendar: &calendar.name,
+                    title: text(TITLE_TEXT_INDEX),
+                    description: text(DESCRIPTION_TEXT_INDEX),
+                    location: text(LOCATION_TEXT_INDEX),
+                    url: text(URL_TEXT_INDEX),
+                    range: &range,
+                    is_all_day: false,
+                };
+
+                frontend.agenda_writer().create(&event)
+            }
+            EventOrigin::Table { index, kind } => {
+                let Some(table) = self.calendar.state.get_event_table(kind.is_long()) else {
+                    return Err(String::from(captions::failure::EVENT_IS_NOT_FOUND));
+                };
+
+                let Some(uid) = table.obtain_uid(*index).filter(|uid| !uid.is_empty()) else {
+                    return Err(String::from(captions::failure::NO_UID));
+                };
+
+                // The time is written only if the user has changed it.  Otherwise, the original
+                // time of the event is kept as it is, e.g. its zone.
+                let is_moved = table.obtain_original_range(*index).is_none_or(|original| {
+                    let from = format_date_time(&original.start_date, &original.start_time);
+                    let until = format_date_time(&original.end_date, &original.end_time);
+                    text(FROM_TEXT_INDEX) != from || text(UNTIL_TEXT_INDEX) != until
+                });
+
+                let event = EventUpdate {
+                    uid,
+                    calendar: table.obtain_calendar(*index).unwrap_or_default(),
+                    title: text(TITLE_TEXT_INDEX),
+                    description: text(DESCRIPTION_TEXT_INDEX),
+                    location: text(LOCATION_TEXT_INDEX),
+                    url: text(URL_TEXT_INDEX),
+                    range: is_moved.then_some(&range),
+                };
+
+                frontend.agenda_writer().update(&event)
+            }
+        };
+
+        started.map_err(|error| format!("{}: {error}", captions::failure::SAVE))
+    }
+
+    /// Starts deleting or duplicating the event.  The failure of the change is told by the banner.
+    /// See [`PendingChange::leaves_event_view`].
+    fn modify_event(
+        &mut self,
+        frontend: &mut F,
+        index: u32,
+        kind: &CalendarEventKind,
+        modification: EventModification,
+        leaves_event_view: bool,
+    ) -> Result<(), F::Error> {
+        if self.pending_change.is_some() {
+            let failure = String::from(captions::failure::CHANGE_IS_PENDING);
+            self.tell_failure(frontend, failure);
+            return Ok(());
+        }
+
+        let failure = match modification {
+            EventModification::Delete => captions::failure::DELETE,
+            EventModification::Duplicate => captions::failure::DUPLICATE,
+        };
+        let started: Result<PendingWrite<F>, String> = 'write: {
+            let Some(table) = self.calendar.state.get_event_table(kind.is_long()) else {
+                return Ok(());
+            };
+
+            let Some(uid) = table.obtain_uid(index).filter(|uid| !uid.is_empty()) else {
+                break 'write Err(String::from(captions::failure::NO_UID));
+            };
+
+            let calendar = table.obtain_calendar(index).unwrap_or_default();
+            let title = table.obtain_title(index).unwrap_or_default();
+            match modification {
+                EventModification::Delete => {
+                    use captions::confirmation::{ALL_OCCURRENCES, DELETE_EVENT};
+                    let message = format!("{DELETE_EVENT} \"{title}\"?  {ALL_OCCURRENCES}");
+                    if !frontend.confirm(&message)? {
+                        return Ok(());
+                    }
+
+                    frontend.agenda_writer().delete(uid, calendar)
+                }
+                EventModification::Duplicate => {
+                    let Some(range) = table.obtain_original_range(index) else {
+                        return Ok(());
+                    };
+
+                    let range = calendar::EventRange {
+                        start_date: range.start_date.add_week(),
+                        start_time: range.start_time.clone(),
+                        end_date: range.end_date.add_week(),
+                        end_time: range.end_time.clone(),
+                    };
+
+                    let event = NewEvent {
+                        calendar,
+                        title,
+                        description: table.obtain_description(index).unwrap_or_default(),
+                        location: table.obtain_location(index).unwrap_or_default(),
+                        url: table.obtain_url(index).unwrap_or_default(),
+                        range: &range,
+                        is_all_day: table.is_all_day(index),
+                    };
+                    frontend.agenda_writer().create(&event)
+                }
+            }
+            .map_err(|error| format!("{failure}: {error}"))
+        };
+
+        match started {
+            Ok(pending) => {
+                self.pending_change = Some(PendingChange {
+                    pending,
+                    failure,
+                    leaves_event_view,
+                })
+            }
+            Err(failure) => self.tell_failure(frontend, failure),
+        }
+
+        Ok(())
+    }
+
+    /// Takes the result of the change being written.  The written change is shown by requesting
+    /// the week again.  The failed change is told by the banner.  Returns whether the event view is
+    /// to be left.
+    fn receive_change(&mut self, frontend: &mut F) -> Result<bool, F::Error> {
+        let Some(written) = self
+            .pending_change
+            .as_ref()
+            .and_then(|change| frontend.agenda_writer().poll(&change.pending))
+        else {
+            return Ok(false);
+        };
+
+        let Some(change) = self.pending_change.take() else {
+            unreachable!("the change is polled right above")
+        };
+
+        match written {
+            Ok(()) => {
+                self.calendar.update_week_data(frontend)?;
+                if let Some(view) = self.agenda_view.as_ref() {
+                    let start = view.start.clone();
+                    self.request_agenda(frontend, start);
+                }
+                Ok(change.leaves_event_view)
+            }
+            Err(error) => {
+                self.tell_failure(frontend, format!("{}: {error}", change.failure));
+                Ok(false)
+            }
+        }
+    }
+
+    pub fn get_root_activity(&self) -> Activity {
+        Activity::WeekView
+    }
+
+    /// Whether the typed text is taken by the `activity`: a field of the event view has the
+    /// cursor, the search view is open, or the date prompt or the filter of the week view is
+    /// typed.  See [`Action::TextInput`].
+    pub fn takes_text_input(&self, activity: &Activity) -> bool {
+        match activity {
+            Activity::EventView => self
+                .event_details_view
+                .as_ref()
+                .is_some_and(|view| view.selection_highlight.is_some()),
+            Activity::SearchView => self.search_view.is_some(),
+            Activity::WeekView => {
+                let is_filter_typed = self.calendar.filter.as_ref().is_some_and(|f| f.is_typing);
+                self.calendar.date_prompt.is_some() || is_filter_typed
+            }
+            Activity::MonthView | Activity::AgendaView | Activity::YearView => false,
+        }
+    }
+
+    // main function
+    pub fn create_render_data<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        activity: Activity,
+        frontend: &'frontend mut F,
+        window_size: Point,
+        events: impl IntoIterator<Item = Action>,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        if self.receive_change(frontend)? && matches!(activity, Activity::EventView) {
+            self.event_details_view = None;
+            return self.leave_event_view(frontend, window_size);
+        }
+
+        match activity {
+            Activity::WeekView => self.create_week_view_render_data(frontend, window_size, events),
+            Activity::EventView => {
+                self.create_event_view_render_data(frontend, window_size, events)
+            }
+            Activity::MonthView => {
+                self.create_month_view_render_data(frontend, window_size, events)
+            }
+            Activity::AgendaView => {
+                self.create_agenda_view_render_data(frontend, window_size, events)
+            }
+            Activity::YearView => self.create_year_view_render_data(frontend, window_size, events),
+            Activity::SearchView => {
+                self.create_search_view_render_data(frontend, window_size, events)
+            }
+        }
+    }
+
+    /// Replaces the agenda view by the one of the days which start on `start`.
+    fn request_agenda(&mut self, frontend: &F, start: calendar::date::Date) {
+        let src = frontend.agenda_source();
+        if let Some(view) = self.agenda_view.take() {
+            view.release(src);
+        }
+
+        self.agenda_view = Some(AgendaView::request(src, start));
+    }
+
+    /// Replaces the month view by the one of the month which holds `date`.
+    fn request_month(&mut self, frontend: &F, date: &calendar::date::Date) {
+        let src = frontend.agenda_source();
+        if let Some(view) = self.month_view.take() {
+            view.release(src);
+        }
+
+        self.month_view = Some(MonthView::request(src, date, self.calendar.first_weekday));
+    }
+
+    /// Leaves the month view for the week view.
+    fn close_month<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        if let Some(view) = self.month_view.take() {
+            view.release(frontend.agenda_source());
+        }
+
+        frontend.get_month_text_registry().clear();
+        frontend.get_banner_text_registry().clear();
+        self.calendar.request_render();
+        self.create_week_view_render_data(frontend, window_size, Vec::new())
+    }
+
+    fn compute_month_arguments(&self, window_size: &Point) -> calendar::render::MonthArguments {
+        calendar::render::MonthArguments {
+            cell_width: window_size.x as f32 / 7.,
+            cell_height: (window_size.y as f32 - MONTH_HEADER_HEIGHT)
+                / calendar::render::MONTH_WEEKS as f32,
+            offset_x: 0.,
+            offset_y: MONTH_HEADER_HEIGHT,
+            line_height: self.ui.title_font_height as f32 + MONTH_LINE_PADDING,
+        }
+    }
+
+    /// The month view shows six weeks.  PageUp and PageDown switch the month rather than the
+    /// week.  The click on a day opens the week of the day.
+    fn create_month_view_render_data<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+        events: impl IntoIterator<Item = Action>,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        let arguments = self.compute_month_arguments(&window_size);
+        for event in events {
+            let Some(view) = self.month_view.as_mut() else {
+                break;
+            };
+
+            match event {
+                Action::Escape | Action::ToggleMonthView => {
+                    return self.close_month(frontend, window_size);
+                }
+                Action::WindowResize => {
+                    frontend.get_banner_text_registry().clear();
+                    view.layout = None;
+                }
+                month @ (Action::SubtractWeek | Action::AddWeek) => {
+                    let months = if let Action::AddWeek = month { 1 } else { -1 };
+                    let date = view.month_start.add_months(months);
+                    frontend.get_banner_text_registry().clear();
+                    self.request_month(frontend, &date);
+                }
+                Action::Retry => {
+                    if let RangeRequest::Failed { .. } = view.state {
+                        let date = view.month_start.clone();
+                        frontend.get_banner_text_registry().clear();
+                        self.request_month(frontend, &date);
+                    }
+                }
+                Action::AgendaChanged => {
+                    let date = view.month_start.clone();
+                    self.calendar.is_agenda_changed = true;
+                    self.request_month(frontend, &date);
+                }
+                Action::MouseMove { x, y, .. } => self.ui.mouse_position = FPoint { x, y },
+                Action::MouseButtonUp { position } => {
+                    let Some(day) = arguments.find_cell(&position) else {
+                        continue;
+                    };
+
+                    let date = view.date(day);
+                    let week_start = self.calendar.range_start(&date);
+                    if week_start != self.calendar.week_start {
+                        self.calendar.week_start = week_start;
+                        self.calendar.is_week_switched = true;
+                    }
+                    return self.close_month(frontend, window_size);
+                }
+                _ => (),
+            }
+        }
+
+        // NOTE(alex): the view is checked before it's borrowed.  The borrow checker rejects
+        // leaving the view from the `else` branch of the borrow which is returned below.
+        if self.month_view.is_none() {
+            return self.close_month(frontend, window_size);
+        }
+
+        let Some(view) = self.month_view.as_mut() else {
+            unreachable!("the month view is checked right above");
+        };
+
+        view.receive(frontend.agenda_source());
+        let banner: Option<FRect> = match &view.state {
+            RangeRequest::Failed { error } => Some(Self::create_banner_text_object(
+                frontend,
+                format_args!("{error}. Press R to retry."),
+                &window_size,
+            )?),
+            _ => {
+                frontend.get_banner_text_registry().clear();
+                None
+            }
+        };
+
+        if view.layout.is_none() {
+            let empty = Box::default();
+            let agenda = view.state.data().unwrap_or(&empty);
+            let layout = calendar::render::month_layout(
+                &agenda.long,
+                &agenda.short,
+                &view.grid_start,
+                &arguments,
+            );
+            Self::create_month_text_objects(
+                frontend,
+                view,
+                agenda,
+                &layout,
+                &arguments,
+                &self.ui.event_title_offset,
+            )?;
+            view.layout = Some(layout);
+        }
+
+        let cells = (0..calendar::render::MONTH_DAYS)
+            .map(|day| arguments.cell(day))
+            .collect();
+        let (bars, lines): (&[_], &[_]) = match view.layout.as_ref() {
+            Some(layout) => (&layout.bars, &layout.lines),
+            None => (&[], &[]),
+        };
+
+        Ok(NewState {
+            activity: Activity::MonthView,
+            render_data: RenderData::MonthView(MonthViewRenderData {
+                frontend,
+                cells,
+                bars,
+                lines,
+                banner,
+            }),
+        })
+    }
+
+    /// The name of the month, the names of the days, the dates of the grid, the titles of the
+    /// events and the numbers of the hidden events.
+    fn create_month_text_objects(
+        frontend: &mut F,
+        view: &MonthView<<F::AgendaSource as AgendaSource>::RequestHandle, F::Error>,
+        agenda: &calendar::obtain::WeekScheduleWithLanes,
+        layout: &calendar::render::MonthLayout,
+        arguments: &calendar::render::MonthArguments,
+        event_title_offset: &FPoint,
+    ) -> Result<(), F::Error> {
+        let registry = frontend.get_month_text_registry();
+        registry.clear();
+        let padding = event_title_offset.x;
+        let width = arguments.cell_width * 7.;
+        let title = format!(
+            "{} {}",
+            view.month_start.month_name(),
+            view.month_start.year
+        );
+        let position = FRect {
+            x: padding,
+            y: 0.,
+            w: width - padding * 2.,
+            h: MONTH_TITLE_HEIGHT,
+        };
+        registry.create(title, Color::WHITE, position)?;
+
+        let dates =
+            DateStream::new(view.grid_start.clone()).take(calendar::render::MONTH_DAYS as usize);
+        for (day, date) in (0..).zip(dates) {
+            let cell = arguments.cell(day);
+            if day < 7 {
+                let position = FRect {
+                    x: cell.x + padding,
+                    y: MONTH_TITLE_HEIGHT,
+                    w: cell.w - padding * 2.,
+                    h: MONTH_HEADER_HEIGHT - MONTH_TITLE_HEIGHT,
+                };
+                registry.create(date.weekday().name(), Color::WHITE, position)?;
+            }
+
+            let color = if date.month == view.month_start.month {
+                Color::WHITE
+            } else {
+                Color::from_rgb(MONTH_OTHER_DATE_COLOR)
+            };
+            let position = FRect {
+                x: cell.x + padding,
+                y: cell.y,
+                w: cell.w - padding * 2.,
+                h: arguments.line_height,
+            };
+            registry.create(date.day.to_string(), color, position)?;
+        }
+
+        let entries = layout
+            .bars
+            .iter()
+            .map(|bar| (&agenda.long, bar))
+            .chain(layout.lines.iter().map(|line| (&agenda.short, line)));
+        for (table, entry) in entries {
+            let title = table.obtain_title(entry.event).unwrap_or_default();
+            let rectangle = &entry.rectangle;
+            let position = FRect {
+                x: rectangle.at.x + padding,
+                y: rectangle.at.y + MONTH_LINE_PADDING / 2.,
+                w: rectangle.size.x - padding * 2.,
+                h: rectangle.size.y - MONTH_LINE_PADDING / 2.,
+            };
+            registry.create(title, Color::BLACK, position)?;
+        }
+
+        for overflow in &layout.overflows {
+            let position = FRect {
+                x: overflow.at.x + padding,
+                y: overflow.at.y,
+                w: arguments.cell_width - padding * 2.,
+                h: arguments.line_height,
+            };
+            registry.create(format!("+{} more", overflow.count), Color::WHITE, position)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the year view by the one of the year which holds `date`.
+    fn request_year(&mut self, frontend: &F, date: &calendar::date::Date) {
+        let src = frontend.agenda_source();
+        if let Some(view) = self.year_view.take() {
+            view.release(src);
+        }
+
+        self.year_view = Some(YearView::request(src, date, self.calendar.first_weekday));
+    }
+
+    /// Leaves the year view for the week view.
+    fn close_year<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        if let Some(view) = self.year_view.take() {
+            view.release(frontend.agenda_source());
+        }
+
+        frontend.get_year_text_registry().clear();
+        frontend.get_banner_text_registry().clear();
+        self.calendar.request_render();
+        self.create_week_view_render_data(frontend, window_size, Vec::new())
+    }
+
+    fn compute_year_arguments(&self, window_size: &Point) -> calendar::render::YearArguments {
+        let row_count = (12 / calendar::render::YEAR_COLUMNS) as f32;
+        calendar::render::YearArguments {
+            month_width: (window_size.x as f32 - YEAR_PADDING * 2.)
+                / calendar::render::YEAR_COLUMNS as f32,
+            month_height: (window_size.y as f32 - MONTH_TITLE_HEIGHT - YEAR_PADDING) / row_count,
+            offset_x: YEAR_PADDING,
+            offset_y: MONTH_TITLE_HEIGHT,
+            title_height: self.ui.title_font_height as f32 + MONTH_LINE_PADDING,
+        }
+    }
+
+    /// The year view shades the days by the time taken by their events.  PageUp and PageDown
+    /// switch the year.  The click on a day opens the week of the day.
+    fn create_year_view_render_data<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+        events: impl IntoIterator<Item = Action>,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        let arguments = self.compute_year_arguments(&window_size);
+        for event in events {
+            let Some(view) = self.year_view.as_mut() else {
+                break;
+            };
+
+            match event {
+                Action::Escape | Action::ToggleYearView => {
+                    return self.close_year(frontend, window_size);
+                }
+                Action::WindowResize => {
+                    frontend.get_banner_text_registry().clear();
+                    view.cells = None;
+                }
+                year @ (Action::SubtractWeek | Action::AddWeek) => {
+                    let months = if let Action::AddWeek = year { 12 } else { -12 };
+                    let date = view.year_start.add_months(months);
+                    frontend.get_banner_text_registry().clear();
+                    self.request_year(frontend, &date);
+                }
+                Action::Retry => {
+                    if let RangeRequest::Failed { .. } = view.state {
+                        let date = view.year_start.clone();
+                        frontend.get_banner_text_registry().clear();
+                        self.request_year(frontend, &date);
+                    }
+                }
+                Action::AgendaChanged => {
+                    let date = view.year_start.clone();
+                    self.calendar.is_agenda_changed = true;
+                    self.request_year(frontend, &date);
+                }
+                Action::MouseMove { x, y, .. } => self.ui.mouse_position = FPoint { x, y },
+                Action::MouseButtonUp { position } => {
+                    let Some(date) = arguments
+                        .find_cell(&position)
+                        .and_then(|(month, day)| view.date(month, day))
+                    else {
+                        continue;
+                    };
+
+                    let week_start = self.calendar.range_start(&date);
+                    if week_start != self.calendar.week_start {
+                        self.calendar.week_start = week_start;
+                        self.calendar.is_week_switched = true;
+                    }
+                    return self.close_year(frontend, window_size);
+                }
+                _ => (),
+            }
+        }
+
+        // NOTE(alex): the view is checked before it's borrowed like in
+        // [`Self::create_month_view_render_data`].
+        if self.year_view.is_none() {
+            return self.close_year(frontend, window_size);
+        }
+
+        let Some(view) = self.year_view.as_mut() else {
+            unreachable!("the year view is checked right above");
+        };
+
+        view.receive(frontend.agenda_source());
+        let banner: Option<FRect> = match &view.state {
+            RangeRequest::Failed { error } => Some(Self::create_banner_text_object(
+                frontend,
+                format_args!("{error}. Press R to retry."),
+                &window_size,
+            )?),
+            _ => {
+                frontend.get_banner_text_registry().clear();
+                None
+            }
+        };
+
+        if view.cells.is_none() {
+            let cells = Self::create_year_text_objects(frontend, view, &arguments)?;
+            view.cells = Some(cells);
+        }
+
+        let busy_minutes = view.state.data().map_or(&[][..], Vec::as_slice);
+        let days = view
+            .cells
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(day, cell)| {
+                let minutes = busy_minutes.get(day).copied().unwrap_or_default();
+                let share = minutes.min(YEAR_BUSY_MINUTES) as f32 / YEAR_BUSY_MINUTES as f32;
+                (share, *cell)
+            })
+            .collect();
+
+        Ok(NewState {
+            activity: Activity::YearView,
+            render_data: RenderData::YearView(YearViewRenderData {
+                frontend,
+                days,
+                banner,
+            }),
+        })
+    }
+
+    /// The year, the names of the months and the numbers of the days.  Returns the cells of the
+    /// days of the year.
+    fn create_year_text_objects(
+        frontend: &mut F,
+        view: &YearView<<F::AgendaSource as AgendaSource>::RequestHandle, F::Error>,
+        arguments: &calendar::render::YearArguments,
+    ) -> Result<Vec<FRect>, F::Error> {
+        let registry = frontend.get_year_text_registry();
+        registry.clear();
+        let position = FRect {
+            x: YEAR_PADDING,
+            y: 0.,
+            w: arguments.month_width * calendar::render::YEAR_COLUMNS as f32,
+            h: MONTH_TITLE_HEIGHT,
+        };
+        registry.create(view.year_start.year.to_string(), Color::WHITE, position)?;
+
+        let mut cells: Vec<FRect> = Vec::new();
+        for month in 0..12 {
+            let origin = arguments.month(month);
+            let position = FRect {
+                h: arguments.title_height,
+                ..origin
+            };
+            let name = view.year_start.add_months(month as i16).month_name();
+            registry.create(name, Color::WHITE, position)?;
+
+            for day in 0..calendar::render::MONTH_DAYS {
+                let Some(date) = view.date(month, day) else {
+                    continue;
+                };
+
+                let cell = arguments.cell(month, day);
+                registry.create(date.day.to_string(), Color::WHITE, cell)?;
+                cells.push(cell);
+            }
+        }
+
+        Ok(cells)
+    }
+
+    /// Leaves the agenda view for the week view.
+    fn close_agenda<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        if let Some(view) = self.agenda_view.take() {
+            view.release(frontend.agenda_source());
+        }
+
+        self.pending_match = None;
+        frontend.get_agenda_text_registry().clear();
+        frontend.get_banner_text_registry().clear();
+        self.calendar.request_render();
+        self.create_week_view_render_data(frontend, window_size, Vec::new())
+    }
+
+    /// The height of a line of the agenda view.
+    fn agenda_line_height(&self) -> f32 {
+        self.ui.title_font_height as f32 + AGENDA_LINE_PADDING
+    }
+
+    /// The agenda view lists the events of [`agenda_view::AGENDA_DAYS`] days which start on the
+    /// current day.  PageUp and PageDown switch the days.  The click on an event opens it once
+    /// its week is obtained like the match of the search.
+    fn create_agenda_view_render_data<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+        events: impl IntoIterator<Item = Action>,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        let line_height = self.agenda_line_height();
+        let viewport_height = window_size.y as f32 - AGENDA_PADDING * 2.;
+        for event in events {
+            let Some(view) = self.agenda_view.as_mut() else {
+                break;
+            };
+
+            match event {
+                Action::Escape | Action::ToggleAgendaView => {
+                    return self.close_agenda(frontend, window_size);
+                }
+                Action::WindowResize => {
+                    frontend.get_banner_text_registry().clear();
+                    view.entries = None;
+                }
+                days @ (Action::SubtractWeek | Action::AddWeek) => {
+                    let shift = match days {
+                        Action::AddWeek => agenda_view::AGENDA_DAYS as i16,
+                        _ => -(agenda_view::AGENDA_DAYS as i16),
+                    };
+                    let start = view.start.add_days(shift);
+                    frontend.get_banner_text_registry().clear();
+                    self.request_agenda(frontend, start);
+                }
+                Action::Retry => {
+                    if let RangeRequest::Failed { .. } = view.state {
+                        let start = view.start.clone();
+                        frontend.get_banner_text_registry().clear();
+                        self.request_agenda(frontend, start);
+                    }
+                }
+                Action::AgendaChanged => {
+                    let start = view.start.clone();
+                    self.calendar.is_agenda_changed = true;
+                    self.request_agenda(frontend, start);
+                }
+                Action::Scroll { offset, .. } => {
+                    view.scroll(
+                        -offset * TEXT_SCROLL_AMPLIFIER,
+                        line_height,
+                        viewport_height,
+                    );
+                }
+                Action::MouseMove { x, y, .. } => self.ui.mouse_position = FPoint { x, y },
+                Action::MouseButtonUp { position } => {
+                    let entry = view
+                        .find_line(position.y - AGENDA_PADDING, line_height)
+                        .and_then(|line| view.entries.as_ref()?.get(line));
+                    let Some(&calendar::render::AgendaEntry::Event { event, is_long }) = entry
+                    else {
+                        continue;
+                    };
+
+                    let found = view.agenda().and_then(|agenda| {
+                        let table = if is_long { &agenda.long } else { &agenda.short };
+                        agenda_match(table, event, is_long)
+                    });
+                    if let Some(found) = found {
+                        self.calendar.show_date(&found.range.start_date);
+                        self.calendar.focused_day = None;
+                        self.pending_match = Some(found);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        // NOTE(alex): the view is checked before it's borrowed like in
+        // [`Self::create_month_view_render_data`].
+        if self.agenda_view.is_none() {
+            return self.close_agenda(frontend, window_size);
+        }
+
+        if let Some(view) = self.open_pending_match(frontend, &window_size)? {
+            self.event_details_view = Some(view);
+            return self.create_event_view_render_data(frontend, window_size, Vec::new());
+        }
+
+        let Some(view) = self.agenda_view.as_mut() else {
+            unreachable!("the agenda view is checked right above");
+        };
+
+        view.receive(frontend.agenda_source());
+        let banner: Option<FRect> = match &view.state {
+            RangeRequest::Failed { error } => Some(Self::create_banner_text_object(
+                frontend,
+                format_args!("{error}. Press R to retry."),
+                &window_size,
+            )?),
+            _ => {
+                frontend.get_banner_text_registry().clear();
+                None
+            }
+        };
+
+        let empty = calendar::obtain::WeekScheduleWithLanes::default();
+        if view.entries.is_none() {
+            let agenda = view.agenda().unwrap_or(&empty);
+            let entries = calendar::render::agenda_entries(
+                &agenda.long,
+                &agenda.short,
+                &view.start,
+                agenda_view::AGENDA_DAYS,
+            );
+            Self::create_agenda_text_objects(
+                frontend,
+                &entries,
+                &agenda.long,
+                &agenda.short,
+                window_size.x as f32,
+                line_height,
+            )?;
+            view.entries = Some(entries);
+            view.scroll(0., line_height, viewport_height);
+        }
+
+        let agenda = view.agenda().unwrap_or(&empty);
+        let line_top = |line: usize| AGENDA_PADDING + line as f32 * line_height - view.offset;
+        let entries: &[_] = view.entries.as_deref().unwrap_or_default();
+        frontend
+            .get_agenda_text_registry()
+            .update_vertical_offsets((0..entries.len()).map(line_top));
+
+        let swatch_width = line_height / 2.;
+        let swatches = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(line, entry)| match entry {
+                calendar::render::AgendaEntry::Event { event, is_long } => {
+                    let table = if *is_long {
+                        &agenda.long
+                    } else {
+                        &agenda.short
+                    };
+                    let color = table.calendar_colors.get(*event as usize)?;
+                    let rectangle = FRect {
+                        x: AGENDA_PADDING,
+                        y: line_top(line),
+                        w: swatch_width,
+                        h: line_height - AGENDA_LINE_PADDING,
+                    };
+                    Some((sdlext::Color::from_rgba(color.0), rectangle))
+                }
+                calendar::render::AgendaEntry::Date(_) => None,
+            })
+            .collect();
+
+        let hovered_line = view
+            .find_line(self.ui.mouse_position.y - AGENDA_PADDING, line_height)
+            .filter(|line| {
+                matches!(
+                    entries.get(*line),
+                    Some(calendar::render::AgendaEntry::Event { .. })
+                )
+            })
+            .map(|line| FRect {
+                x: 0.,
+                y: line_top(line) - AGENDA_LINE_PADDING / 2.,
+                w: window_size.x as f32,
+                h: line_height,
+            });
+
+        Ok(NewState {
+            activity: Activity::AgendaView,
+            render_data: RenderData::AgendaView(AgendaViewRenderData {
+                frontend,
+                swatches,
+                hovered_line,
+                banner,
+            }),
+        })
+    }
+
+    /// The captions of the days and the lines of the events like `09:00-09:30  Standup — Room 1`.
+    /// The text objects follow the order of `entries`.
+    fn create_agenda_text_objects(
+        frontend: &mut F,
+        entries: &[calendar::render::AgendaEntry],
+        long: &calendar::EventTable,
+        short: &calendar::EventTable,
+        width: f32,
+        line_height: f32,
+    ) -> Result<(), F::Error> {
+        let registry = frontend.get_agenda_text_registry();
+        registry.clear();
+        for entry in entries {
+            let (text, x) = match entry {
+                calendar::render::AgendaEntry::Date(date) => (
+                    format!("{} {}", date.weekday().name(), date.iso_8601().as_str()),
+                    AGENDA_PADDING,
+                ),
+                calendar::render::AgendaEntry::Event { event, is_long } => {
+                    let table = if *is_long { long } else { short };
+                    (
+                        agenda_event_line(table, *event, *is_long),
+                        AGENDA_PADDING * 2. + line_height / 2.,
+                    )
+                }
+            };
+            let position = FRect {
+                x,
+                y: 0.,
+                w: width - x - AGENDA_PADDING,
+                h: line_height,
+            };
+            registry.create(text, Color::WHITE, position)?;
+        }
+        Ok(())
+    }
+
+    /// Leaves the search view for the week view.
+    fn close_search<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        self.search_view = None;
+        frontend.get_search_text_registry().clear();
+        self.calendar.request_render();
+        self.create_week_view_render_data(frontend, window_size, Vec::new())
+    }
+
+    /// The number of the matches which fit the window.  The prompt takes the first line.
+    fn search_line_count(&self, window_size: &Point) -> usize {
+        let height = window_size.y as f32 - SEARCH_PADDING * 2.;
+        let line_count = (height / self.agenda_line_height()).floor() as usize;
+        line_count.saturating_sub(1)
+    }
+
+    /// The line of the search view at the ordinate `y`.  The prompt is on the line 0.
+    fn find_search_line(&self, y: f32) -> Option<usize> {
+        let line = ((y - SEARCH_PADDING) / self.agenda_line_height()).floor();
+        (line >= 0.).then_some(line as usize)
+    }
+
+    /// Leaves the search view for the week of the selected match.  The match is opened once the
+    /// week is obtained.  See [`Self::open_pending_match`].
+    fn open_search_match<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        if let Some(found) = self.search_view.take().and_then(SearchView::into_selected) {
+            self.calendar.show_date(&found.range.start_date);
+            self.calendar.focused_day = None;
+            self.pending_match = Some(found);
+        }
+
+        self.close_search(frontend, window_size)
+    }
+
+    /// Creates the view of the match of the search once its week is obtained.  The match is
+    /// dropped if the week has failed or the event is not found in it.
+    fn open_pending_match(
+        &mut self,
+        frontend: &mut F,
+        window_size: &Point,
+    ) -> Result<Option<EventDetailsView>, F::Error> {
+        if self.pending_match.is_none() {
+            return Ok(None);
+        }
+
+        self.update_week(frontend)?;
+        self.calendar.get_rendering(frontend);
+        if !self.calendar.is_settled() {
+            return Ok(None);
+        }
+
+        let Some(found) = self.pending_match.take() else {
+            unreachable!("the match is checked right above");
+        };
+
+        let event = [CalendarEventKind::Long, CalendarEventKind::Short]
+            .into_iter()
+            .find_map(|kind| {
+                let table = self.calendar.state.get_event_table(kind.is_long())?;
+                (0..table.titles.len() as u32)
+                    .find(|event| is_search_match(table, *event, &found))
+                    .map(|event| (event, kind))
+            });
+        let Some(details) = event.and_then(|(event, kind)| self.find_event_details(event, kind))
+        else {
+            return Ok(None);
+        };
+
+        Activities::<F>::create_event_details_text_objects(
+            details,
+            window_size,
+            &mut frontend
+                .get_event_details_text_object_regirsty()
+                .borrow_mut(),
+            &mut frontend
+                .get_event_details_field_label_regirsty()
+                .borrow_mut(),
+            Color::WHITE,
+        )
+        .map(Some)
+    }
+
+    /// The search view takes the query and lists its matches.  Enter searches the typed query.
+    /// Once the query is searched, Enter opens the selected match.  Tab and Shift+Tab select the
+    /// next and the previous match.  The click on a match opens it.
+    fn create_search_view_render_data<'wdrect, 'frontend>(
+        &'wdrect mut self,
+        frontend: &'frontend mut F,
+        window_size: Point,
+        events: impl IntoIterator<Item = Action>,
+    ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
+        let line_count = self.search_line_count(&window_size);
+        for event in events {
+            let clicked_line = match &event {
+                Action::MouseButtonUp { position } => self.find_search_line(position.y),
+                _ => None,
+            };
+            let Some(view) = self.search_view.as_mut() else {
+                break;
+            };
+
+            match event {
+                Action::Escape => return self.close_search(frontend, window_size),
+                Action::WindowResize => view.is_laid_out = false,
+                Action::TextInput(text) => view.push_str(&text),
+                Action::DeleteBackward => view.pop(),
+                Action::NextField => view.select(1, line_count),
+                Action::PreviousField => view.select(-1, line_count),
+                Action::Scroll { offset, .. } => view.scroll(-offset.signum() as isize, line_count),
+                Action::MouseMove { x, y, .. } => self.ui.mouse_position = FPoint { x, y },
+                Action::MouseButtonUp { .. } => {
+                    let found = clicked_line
+                        .and_then(|line| line.checked_sub(1))
+                        .and_then(|line| view.find_match(line, line_count));
+                    if let Some(found) = found {
+                        view.selected = found;
+                        return self.open_search_match(frontend, window_size);
+                    }
+                }
+                Action::Submit => match view.state {
+                    SearchState::Typing if !view.query.trim().is_empty() => {
+                        let today = frontend.get_today()?;
+                        match frontend.agenda_source().search(view.query.trim(), &today) {
+                            Ok(handle) => view.wait(handle),
+                            Err(e) => view.receive(Err(e), &today, line_count),
+                        }
+                    }
+                    SearchState::Found { .. } if !view.matches().is_empty() => {
+                        return self.open_search_match(frontend, window_size);
+                    }
+                    _ => (),
+                },
+                _ => (),
+            }
+        }
+
+        let hovered_line = self.find_search_line(self.ui.mouse_position.y);
+        let line_height = self.agenda_line_height();
+        // NOTE(alex): the view is checked before it's borrowed like in
+        // [`Self::create_month_view_render_data`].
+        if self.search_view.is_none() {
+            return self.close_search(frontend, window_size);
+        }
 
-                Ok(NewState {
-                    activity: Activity::WeekView,
-                    render_data: RenderData::WeekView(render_data),
-                })
+        let Some(view) = self.search_view.as_mut() else {
+            unreachable!("the search view is checked right above");
+        };
+
+        if let SearchState::Searching { handle } = &view.state {
+            let source = frontend.agenda_source();
+            if source.is_search_ready(handle) {
+                let found = source.fetch_matches(handle);
+                let today = frontend.get_today()?;
+                view.receive(found, &today, line_count);
             }
         }
+
+        if !view.is_laid_out {
+            Self::create_search_text_objects(
+                frontend,
+                view,
+                window_size.x as f32,
+                line_height,
+                line_count,
+            )?;
+            view.is_laid_out = true;
+        }
+
+        let line_rectangle = |line: usize| FRect {
+            x: 0.,
+            y: SEARCH_PADDING + line as f32 * line_height - AGENDA_LINE_PADDING / 2.,
+            w: window_size.x as f32,
+            h: line_height,
+        };
+        let selected_line = view
+            .selected
+            .checked_sub(view.first)
+            .and_then(|line| view.find_match(line, line_count).map(|_| line))
+            .map(|line| line_rectangle(line + 1));
+        let hovered_line = hovered_line
+            .and_then(|line| line.checked_sub(1))
+            .and_then(|line| view.find_match(line, line_count).map(|_| line))
+            .map(|line| line_rectangle(line + 1));
+
+        Ok(NewState {
+            activity: Activity::SearchView,
+            render_data: RenderData::SearchView(SearchViewRenderData {
+                frontend,
+                selected_line,
+                hovered_line,
+            }),
+        })
     }
 
-    pub fn get_root_activity(&self) -> Activity {
-        Activity::WeekView
+    /// The prompt followed by the shown matches like `2026-03-14 09:00    Standup — work`.  The
+    /// failure of the search is told instead of the matches.
+    fn create_search_text_objects(
+        frontend: &mut F,
+        view: &SearchView<<F::AgendaSource as AgendaSource>::SearchHandle, F::Error>,
+        width: f32,
+        line_height: f32,
+        line_count: usize,
+    ) -> Result<(), F::Error> {
+        let mut lines: Vec<String> = vec![format!("{} {}", captions::search::PROMPT, view.query)];
+        match &view.state {
+            SearchState::Typing => (),
+            SearchState::Searching { .. } => lines.push(String::from(captions::search::SEARCHING)),
+            SearchState::Failed { error } => lines.push(format!("{error}.")),
+            SearchState::Found { matches } if matches.is_empty() => {
+                lines.push(String::from(captions::search::NOT_FOUND))
+            }
+            SearchState::Found { matches } => {
+                let shown = matches.iter().skip(view.first).take(line_count);
+                lines.extend(shown.map(search_match_line));
+            }
+        }
+
+        let registry = frontend.get_search_text_registry();
+        registry.clear();
+        for (line, text) in lines.into_iter().enumerate() {
+            let position = FRect {
+                x: SEARCH_PADDING,
+                y: SEARCH_PADDING + line as f32 * line_height,
+                w: width - SEARCH_PADDING * 2.,
+                h: line_height,
+            };
+            registry.create(text, Color::WHITE, position)?;
+        }
+        Ok(())
     }
 
-    // main function
-    pub fn create_render_data<'wdrect, 'frontend>(
+    /// Returns from the event view to the view which has opened it.
+    fn leave_event_view<'wdrect, 'frontend>(
         &'wdrect mut self,
-        activity: Activity,
         frontend: &'frontend mut F,
         window_size: Point,
-        events: impl IntoIterator<Item = Action>,
     ) -> Result<NewState<'wdrect, 'frontend, F>, F::Error> {
-        match activity {
-            Activity::WeekView => self.create_week_view_render_data(frontend, window_size, events),
-            Activity::EventView => {
-                self.create_event_view_render_data(frontend, window_size, events)
+        self.dismiss_failure(frontend);
+        if let Some(change) = self.pending_change.as_mut() {
+            change.leaves_event_view = false;
+        }
+
+        match self.agenda_view.as_mut() {
+            Some(view) => {
+                // The event might have been changed.
+                view.entries = None;
+                self.create_agenda_view_render_data(frontend, window_size, Vec::new())
             }
+            None => self.create_week_view_render_data(frontend, window_size, Vec::new()),
         }
     }
 
@@ -1209,11 +3577,7 @@ impl<F: Frontend> App<F> {
                 }
                 Action::Escape => {
                     self.calendar.request_render();
-                    return self.create_week_view_render_data(
-                        frontend,
-                        window_size,
-                        Vec::new().into_iter(),
-                    );
+                    return self.leave_event_view(frontend, window_size);
                 }
                 Action::MouseMove {
                     x,
@@ -1271,6 +3635,44 @@ impl<F: Frontend> App<F> {
                     position: click_position,
                     button: MouseButton::Left,
                 } => {
+                    // The click on the calendar of a new event chooses the next calendar.
+                    let is_calendar_switched = match self.event_details_view.as_mut() {
+                        Some(EventDetailsView {
+                            origin: EventOrigin::Draft(draft),
+                            calendar_base_rectangle,
+                            texts,
+                            ..
+                        }) if calendar_base_rectangle.covers_point(&click_position) => {
+                            draft.calendar = (draft.calendar + 1) % draft.calendars.len();
+                            // The time typed by the user is kept when the form is created again.
+                            let text = |index: usize| texts[index].as_ref();
+                            if let Some(range) =
+                                parse_event_range(text(FROM_TEXT_INDEX), text(UNTIL_TEXT_INDEX))
+                            {
+                                draft.range = range;
+                            }
+                            true
+                        }
+                        _ => false,
+                    };
+
+                    if is_calendar_switched {
+                        // The name of the calendar is a label.  Given that, the entire form is
+                        // created again.
+                        if let Some(view) = self.event_details_view.take()
+                            && let EventOrigin::Draft(draft) = view.origin
+                        {
+                            self.event_details_view =
+                                Some(Activities::create_event_draft_text_objects(
+                                    frontend,
+                                    draft,
+                                    &view.texts,
+                                    &window_size,
+                                )?);
+                        }
+                        continue;
+                    }
+
                     let registry = frontend.get_event_details_text_object_regirsty().borrow();
                     let maybe_index_and_viewport = registry
                         .get_viewports()
@@ -1359,6 +3761,8 @@ impl<F: Frontend> App<F> {
                     }
                 }
                 Action::WindowResize => {
+                    // The text of the banner is wrapped by the width of the window.
+                    frontend.get_banner_text_registry().clear();
                     let window_width = window_size.x as f32;
                     // FIXME(alex): store this offset somewhere and pass to the functions which
                     // creates the text objects in Activities::create_event_details_text_objects.
@@ -1474,9 +3878,100 @@ impl<F: Frontend> App<F> {
                         continue;
                     };
 
-                    selection.highlight_start = 0;
-                    selection.highlight_end = text.chars().count() as i32;
+                    select_all(selection, text);
+                }
+                Action::TextInput(input) => {
+                    let Some(view) = self.event_details_view.as_mut() else {
+                        continue;
+                    };
+
+                    let registry: &mut F::TextObjectRegistry = &mut frontend
+                        .get_event_details_text_object_regirsty()
+                        .borrow_mut();
+                    edit_text_field::<F>(view, registry, TextEdit::Insert(&input))?;
+                }
+                Action::DeleteBackward => {
+                    let Some(view) = self.event_details_view.as_mut() else {
+                        continue;
+                    };
+
+                    let registry: &mut F::TextObjectRegistry = &mut frontend
+                        .get_event_details_text_object_regirsty()
+                        .borrow_mut();
+                    edit_text_field::<F>(view, registry, TextEdit::DeleteBackward)?;
+                }
+                Action::DeleteForward => {
+                    let Some(view) = self.event_details_view.as_mut() else {
+                        continue;
+                    };
+
+                    let registry: &mut F::TextObjectRegistry = &mut frontend
+                        .get_event_details_text_object_regirsty()
+                        .borrow_mut();
+                    edit_text_field::<F>(view, registry, TextEdit::DeleteForward)?;
+                }
+                movement @ (Action::CursorLeft
+                | Action::CursorRight
+                | Action::CursorHome
+                | Action::CursorEnd) => {
+                    let Some(view) = self.event_details_view.as_mut() else {
+                        continue;
+                    };
+
+                    let movement = match movement {
+                        Action::CursorLeft => CursorMovement::Left,
+                        Action::CursorRight => CursorMovement::Right,
+                        Action::CursorHome => CursorMovement::Home,
+                        Action::CursorEnd => CursorMovement::End,
+                        _ => unreachable!(),
+                    };
+                    move_cursor(view, movement);
+                }
+                Action::Save => {
+                    if self.event_details_view.is_none() {
+                        continue;
+                    }
+
+                    if self.pending_change.is_some() {
+                        let failure = String::from(captions::failure::CHANGE_IS_PENDING);
+                        self.tell_failure(frontend, failure);
+                        continue;
+                    }
+
+                    // The view is left once the event is saved.  See [`App::receive_change`].
+                    match self.save_event(frontend) {
+                        Ok(pending) => {
+                            self.pending_change = Some(PendingChange {
+                                pending,
+                                failure: captions::failure::SAVE,
+                                leaves_event_view: true,
+                            })
+                        }
+                        Err(failure) => self.tell_failure(frontend, failure),
+                    }
+                }
+                modification @ (Action::DeleteEvent | Action::DuplicateEvent) => {
+                    let Some(EventDetailsView {
+                        origin: EventOrigin::Table { index, kind },
+                        ..
+                    }) = self.event_details_view.as_ref()
+                    else {
+                        continue;
+                    };
+
+                    let (index, kind) = (*index, *kind);
+                    let modification = match modification {
+                        Action::DeleteEvent => EventModification::Delete,
+                        Action::DuplicateEvent => EventModification::Duplicate,
+                        _ => unreachable!(),
+                    };
+
+                    let leaves_event_view = true;
+                    self.modify_event(frontend, index, &kind, modification, leaves_event_view)?;
                 }
+                // The event being viewed is kept.  The week is requested again once the week
+                // view is back.
+                Action::AgendaChanged => self.calendar.is_agenda_changed = true,
                 _ => (),
             }
         }
@@ -1571,17 +4066,21 @@ impl<F: Frontend> App<F> {
             }
         }
 
+        let banner: Option<FRect> = match self.failure.as_ref() {
+            Some(failure) => Some(Self::create_banner_text_object(
+                frontend,
+                format_args!("{failure}."),
+                &window_size,
+            )?),
+            None => None,
+        };
+
         Ok({
             // The view is immutable later on.
-            let view: &EventDetailsView = &*view_mut;
-            let calendar_color = self
-                .calendar
-                .state
-                .get_event_table(view.event_kind.is_long())
-                .and_then(|table| table.calendar_colors.get(view.event_index as usize))
-                .expect(
-                    "fail to get the event table.  An invalid data within the EventDetailsView?",
-                );
+            let Some(view) = self.event_details_view.as_ref() else {
+                unreachable!("the Event Details View must be ready")
+            };
+            let calendar_color = obtain_calendar_color(&self.calendar.state, view);
 
             NewState {
                 activity: Activity::EventView,
@@ -1604,6 +4103,7 @@ impl<F: Frontend> App<F> {
                     calendar_color_rectangle: view.calendar_color_rectangle,
                     calendar_color_border: Color::WHITE,
                     calendar_base_rectangle: view.calendar_base_rectangle,
+                    banner,
                 }),
             }
         })
@@ -1622,6 +4122,208 @@ fn format_date_time(date: &calendar::date::Date, time: &calendar::date::Time) ->
     )
 }
 
+enum EventModification {
+    Delete,
+    /// Copies the event to the following week.
+    Duplicate,
+}
+
+enum TextEdit<'t> {
+    /// Replaces the selected text.  If nothing is selected, the text is inserted at the cursor.
+    Insert(&'t str),
+    /// Removes the selected text or the character before the cursor.
+    DeleteBackward,
+    /// Removes the selected text or the character after the cursor.
+    DeleteForward,
+}
+
+/// The byte range of `content` which is covered by `selection`.  The range is empty if there is
+/// only the cursor.  If the field is chosen without placing the cursor, the range is at the end of
+/// the content.
+fn selected_range(content: &str, selection: &SelectionHighlight) -> (usize, usize) {
+    let clamp = |offset: i32| {
+        let mut offset = (offset.max(0) as usize).min(content.len());
+        while !content.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    };
+
+    match (selection.highlight_start, selection.highlight_end) {
+        (-1, _) => (content.len(), content.len()),
+        (start, -1) => (clamp(start), clamp(start)),
+        (start, end) => (clamp(start.min(end)), clamp(start.max(end))),
+    }
+}
+
+/// Selects the entire `text`.  The offsets are in bytes like the rest of [`SelectionHighlight`].
+fn select_all(selection: &mut SelectionHighlight, text: &str) {
+    selection.highlight_start = 0;
+    selection.highlight_end = text.len() as i32;
+}
+
+/// Applies the edit to `content`.  Returns the new content and the offset of the cursor after the
+/// edit.
+fn apply_text_edit(
+    content: &str,
+    selection: &SelectionHighlight,
+    edit: TextEdit,
+) -> (String, usize) {
+    let (mut start, mut end) = selected_range(content, selection);
+    let replacement: &str = match edit {
+        TextEdit::Insert(text) => text,
+        TextEdit::DeleteBackward => {
+            if start == end {
+                start = previous_char_boundary(content, start);
+            }
+            ""
+        }
+        TextEdit::DeleteForward => {
+            if start == end {
+                end = next_char_boundary(content, end);
+            }
+            ""
+        }
+    };
+
+    let mut new_content = String::with_capacity(content.len() + replacement.len());
+    new_content.push_str(&content[..start]);
+    new_content.push_str(replacement);
+    new_content.push_str(&content[end..]);
+    (new_content, start + replacement.len())
+}
+
+/// Applies the edit to the text field which has the cursor.  The changes are kept in the view
+/// until they are saved.  See [`Action::Save`].
+fn edit_text_field<F: Frontend>(
+    view: &mut EventDetailsView,
+    registry: &mut F::TextObjectRegistry,
+    edit: TextEdit,
+) -> Result<(), F::Error> {
+    let Some(selection) = view.selection_highlight.as_mut() else {
+        return Ok(());
+    };
+
+    let index = selection.selected_text_field as usize;
+    let Some(content) = view.texts.get(index) else {
+        return Ok(());
+    };
+
+    let (new_content, cursor) = apply_text_edit(content, selection, edit);
+    registry.set_text(index as u32, new_content.as_str())?;
+
+    selection.highlight_start = cursor as i32;
+    selection.highlight_end = -1;
+    selection.is_highlighting = false;
+    view.texts[index] = new_content.into_boxed_str();
+    Ok(())
+}
+
+fn previous_char_boundary(content: &str, offset: usize) -> usize {
+    content[..offset]
+        .char_indices()
+        .next_back()
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn next_char_boundary(content: &str, offset: usize) -> usize {
+    content[offset..]
+        .chars()
+        .next()
+        .map(|c| offset + c.len_utf8())
+        .unwrap_or(offset)
+}
+
+enum CursorMovement {
+    Left,
+    Right,
+    Home,
+    End,
+}
+
+/// Moves the cursor within the text field which has it.  The selection is dropped.  If some text
+/// is selected, the cursor stops at the edge of the selection when it's moved to the left or to
+/// the right.
+fn move_cursor(view: &mut EventDetailsView, movement: CursorMovement) {
+    let Some(selection) = view.selection_highlight.as_mut() else {
+        return;
+    };
+
+    let Some(content) = view.texts.get(selection.selected_text_field as usize) else {
+        return;
+    };
+
+    let (start, end) = selected_range(content, selection);
+    let cursor = match (selection.highlight_start, selection.highlight_end) {
+        (-1, _) | (_, -1) => start,
+        (_, end) => end.clamp(0, content.len() as i32) as usize,
+    };
+
+    let new_cursor = match movement {
+        CursorMovement::Left if start != end => start,
+        CursorMovement::Right if start != end => end,
+        CursorMovement::Left => previous_char_boundary(content, cursor),
+        CursorMovement::Right => next_char_boundary(content, cursor),
+        CursorMovement::Home => content[..cursor].rfind('\n').map(|i| i + 1).unwrap_or(0),
+        CursorMovement::End => content[cursor..]
+            .find('\n')
+            .map(|i| cursor + i)
+            .unwrap_or(content.len()),
+    };
+
+    selection.highlight_start = new_cursor as i32;
+    selection.highlight_end = -1;
+    selection.is_highlighting = false;
+}
+
+/// Parses the date and the time like they're shown in [`EventDetailsView`].  E.g. `2025-11-03
+/// 09:15`.
+fn parse_date_time(value: &str) -> Option<(calendar::date::Date, calendar::date::Time)> {
+    let (date, time) = value.trim().split_once(' ')?;
+    if date.len() != 10 || !date.is_ascii() || time.len() != 5 || !time.is_ascii() {
+        return None;
+    }
+
+    Some((date.parse().ok()?, time.parse().ok()?))
+}
+
+/// Parses the range of the event from the fields "From" and "Until".  Returns `None` if either
+/// value is invalid or if the event ends before it starts.
+fn parse_event_range(from: &str, until: &str) -> Option<calendar::EventRange> {
+    let (start_date, start_time) = parse_date_time(from)?;
+    let (end_date, end_time) = parse_date_time(until)?;
+    let start = (&start_date, start_time.total_minutes());
+    if (&end_date, end_time.total_minutes()) < start {
+        return None;
+    }
+
+    Some(calendar::EventRange {
+        start_date,
+        start_time,
+        end_date,
+        end_time,
+    })
+}
+
+/// The color of the calendar of the event shown by the view.
+fn obtain_calendar_color<H, E>(
+    state: &CalendarState<H, E>,
+    view: &EventDetailsView,
+) -> calendar::Color {
+    match &view.origin {
+        EventOrigin::Table { index, kind } => *state
+            .get_event_table(kind.is_long())
+            .and_then(|table| table.calendar_colors.get(*index as usize))
+            .expect("fail to get the event table.  An invalid data within the EventDetailsView?"),
+        EventOrigin::Draft(draft) => draft
+            .calendars
+            .get(draft.calendar)
+            .map(|calendar| calendar.color)
+            .expect("the draft is created only if there is a calendar to store it"),
+    }
+}
+
 fn event_details_view_set_offsets<F: Frontend>(
     view: &mut EventDetailsView,
     event_details_text_object_registry: &mut F::TextObjectRegistry,
@@ -1903,8 +4605,7 @@ impl<F: Frontend> Activities<F> {
         Ok(EventDetailsView {
             text_field_padding: EVENT_DETAILS_VIEW_PADDING,
             selection_highlight: None,
-            event_index: details.index,
-            event_kind: details.event_kind,
+            origin: details.origin,
             flexible_fields: Box::from(&flexible_fields[..flexible_fields_cursor]),
             texts: texts.into_boxed_slice(),
             text_offsets: (0..field_counter)
@@ -1917,13 +4618,72 @@ impl<F: Frontend> Activities<F> {
             vertical_origins,
         })
     }
+
+    /// Creates the form of the new event which takes `range`.  The form is not created if there
+    /// is no calendar to store the event.
+    fn create_event_draft_view(
+        frontend: &F,
+        range: calendar::EventRange,
+        calendars: Vec<WritableCalendar>,
+        window_size: &Point,
+    ) -> Result<Option<EventDetailsView>, F::Error> {
+        if calendars.is_empty() {
+            return Ok(None);
+        }
+
+        let draft = EventDraft {
+            range,
+            calendars,
+            calendar: 0,
+        };
+
+        Self::create_event_draft_text_objects(frontend, draft, &[], window_size).map(Some)
+    }
+
+    /// Renders the form of the new event.  `texts` are the contents of the text fields typed in
+    /// so far.  They're indexed like the text objects of [`EventDetailsView`].
+    fn create_event_draft_text_objects(
+        frontend: &F,
+        draft: EventDraft,
+        texts: &[Box<str>],
+        window_size: &Point,
+    ) -> Result<EventDetailsView, F::Error> {
+        let text = |index: usize| texts.get(index).map(AsRef::as_ref).unwrap_or_default();
+        let range = draft.range.clone();
+        let calendar_name: String = draft
+            .calendars
+            .get(draft.calendar)
+            .map(|calendar| calendar.name.clone())
+            .unwrap_or_default();
+
+        let details = EventDetails {
+            title: text(TITLE_TEXT_INDEX),
+            description: text(DESCRIPTION_TEXT_INDEX),
+            origin: EventOrigin::Draft(draft),
+            range: &range,
+            url: text(URL_TEXT_INDEX),
+            location: text(LOCATION_TEXT_INDEX),
+            calendar_name: &calendar_name,
+        };
+
+        Self::create_event_details_text_objects(
+            details,
+            window_size,
+            &mut frontend
+                .get_event_details_text_object_regirsty()
+                .borrow_mut(),
+            &mut frontend
+                .get_event_details_field_label_regirsty()
+                .borrow_mut(),
+            Color::WHITE,
+        )
+    }
 }
 
 struct EventDetails<'event> {
     title: &'event str,
     description: &'event str,
-    index: u32,
-    event_kind: CalendarEventKind,
+    origin: EventOrigin,
     range: &'event calendar::EventRange,
     url: &'event str,
     location: &'event str,
@@ -1991,11 +4751,17 @@ fn find_clicked_event(
     })
 }
 
+#[allow(clippy::enum_variant_names)]
 pub enum Activity {
     WeekView,
     EventView,
+    MonthView,
+    AgendaView,
+    YearView,
+    SearchView,
 }
 
+#[derive(Clone, Copy)]
 enum CalendarEventKind {
     // the position is relative to the long event surface
     Long,
@@ -2126,8 +4892,32 @@ pub enum Action {
     Escape,
 
     WindowResize,
+    /// The files of the agenda are changed outside of the application.  E.g. by vdirsyncer.
+    AgendaChanged,
+    /// Requests the shown week again once the agenda source has failed to provide it.
+    Retry,
     SubtractWeek,
     AddWeek,
+    /// Switches between the week and the day under the mouse cursor.
+    ToggleDayView,
+    /// Switches between the week view and the month view.
+    ToggleMonthView,
+    /// Switches between the week view and the list of its events.
+    ToggleAgendaView,
+    /// Switches between the week view and the year view.
+    ToggleYearView,
+    /// Opens the prompt of the search.
+    Search,
+    /// Opens the filter of the events of the week view or focuses it again.
+    Filter,
+    /// Opens the prompt of the date to go to.
+    GoToDate,
+    /// Shows the current week and scrolls to the current hour.
+    Today,
+    /// A minute of the clock has passed.  The current time is obtained again.
+    ClockTick,
+    /// Confirms the input.  E.g. searches the typed query.
+    Submit,
     // NOTE(alex): the following actions depends on the layout of the window.  This causes a quite
     // a couple of questions:
     //
@@ -2165,6 +4955,38 @@ pub enum Action {
     NextField,
     PreviousField,
     SelectAll,
+    /// The text typed by the user.
+    TextInput(String),
+    DeleteBackward,
+    DeleteForward,
+    CursorLeft,
+    CursorRight,
+    /// Moves the cursor to the start of the line.
+    CursorHome,
+    /// Moves the cursor to the end of the line.
+    CursorEnd,
+    Save,
+    /// Deletes the event which is either opened or under the mouse cursor.
+    DeleteEvent,
+    /// Copies the event which is either opened or under the mouse cursor to the following week.
+    DuplicateEvent,
+}
+
+/// The action comes from a key which types a letter.  It doesn't act while the text is typed.
+fn is_typing_key(action: &Action) -> bool {
+    use Action::*;
+    matches!(
+        action,
+        Retry
+            | Filter
+            | GoToDate
+            | Today
+            | Zoom(_)
+            | ToggleDayView
+            | ToggleMonthView
+            | ToggleAgendaView
+            | ToggleYearView
+    )
 }
 
 pub enum MouseButton {
@@ -2195,21 +5017,133 @@ fn create_long_events<'a, TTC: TextTextureRegistry>(
     register_event_titles(&mut registration, &event_data.titles, &replacement).map(|_| replacement)
 }
 
+/// The titles of the events are preceded by the time of the events and followed by the
+/// location if `is_detailed` is set.
 fn create_short_events<'a, TTC: TextTextureRegistry>(
     event_data: &calendar::EventTable,
     week_start: &calendar::date::Date,
     mut registration: EventTitleRegistration<'a, TTC>,
     view: &View,
+    is_detailed: bool,
 ) -> Result<calendar::render::Rectangles, TTC::Error> {
     let new_rectangles = calendar::ui::create_short_event_rectangles(
         &view.short_event_surface,
         event_data,
         week_start,
+        view.day_count,
     );
 
     registration.text_registry.clear();
-    register_event_titles(&mut registration, &event_data.titles, &new_rectangles)
-        .map(|_| new_rectangles)
+    let registered = if is_detailed {
+        let titles: Vec<String> = (0..event_data.titles.len() as u32)
+            .map(|event| detail_event_title(event_data, event))
+            .collect();
+        register_event_titles(&mut registration, &titles, &new_rectangles)
+    } else {
+        register_event_titles(&mut registration, &event_data.titles, &new_rectangles)
+    };
+    registered.map(|_| new_rectangles)
+}
+
+/// The line of the event in the agenda view.  The long events are marked as lasting all day.
+fn agenda_event_line(event_data: &calendar::EventTable, event: u32, is_long: bool) -> String {
+    let title = event_data.obtain_title(event).unwrap_or_default();
+    let time = match event_data.obtain_range(event) {
+        Some(range) if !is_long => format!(
+            "{:02}:{:02}-{:02}:{:02}",
+            range.start_time.hour,
+            range.start_time.minute,
+            range.end_time.hour,
+            range.end_time.minute
+        ),
+        _ => String::from("all day"),
+    };
+
+    let mut ret = format!("{time:<11}  {title}");
+    if let Some(location) = event_data.obtain_location(event).filter(|l| !l.is_empty()) {
+        ret.push_str(" — ");
+        ret.push_str(location);
+    }
+    ret
+}
+
+/// The line of the match in the search view.  The long events are marked as lasting all day.
+fn search_match_line(found: &calendar::obtain::SearchMatch) -> String {
+    let range = &found.range;
+    let time = match found.is_long {
+        true => String::from("all day"),
+        false => format!(
+            "{:02}:{:02}",
+            range.start_time.hour, range.start_time.minute
+        ),
+    };
+
+    let date = range.start_date.iso_8601();
+    let mut ret = format!("{} {time:<7}  {}", date.as_str(), found.title);
+    if !found.calendar.is_empty() {
+        ret.push_str(" — ");
+        ret.push_str(&found.calendar);
+    }
+    ret
+}
+
+/// The `event` of the table of the agenda view as the match of the search.  It's found in the
+/// table of the week by [`is_search_match`].
+fn agenda_match(
+    table: &calendar::EventTable,
+    event: u32,
+    is_long: bool,
+) -> Option<calendar::obtain::SearchMatch> {
+    Some(calendar::obtain::SearchMatch {
+        title: table.obtain_title(event)?.to_owned(),
+        calendar: table.obtain_calendar(event).unwrap_or_default().to_owned(),
+        uid: table.obtain_uid(event)?.to_owned(),
+        range: table.obtain_range(event)?.clone(),
+        is_long,
+    })
+}
+
+/// The `event` of the table is the match of the search.  The event which crosses the midnight is
+/// matched by its first half.
+fn is_search_match(
+    table: &calendar::EventTable,
+    event: u32,
+    found: &calendar::obtain::SearchMatch,
+) -> bool {
+    let (Some(range), Some(title), Some(uid)) = (
+        table.obtain_range(event),
+        table.obtain_title(event),
+        table.obtain_uid(event),
+    ) else {
+        return false;
+    };
+
+    let start = range.start_time.total_minutes();
+    uid == found.uid
+        && title == found.title
+        && range.start_date == found.range.start_date
+        && start == found.range.start_time.total_minutes()
+}
+
+/// The title like `09:00-09:30 Standup`.  The location follows on the next line.
+fn detail_event_title(event_data: &calendar::EventTable, event: u32) -> String {
+    let title = event_data.obtain_title(event).unwrap_or_default();
+    let mut ret = match event_data.obtain_range(event) {
+        Some(range) => format!(
+            "{:02}:{:02}-{:02}:{:02} {title}",
+            range.start_time.hour,
+            range.start_time.minute,
+            range.end_time.hour,
+            range.end_time.minute
+        ),
+        None => String::from(title),
+    };
+
+    if let Some(location) = event_data.obtain_location(event).filter(|l| !l.is_empty()) {
+        ret.push('\n');
+        ret.push_str(location);
+    }
+    ret
 }
 
 pub trait GetLongEventTextRegistry {
@@ -2249,15 +5183,29 @@ pub trait Frontend:
     + GetShortEventTextRegistry<Registry = Self::TextTextureRegistry>
 {
     type TextObject;
-    type Error;
+    type Error: std::fmt::Display;
     type TextTextureRegistry: TextTextureRegistry<Error = Self::Error>;
     type TextObjectRegistry: TextObjectRegistry<Error = Self::Error, TextObject = Self::TextObject>;
     type AgendaSource: AgendaSource<Error = Self::Error>;
+    type AgendaWriter: AgendaWriter<Error = Self::Error>;
+    type WeekCache: WeekCache;
     type TextEngine: TextEngine<Error = Self::Error, TextObject = Self::TextObject>;
 
     fn get_hours_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
     fn get_days_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
     fn get_dates_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
+    /// The text of the banner which tells about the failure of the agenda source.
+    fn get_banner_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
+    /// The texts of the month view.  See [`App::create_month_text_objects`].
+    fn get_month_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
+    /// The texts of the agenda view.  See [`App::create_agenda_text_objects`].
+    fn get_agenda_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
+    /// The texts of the year view.  See [`App::create_year_text_objects`].
+    fn get_year_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
+    /// The texts of the search view.  See [`App::create_search_text_objects`].
+    fn get_search_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
+    /// The text of the prompt bar of the week view.  See [`App::create_prompt_text_object`].
+    fn get_prompt_text_registry(&mut self) -> &mut Self::TextTextureRegistry;
 
     fn get_event_details_field_label_regirsty(&self) -> &RefCell<Self::TextTextureRegistry>;
 
@@ -2266,9 +5214,32 @@ pub trait Frontend:
     fn get_text_engine(&self) -> &Self::TextEngine;
 
     fn get_current_week_start(&self) -> Result<calendar::date::Date, Self::Error>;
+    /// The current date.  The ranges shorter than [`SHORT_RANGE`] start on it.
+    fn get_today(&self) -> Result<calendar::date::Date, Self::Error>;
+    /// The current date and time.  See [`Action::ClockTick`].
+    fn get_now(&self) -> Result<(calendar::date::Date, calendar::date::Time), Self::Error>;
     fn set_clipboard(&self, text: impl Into<Vec<u8>>) -> Result<(), Self::Error>;
+    /// Asks the user to confirm the action described by `message`.  The call blocks until the
+    /// user answers.
+    fn confirm(&self, message: &str) -> Result<bool, Self::Error>;
 
     fn agenda_source(&self) -> &Self::AgendaSource;
+    fn agenda_writer(&self) -> &Self::AgendaWriter;
+    fn week_cache(&self) -> &Self::WeekCache;
+}
+
+/// The storage of the weeks obtained from [`AgendaSource`].  The stored week is shown while the
+/// fresh one is requested.
+pub trait WeekCache {
+    fn load(
+        &self,
+        week_start: &calendar::date::Date,
+    ) -> Option<calendar::obtain::WeekScheduleWithLanes>;
+    fn store(
+        &self,
+        week_start: &calendar::date::Date,
+        schedule: &calendar::obtain::WeekScheduleWithLanes,
+    );
 }
 
 /// The trait to fetch the data for the calendar.
@@ -2278,7 +5249,7 @@ pub trait Frontend:
 /// designed to fetch the data asynchronously.
 ///
 /// Happy path scenario:
-/// The process runs with method [`AgendaSource::request`] which returns a handle to be polled by
+/// The process runs with method [`AgendaSource::request_range`] which returns a handle to be polled by
 /// the method [`AgendaSource::is_ready`].  When the data is ready it's fetched with method
 /// [`AgendaSource::fetch`].
 ///
@@ -2289,20 +5260,96 @@ pub trait Frontend:
 /// longer needed.
 pub trait AgendaSource {
     type RequestHandle;
+    /// The search which is not over yet.  The search is abandoned once its handle is dropped.
+    type SearchHandle;
     type Error;
 
-    fn request(
+    /// Requests the agenda of `day_count` days which start on `start`.  E.g. the month view
+    /// requests six weeks.
+    fn request_range(
         &self,
-        week_start: &calendar::date::Date,
+        start: &calendar::date::Date,
+        day_count: u16,
     ) -> Result<Self::RequestHandle, Self::Error>;
     fn cancel(&self, handle: &Self::RequestHandle);
     fn is_ready(&self, handle: &Self::RequestHandle) -> bool;
     fn free(&self, handle: Self::RequestHandle);
+    /// Takes the data of the ready request.  The error is returned if the data can't be obtained
+    /// or parsed.  `start` is the first day of the request.  The lanes of the events are not
+    /// computed.  See [`calendar::obtain::get_lanes`].
     fn fetch(
         &self,
         handle: &Self::RequestHandle,
-        week_start: &calendar::date::Date,
-    ) -> calendar::obtain::WeekScheduleWithLanes;
+        start: &calendar::date::Date,
+    ) -> Result<calendar::obtain::WeekScheduleWithLanes, Self::Error>;
+    /// Starts the search of the events whose title, description or location holds the `query`.
+    /// The sources which can't search on their own scan the years around the date `around`.
+    fn search(
+        &self,
+        query: &str,
+        around: &calendar::date::Date,
+    ) -> Result<Self::SearchHandle, Self::Error>;
+    fn is_search_ready(&self, handle: &Self::SearchHandle) -> bool;
+    /// Takes the matches of the ready search.
+    fn fetch_matches(
+        &self,
+        handle: &Self::SearchHandle,
+    ) -> Result<Vec<calendar::obtain::SearchMatch>, Self::Error>;
+}
+
+/// The calendar where the new events can be stored.
+#[derive(Clone)]
+pub struct WritableCalendar {
+    pub name: String,
+    pub color: calendar::Color,
+}
+
+/// The event which is created by the user.  The time of the range is the local time of the
+/// machine.  The end of the range is exclusive.
+pub struct NewEvent<'e> {
+    pub calendar: &'e str,
+    pub title: &'e str,
+    pub description: &'e str,
+    pub location: &'e str,
+    pub url: &'e str,
+    pub range: &'e calendar::EventRange,
+    /// The event takes the entire days from the start date until the end date inclusive.  The
+    /// time of the range is ignored.
+    pub is_all_day: bool,
+}
+
+/// The changes of the event which is already stored.  The event is found by its `uid` within the
+/// `calendar`.
+pub struct EventUpdate<'e> {
+    pub uid: &'e str,
+    pub calendar: &'e str,
+    pub title: &'e str,
+    pub description: &'e str,
+    pub location: &'e str,
+    pub url: &'e str,
+    /// The new time of the event like in [`NewEvent`].  `None` if the time is not changed.
+    pub range: Option<&'e calendar::EventRange>,
+}
+
+/// The write-side counterpart of [`AgendaSource`].  Like the reading, the writing is done in the
+/// background.  Its result is taken by [`AgendaWriter::poll`].
+///
+/// The week which is affected by the change is to be requested from [`AgendaSource`] again.
+pub trait AgendaWriter {
+    type Error;
+    /// The result of the work which is being done in the background.
+    type Pending<T>;
+
+    /// Starts loading the calendars which accept the new events.
+    fn calendars(&self) -> Result<Self::Pending<Vec<WritableCalendar>>, Self::Error>;
+    fn create(&self, event: &NewEvent) -> Result<Self::Pending<()>, Self::Error>;
+    fn update(&self, event: &EventUpdate) -> Result<Self::Pending<()>, Self::Error>;
+    /// Deletes the event `uid` from the `calendar`.  All of the occurrences of a repeating event
+    /// are deleted.
+    fn delete(&self, uid: &str, calendar: &str) -> Result<Self::Pending<()>, Self::Error>;
+    /// Takes the result of the work once it's done.  `None` while it's being done.  The result is
+    /// taken once.
+    fn poll<T>(&self, pending: &Self::Pending<T>) -> Option<Result<T, Self::Error>>;
 }
 
 /// Stores textures of the text objects.
@@ -2344,6 +5391,9 @@ pub trait TextObjectRegistry {
 
     /// Sets the text wrap length
     fn set_wrap(&mut self, index: u32, width: f32) -> Result<(), Self::Error>;
+
+    /// Replaces the text of the text object created by [`Self::create`].
+    fn set_text(&mut self, index: u32, text: impl Into<Vec<u8>>) -> Result<(), Self::Error>;
 }
 
 struct EventTitleRegistration<'a, TTC: TextTextureRegistry> {
@@ -2375,3 +5425,93 @@ where
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The standup described as a sync, the lunch in the room 1 and the review.
+    fn create_table() -> calendar::EventTable {
+        let strings = |s: &[&str]| s.iter().map(|x| String::from(*x)).collect();
+        calendar::EventTable {
+            calendar_colors: vec![calendar::Color(0x808080ff); 3],
+            titles: strings(&["Standup", "Lunch", "Review"]),
+            description_handles: vec![0, 1, 1],
+            description_strings: strings(&["Weekly SYNC"]),
+            location_handles: vec![1, 0, 1],
+            location_strings: strings(&["Room 1"]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_event_filter_match() {
+        let table = create_table();
+        let found = |query: &str| -> Vec<u32> {
+            (0..3)
+                .filter(|event| EventFilter::is_match(&table, *event, query))
+                .collect()
+        };
+        assert_eq!(found("lunch"), [1]);
+        assert_eq!(found("sync"), [0]);
+        assert_eq!(found("room"), [1]);
+        assert_eq!(found("e"), [0, 2]);
+        assert_eq!(found("dinner"), []);
+    }
+
+    #[test]
+    fn test_agenda_match() {
+        let date = calendar::date::Date::new::<2026, 3, 14>();
+        let range = |hour| calendar::EventRange {
+            start_date: date.clone(),
+            start_time: calendar::date::Time::try_new(hour, 0).unwrap(),
+            end_date: date.clone(),
+            end_time: calendar::date::Time::try_new(hour + 1, 0).unwrap(),
+        };
+        let mut table = create_table();
+        table.event_ranges = vec![range(9), range(12), range(15)];
+        table.uids = vec![String::from("a"), String::from("b"), String::from("c")];
+
+        // The event chosen in the agenda view is found in the table of the week.
+        let found = agenda_match(&table, 1, false);
+        let events: Vec<u32> = (0..3)
+            .filter(|event| {
+                found
+                    .as_ref()
+                    .is_some_and(|found| is_search_match(&table, *event, found))
+            })
+            .collect();
+        assert_eq!(events, [1]);
+        assert!(agenda_match(&table, 3, false).is_none());
+    }
+
+    #[test]
+    fn test_event_filter_colors() {
+        let table = create_table();
+        let red = |colors: &[calendar::Color]| -> Vec<u32> {
+            colors.iter().map(|color| color.0 >> 24).collect()
+        };
+        assert_eq!(red(&EventFilter::colors(&table, None)), [0x80; 3]);
+
+        // The match is brighter than its calendar, the rest are darker.
+        let colors = red(&EventFilter::colors(&table, Some("room")));
+        assert!(colors[1] > 0x80);
+        assert!(colors[0] < 0x80);
+        assert_eq!(colors[0], colors[2]);
+    }
+
+    #[test]
+    fn test_edit_selected_non_ascii_text() {
+        let mut selection = SelectionHighlight::new();
+        select_all(&mut selection, "Café");
+        assert_eq!(selected_range("Café", &selection), (0, "Café".len()));
+
+        // The last character is replaced along with the rest.
+        let (content, cursor) = apply_text_edit("Café", &selection, TextEdit::Insert("Tea"));
+        assert_eq!(content, "Tea");
+        assert_eq!(cursor, 3);
+        let (content, cursor) = apply_text_edit("Café", &selection, TextEdit::DeleteBackward);
+        assert_eq!(content, "");
+        assert_eq!(cursor, 0);
+    }
+}
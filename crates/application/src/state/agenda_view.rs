@@ -0,0 +1,185 @@
+use super::AgendaSource;
+use super::range_request::RangeRequest;
+use calendar::date::Date;
+use calendar::render::AgendaEntry;
+
+/// The number of the days listed at once.
+pub const AGENDA_DAYS: u16 = 28;
+
+/// The list of the events of [`AGENDA_DAYS`] days.  The days are requested on their own rather
+/// than taken from the week view.  The list starts on the current day.  See
+/// [`calendar::render::agenda_entries`].
+pub struct AgendaView<Handle, E> {
+    /// The first listed day.
+    pub start: Date,
+    pub state: RangeRequest<Handle, E>,
+    /// The lines of the list for the current window and agenda.  They're dropped once either of
+    /// them changes.  The text objects of the view are created along with them.
+    pub entries: Option<Vec<AgendaEntry>>,
+    /// The scroll of the list.  It's subtracted from the ordinates of the lines.
+    pub offset: f32,
+}
+
+impl<Handle, E> AgendaView<Handle, E> {
+    /// Requests the days which start on `start`.
+    pub fn request<S>(src: &S, start: Date) -> Self
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        Self {
+            state: RangeRequest::request(src, &start, AGENDA_DAYS),
+            start,
+            entries: None,
+            offset: 0.,
+        }
+    }
+
+    /// Cancels the request of the days if it's still running.
+    pub fn release<S>(self, src: &S)
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        self.state.release(src);
+    }
+
+    /// Takes the agenda once the request is ready.  The lines are listed again afterwards.
+    pub fn receive<S>(&mut self, src: &S)
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        if self.state.receive(src, &self.start, Box::new) {
+            self.entries = None;
+            self.offset = 0.;
+        }
+    }
+
+    /// The tables of the long and the short events.  They're empty until the agenda is ready.
+    pub fn agenda(&self) -> Option<&calendar::obtain::WeekScheduleWithLanes> {
+        self.state.data().map(|agenda| &**agenda)
+    }
+
+    /// Scrolls the list by `value`.  The last line doesn't go above the bottom of the viewport.
+    pub fn scroll(&mut self, value: f32, line_height: f32, viewport_height: f32) {
+        let line_count = self.entries.as_ref().map_or(0, Vec::len);
+        let max_offset = (line_count as f32 * line_height - viewport_height).max(0.);
+        self.offset = (self.offset + value).clamp(0., max_offset);
+    }
+
+    /// The index of the line at the ordinate `y` which is relative to the top of the list.
+    pub fn find_line(&self, y: f32, line_height: f32) -> Option<usize> {
+        let line = ((y + self.offset) / line_height).floor();
+        let line_count = self.entries.as_ref().map_or(0, Vec::len);
+        (0. ..line_count as f32)
+            .contains(&line)
+            .then_some(line as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calendar::obtain::{SearchMatch, WeekScheduleWithLanes};
+
+    /// The source whose requests are ready at once.  The agenda holds the first day as a title.
+    struct ReadySource;
+
+    impl AgendaSource for ReadySource {
+        type RequestHandle = Date;
+        type SearchHandle = ();
+        type Error = String;
+
+        fn request_range(&self, start: &Date, _day_count: u16) -> Result<Date, String> {
+            Ok(start.clone())
+        }
+
+        fn cancel(&self, _handle: &Date) {}
+
+        fn is_ready(&self, _handle: &Date) -> bool {
+            true
+        }
+
+        fn free(&self, _handle: Date) {}
+
+        fn fetch(&self, handle: &Date, _start: &Date) -> Result<WeekScheduleWithLanes, String> {
+            let mut agenda = WeekScheduleWithLanes::default();
+            agenda
+                .short
+                .titles
+                .push(handle.iso_8601().as_str().to_owned());
+            Ok(agenda)
+        }
+
+        fn search(&self, _query: &str, _around: &Date) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn is_search_ready(&self, _handle: &()) -> bool {
+            true
+        }
+
+        fn fetch_matches(&self, _handle: &()) -> Result<Vec<SearchMatch>, String> {
+            Ok(Vec::new())
+        }
+    }
+
+    /// The view of the first and the second event of a day.
+    fn create_view() -> AgendaView<Date, String> {
+        let mut view = AgendaView::request(&ReadySource, Date::new::<2026, 3, 14>());
+        view.receive(&ReadySource);
+        let event = |event| AgendaEntry::Event {
+            event,
+            is_long: false,
+        };
+        view.entries = Some(vec![
+            AgendaEntry::Date(view.start.clone()),
+            event(0),
+            event(1),
+        ]);
+        view
+    }
+
+    #[test]
+    fn test_receive() {
+        let mut view = AgendaView::request(&ReadySource, Date::new::<2026, 3, 14>());
+        view.entries = Some(Vec::new());
+        view.offset = 10.;
+        assert!(view.agenda().is_none());
+
+        view.receive(&ReadySource);
+        let titles = view.agenda().map(|agenda| agenda.short.titles.clone());
+        assert_eq!(titles, Some(vec![String::from("2026-03-14")]));
+        assert!(view.entries.is_none());
+        assert_eq!(view.offset, 0.);
+    }
+
+    #[test]
+    fn test_find_line() {
+        let mut view = create_view();
+        assert_eq!(view.find_line(5., 10.), Some(0));
+        assert_eq!(view.find_line(25., 10.), Some(2));
+        assert_eq!(view.find_line(30., 10.), None);
+        assert_eq!(view.find_line(-5., 10.), None);
+
+        // The lines move up as the list is scrolled.
+        view.scroll(15., 10., 10.);
+        assert_eq!(view.offset, 15.);
+        assert_eq!(view.find_line(5., 10.), Some(2));
+        assert!(matches!(
+            view.entries.as_ref().and_then(|x| x.get(2)),
+            Some(AgendaEntry::Event { event: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut view = create_view();
+        // The last line stays at the bottom of the viewport.
+        view.scroll(100., 10., 20.);
+        assert_eq!(view.offset, 10.);
+        view.scroll(-100., 10., 20.);
+        assert_eq!(view.offset, 0.);
+        // The list which fits the viewport is not scrolled.
+        view.scroll(100., 10., 50.);
+        assert_eq!(view.offset, 0.);
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 static NO_RECT: calendar::render::Rectangles = Vec::new();
 
 // FIXME: Flatten the structure
@@ -5,10 +7,15 @@ pub struct WeekData {
     pub agenda: calendar::obtain::WeekScheduleWithLanes,
 }
 
-pub enum CalendarState<Handle> {
+pub enum CalendarState<Handle, E> {
     Loading {
         agenda_source_handle: Handle,
     },
+    /// The agenda source has failed to provide the week.  The week is requested again once the
+    /// user asks for it or the agenda changes.
+    Failed {
+        error: E,
+    },
     Ready {
         week_data: WeekData,
         long_event_clash_size: calendar::Lane,
@@ -21,7 +28,7 @@ pub enum CalendarState<Handle> {
     },
 }
 
-impl<Handle> CalendarState<Handle> {
+impl<Handle, E> CalendarState<Handle, E> {
     pub fn set_color(&mut self, event: u32, is_event_long: bool, color: calendar::Color) {
         if let Self::Ready {
             long_event_rectangles_opt,
@@ -64,7 +71,7 @@ impl<Handle> CalendarState<Handle> {
 
     pub fn obtain_events<'a>(&'a self) -> EventRectangles<'a> {
         match self {
-            Self::Loading { .. } => EventRectangles {
+            Self::Loading { .. } | Self::Failed { .. } => EventRectangles {
                 long: &NO_RECT,
                 short: &NO_RECT,
             },
@@ -82,8 +89,10 @@ impl<Handle> CalendarState<Handle> {
         }
     }
 
+    /// The table is available once the week is obtained.  The rectangles of the events are not
+    /// needed to read it.
     pub fn get_event_table(&self, is_event_long: bool) -> Option<&calendar::EventTable> {
-        if let Self::Ready { week_data, .. } = self {
+        if let Self::Ready { week_data, .. } | Self::Rendering { week_data, .. } = self {
             Some(match is_event_long {
                 true => &week_data.agenda.long,
                 false => &week_data.agenda.short,
@@ -93,14 +102,25 @@ impl<Handle> CalendarState<Handle> {
         }
     }
 
-    /// It provides a memory-safe way to switch the state.  The function creates an uninitialized
-    /// state to replace the current one.  Then it tries to switch to the next state provided by
-    /// the function `update`.  The function must return any valid state and an error if any has
-    /// occurred.
-    pub fn switch<E>(&mut self, update: impl FnOnce(Self) -> (Self, Option<E>)) -> Result<(), E> {
-        // SAFETY: the bald_state is never read until the function finishes.
-        let bald_state: CalendarState<_> = unsafe { std::mem::MaybeUninit::zeroed().assume_init() };
-        let current_state = std::mem::replace(self, bald_state);
+    /// The state which takes the place of the current one while it's being switched.  It holds
+    /// no week, so it doesn't allocate.
+    fn placeholder() -> Self {
+        Self::Rendering {
+            week_data: WeekData {
+                agenda: Default::default(),
+            },
+            long_event_clash_size: 0,
+        }
+    }
+
+    /// Switches the state by moving it into the function `update`.  The state is replaced by
+    /// [`Self::placeholder`] until `update` returns.  The function must return any valid state
+    /// and an error if any has occurred.
+    pub fn switch<SE>(
+        &mut self,
+        update: impl FnOnce(Self) -> (Self, Option<SE>),
+    ) -> Result<(), SE> {
+        let current_state = std::mem::replace(self, Self::placeholder());
         let (new_state, error) = update(current_state);
         *self = new_state;
         match error {
@@ -110,16 +130,13 @@ impl<Handle> CalendarState<Handle> {
     }
 
     pub fn switch_infallible(&mut self, update: impl Fn(Self) -> Self) {
-        // SAFETY: the bald_state is never read until the function finishes.
-        // FIXME(alex): the state is 200 bytes long.
-        let bald_state: CalendarState<_> = unsafe { std::mem::MaybeUninit::zeroed().assume_init() };
-        let current_state = std::mem::replace(self, bald_state);
+        let current_state = std::mem::replace(self, Self::placeholder());
         let new_state = update(current_state);
         *self = new_state;
     }
 
     /// a shortcut to switch to the [`CalendarState<H>::Loading`]
-    pub fn loading<E>(agenda_source_handle: Handle, e: Option<E>) -> (Self, Option<E>) {
+    pub fn loading<SE>(agenda_source_handle: Handle, e: Option<SE>) -> (Self, Option<SE>) {
         (
             Self::Loading {
                 agenda_source_handle,
@@ -128,9 +145,46 @@ impl<Handle> CalendarState<Handle> {
         )
     }
 
+    /// a shortcut to switch to the [`CalendarState<H>::Rendering`] with the received `agenda`
+    pub fn rendering(agenda: calendar::obtain::WeekScheduleWithLanes) -> Self {
+        let week_data = WeekData { agenda };
+        let long_event_clash_size = week_data.agenda.long.calculate_biggest_clash();
+        Self::Rendering {
+            week_data,
+            long_event_clash_size,
+        }
+    }
+
+    /// a shortcut to switch to the [`CalendarState<H>::Rendering`] or the
+    /// [`CalendarState<H>::Failed`] depending on the result of the request
+    pub fn fetched(result: Result<calendar::obtain::WeekScheduleWithLanes, E>) -> Self {
+        match result {
+            Ok(agenda) => Self::rendering(agenda),
+            Err(error) => Self::Failed { error },
+        }
+    }
+
+    /// Shows the `cached` agenda while the request behind `agenda_source_handle` is running.  If
+    /// there is no cached agenda, the state is [`CalendarState<H>::Loading`].  Returns the handle
+    /// to refresh the cached agenda.
+    pub fn from_cache(
+        agenda_source_handle: Handle,
+        cached: Option<calendar::obtain::WeekScheduleWithLanes>,
+    ) -> (Self, Option<Handle>) {
+        match cached {
+            Some(agenda) => (Self::rendering(agenda), Some(agenda_source_handle)),
+            None => (
+                Self::Loading {
+                    agenda_source_handle,
+                },
+                None,
+            ),
+        }
+    }
+
     pub fn get_long_event_clash_size(&self) -> calendar::Lane {
         match self {
-            CalendarState::Loading { .. } => 0,
+            CalendarState::Loading { .. } | CalendarState::Failed { .. } => 0,
             CalendarState::Ready {
                 long_event_clash_size,
                 ..
@@ -147,3 +201,160 @@ pub struct EventRectangles<'rect> {
     pub long: &'rect calendar::render::Rectangles,
     pub short: &'rect calendar::render::Rectangles,
 }
+
+/// The week obtained in advance.
+pub enum PrefetchedWeek<Handle, E> {
+    Requested(Handle),
+    // The week is boxed to keep the ring small while the weeks are requested.
+    Received(Box<calendar::obtain::WeekScheduleWithLanes>),
+    /// The error is shown once the week is switched to.
+    Failed(E),
+}
+
+/// The small ring of the weeks obtained in advance.  Once the ring is full, the week which is
+/// added first is dropped.
+pub struct WeekRing<Handle, E> {
+    weeks: VecDeque<(calendar::date::Date, PrefetchedWeek<Handle, E>)>,
+}
+
+impl<Handle, E> WeekRing<Handle, E> {
+    const CAPACITY: usize = 4;
+
+    pub fn new() -> Self {
+        Self {
+            weeks: VecDeque::with_capacity(Self::CAPACITY),
+        }
+    }
+
+    pub fn contains(&self, week_start: &calendar::date::Date) -> bool {
+        self.weeks.iter().any(|(start, _)| start == week_start)
+    }
+
+    pub fn take(&mut self, week_start: &calendar::date::Date) -> Option<PrefetchedWeek<Handle, E>> {
+        let index = self
+            .weeks
+            .iter()
+            .position(|(start, _)| start == week_start)?;
+        self.weeks.remove(index).map(|(_, week)| week)
+    }
+
+    /// Adds the week to the ring.  Returns the week which is dropped to make room for it.  The
+    /// request of the dropped week is up to the caller.
+    pub fn push(
+        &mut self,
+        week_start: calendar::date::Date,
+        week: PrefetchedWeek<Handle, E>,
+    ) -> Option<PrefetchedWeek<Handle, E>> {
+        let dropped = match self.weeks.len() < Self::CAPACITY {
+            true => None,
+            false => self.weeks.pop_front().map(|(_, week)| week),
+        };
+
+        self.weeks.push_back((week_start, week));
+        dropped
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = PrefetchedWeek<Handle, E>> {
+        self.weeks.drain(..).map(|(_, week)| week)
+    }
+
+    /// Replaces the requests by their outcome.  The function `receive` returns the request back
+    /// if it's not ready.
+    pub fn receive(
+        &mut self,
+        mut receive: impl FnMut(&calendar::date::Date, Handle) -> PrefetchedWeek<Handle, E>,
+    ) {
+        self.weeks = self
+            .weeks
+            .drain(..)
+            .map(|(week_start, week)| {
+                let week = match week {
+                    PrefetchedWeek::Requested(handle) => receive(&week_start, handle),
+                    x @ (PrefetchedWeek::Received(_) | PrefetchedWeek::Failed(_)) => x,
+                };
+                (week_start, week)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calendar::date::Date;
+
+    type Ring = WeekRing<u32, String>;
+
+    fn week(week: i16) -> Date {
+        Date::new::<2025, 11, 3>().add_days(week * 7)
+    }
+
+    fn handle(week: Option<PrefetchedWeek<u32, String>>) -> Option<u32> {
+        match week {
+            Some(PrefetchedWeek::Requested(handle)) => Some(handle),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_week_ring_eviction() {
+        let mut ring = Ring::new();
+        for handle in 0..Ring::CAPACITY as u32 {
+            let dropped = ring.push(week(handle as i16), PrefetchedWeek::Requested(handle));
+            assert!(dropped.is_none());
+        }
+
+        // The week added first makes room for the new one.
+        let dropped = ring.push(week(-1), PrefetchedWeek::Requested(10));
+        assert_eq!(handle(dropped), Some(0));
+        assert!(!ring.contains(&week(0)));
+        assert!(ring.contains(&week(-1)));
+        assert!(ring.contains(&week(3)));
+    }
+
+    #[test]
+    fn test_week_ring_shift() {
+        // The week view is switched from the week 0 to the week 1 and back.
+        let mut ring = Ring::new();
+        ring.push(week(-1), PrefetchedWeek::Requested(1));
+        ring.push(week(1), PrefetchedWeek::Requested(2));
+
+        assert_eq!(handle(ring.take(&week(1))), Some(2));
+        assert!(!ring.contains(&week(1)));
+        ring.push(week(0), PrefetchedWeek::Requested(3));
+
+        assert_eq!(handle(ring.take(&week(0))), Some(3));
+        assert!(ring.take(&week(2)).is_none());
+        assert_eq!(ring.drain().count(), 1);
+        assert!(!ring.contains(&week(-1)));
+    }
+
+    #[test]
+    fn test_week_ring_receive() {
+        let mut ring = Ring::new();
+        ring.push(week(-1), PrefetchedWeek::Requested(1));
+        ring.push(week(1), PrefetchedWeek::Requested(2));
+        ring.push(week(2), PrefetchedWeek::Failed(String::from("offline")));
+
+        // Only the first request is ready.  The failed week is not requested again.
+        let mut received: Vec<Date> = Vec::new();
+        ring.receive(|week_start, handle| {
+            received.push(week_start.clone());
+            match handle {
+                1 => PrefetchedWeek::Received(Box::default()),
+                _ => PrefetchedWeek::Requested(handle),
+            }
+        });
+
+        assert_eq!(received, [week(-1), week(1)]);
+        assert!(matches!(
+            ring.take(&week(-1)),
+            Some(PrefetchedWeek::Received(_))
+        ));
+        assert_eq!(handle(ring.take(&week(1))), Some(2));
+        assert!(matches!(
+            ring.take(&week(2)),
+            Some(PrefetchedWeek::Failed(error)) if error == "offline"
+        ));
+    }
+}
@@ -0,0 +1,56 @@
+use super::AgendaSource;
+use super::range_request::RangeRequest;
+use calendar::date::{Date, Weekday};
+
+/// The six weeks around a month.  The agenda of the weeks is requested at once.
+pub struct MonthView<Handle, E> {
+    /// The first day of the month.
+    pub month_start: Date,
+    /// The first day of the grid.  It's the start of the week which holds `month_start`.
+    pub grid_start: Date,
+    pub state: RangeRequest<Handle, E>,
+    /// The layout for the current window and agenda.  It's dropped once either of them changes.
+    /// The text objects of the view are created along with it.
+    pub layout: Option<calendar::render::MonthLayout>,
+}
+
+impl<Handle, E> MonthView<Handle, E> {
+    /// Requests the weeks of the month which holds `date`.
+    pub fn request<S>(src: &S, date: &Date, first_weekday: Weekday) -> Self
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        let month_start = date.month_start();
+        let grid_start = month_start.week_start(first_weekday);
+        let state = RangeRequest::request(src, &grid_start, calendar::render::MONTH_DAYS);
+        Self {
+            month_start,
+            grid_start,
+            state,
+            layout: None,
+        }
+    }
+
+    /// Cancels the request of the month if it's still running.
+    pub fn release<S>(self, src: &S)
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        self.state.release(src);
+    }
+
+    /// Takes the agenda once the request is ready.  The layout is computed again afterwards.
+    pub fn receive<S>(&mut self, src: &S)
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        if self.state.receive(src, &self.grid_start, Box::new) {
+            self.layout = None;
+        }
+    }
+
+    /// The date of the `day` counted from the first day of the grid.
+    pub fn date(&self, day: u16) -> Date {
+        self.grid_start.add_days(day as i16)
+    }
+}
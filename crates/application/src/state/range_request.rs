@@ -0,0 +1,99 @@
+use super::AgendaSource;
+use calendar::date::Date;
+use calendar::obtain::WeekScheduleWithLanes;
+
+/// The request of the agenda of the days shown by a view.  The views which show more than the
+/// week request their days on their own: [`super::MonthView`], [`super::AgendaView`] and
+/// [`super::YearView`].  The received agenda is kept as `T`.  E.g. the year view keeps only the
+/// busy minutes of its days.
+pub enum RangeRequest<Handle, E, T = Box<WeekScheduleWithLanes>> {
+    Loading {
+        agenda_source_handle: Handle,
+    },
+    /// The agenda source has failed to provide the days.  They're requested again once the user
+    /// asks for it or the agenda changes.
+    Failed {
+        error: E,
+    },
+    Ready {
+        data: T,
+    },
+}
+
+impl<Handle, E, T> RangeRequest<Handle, E, T> {
+    /// Requests `day_count` days which start on `start`.
+    pub fn request<S>(src: &S, start: &Date, day_count: u16) -> Self
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        match src.request_range(start, day_count) {
+            Ok(agenda_source_handle) => Self::Loading {
+                agenda_source_handle,
+            },
+            Err(error) => Self::Failed { error },
+        }
+    }
+
+    /// Cancels the request if it's still running.
+    pub fn release<S>(self, src: &S)
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        if let Self::Loading {
+            agenda_source_handle,
+        } = self
+        {
+            src.cancel(&agenda_source_handle);
+            src.free(agenda_source_handle);
+        }
+    }
+
+    /// Takes the agenda of the days which start on `start` once the request is ready.  `convert`
+    /// turns the agenda into the data of the view.  Returns `true` if the request has finished
+    /// either way.
+    pub fn receive<S>(
+        &mut self,
+        src: &S,
+        start: &Date,
+        convert: impl FnOnce(WeekScheduleWithLanes) -> T,
+    ) -> bool
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+        T: Default,
+    {
+        let is_ready = match self {
+            Self::Loading {
+                agenda_source_handle,
+            } => src.is_ready(agenda_source_handle),
+            Self::Failed { .. } | Self::Ready { .. } => false,
+        };
+        if !is_ready {
+            return false;
+        }
+
+        let Self::Loading {
+            agenda_source_handle,
+        } = std::mem::replace(self, Self::Ready { data: T::default() })
+        else {
+            unreachable!("the request must be loading");
+        };
+
+        let agenda = src.fetch(&agenda_source_handle, start);
+        src.free(agenda_source_handle);
+        *self = match agenda {
+            Ok(agenda) => Self::Ready {
+                data: convert(agenda),
+            },
+            Err(error) => Self::Failed { error },
+        };
+        true
+    }
+
+    /// The received data.  It's `None` until the request is ready.
+    pub fn data(&self) -> Option<&T> {
+        match self {
+            Self::Ready { data } => Some(data),
+            Self::Loading { .. } | Self::Failed { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use calendar::date::Date;
+use calendar::obtain::SearchMatch;
+
+pub enum SearchState<H, E> {
+    /// The query is being typed.  It's searched once the user submits it.
+    Typing,
+    /// The query is submitted.  The matches are taken once the search is ready.
+    Searching {
+        handle: H,
+    },
+    Failed {
+        error: E,
+    },
+    Found {
+        /// The matches sorted by their start.
+        matches: Vec<SearchMatch>,
+    },
+}
+
+/// The prompt of the search along with the list of its matches.  See
+/// [`super::AgendaSource::search`].  The list is scrolled by whole lines.
+pub struct SearchView<H, E> {
+    pub query: String,
+    pub state: SearchState<H, E>,
+    /// The index of the chosen match.
+    pub selected: usize,
+    /// The index of the first shown match.
+    pub first: usize,
+    /// The text objects of the view are created again once it's unset.  E.g. the query or the
+    /// window changes.
+    pub is_laid_out: bool,
+}
+
+impl<H, E> SearchView<H, E> {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            state: SearchState::Typing,
+            selected: 0,
+            first: 0,
+            is_laid_out: false,
+        }
+    }
+
+    /// The matches of the query.  The list is empty until the query is searched.
+    pub fn matches(&self) -> &[SearchMatch] {
+        match &self.state {
+            SearchState::Found { matches } => matches,
+            SearchState::Typing | SearchState::Searching { .. } | SearchState::Failed { .. } => &[],
+        }
+    }
+
+    /// Appends the `text` to the query.  The matches of the former query are dropped.  The search
+    /// of the former query is abandoned.
+    pub fn push_str(&mut self, text: &str) {
+        self.query.push_str(text);
+        self.reset();
+    }
+
+    /// Removes the last character of the query.
+    pub fn pop(&mut self) {
+        if self.query.pop().is_some() {
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = SearchState::Typing;
+        self.selected = 0;
+        self.first = 0;
+        self.is_laid_out = false;
+    }
+
+    /// Waits for the search of the query.
+    pub fn wait(&mut self, handle: H) {
+        self.state = SearchState::Searching { handle };
+        self.is_laid_out = false;
+    }
+
+    /// Takes the result of the search.  The first match which doesn't start before `today` is
+    /// selected.  If there is none, the last one is selected.  `line_count` is the number of the
+    /// matches which fit the window.
+    pub fn receive(&mut self, found: Result<Vec<SearchMatch>, E>, today: &Date, line_count: usize) {
+        self.state = match found {
+            Ok(matches) => SearchState::Found { matches },
+            Err(error) => SearchState::Failed { error },
+        };
+
+        let matches = self.matches();
+        self.selected = matches
+            .iter()
+            .position(|found| found.range.start_date >= *today)
+            .unwrap_or(matches.len().saturating_sub(1));
+        self.first = 0;
+        self.reveal(line_count);
+        self.is_laid_out = false;
+    }
+
+    /// Moves the selection by `shift` matches.  The selection wraps around the list.
+    pub fn select(&mut self, shift: isize, line_count: usize) {
+        let count = self.matches().len();
+        if count == 0 {
+            return;
+        }
+
+        self.selected = (self.selected as isize + shift).rem_euclid(count as isize) as usize;
+        self.reveal(line_count);
+    }
+
+    /// Scrolls the list by `lines`.  The last match doesn't go above the bottom of the window.
+    pub fn scroll(&mut self, lines: isize, line_count: usize) {
+        let max_first = self.matches().len().saturating_sub(line_count);
+        let first = (self.first as isize + lines).clamp(0, max_first as isize) as usize;
+        self.is_laid_out &= first == self.first;
+        self.first = first;
+    }
+
+    /// Scrolls the list to the selected match unless it's shown already.
+    fn reveal(&mut self, line_count: usize) {
+        let first = if self.selected < self.first {
+            self.selected
+        } else if self.selected >= self.first + line_count {
+            (self.selected + 1).saturating_sub(line_count)
+        } else {
+            self.first
+        };
+        self.is_laid_out &= first == self.first;
+        self.first = first;
+    }
+
+    /// The index of the match shown on the `line` of the list.
+    pub fn find_match(&self, line: usize, line_count: usize) -> Option<usize> {
+        let found = self.first + line;
+        (line < line_count && found < self.matches().len()).then_some(found)
+    }
+
+    /// Takes the selected match.
+    pub fn into_selected(self) -> Option<SearchMatch> {
+        match self.state {
+            SearchState::Found { mut matches } if self.selected < matches.len() => {
+                Some(matches.swap_remove(self.selected))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calendar::EventRange;
+    use calendar::date::Time;
+
+    type View = SearchView<std::rc::Rc<()>, String>;
+
+    fn found(title: &str, day: i16) -> SearchMatch {
+        let date = Date::new::<2026, 3, 14>().add_days(day);
+        SearchMatch {
+            title: String::from(title),
+            calendar: String::from("work"),
+            uid: String::new(),
+            range: EventRange {
+                start_date: date.clone(),
+                start_time: Time::midnight(),
+                end_date: date,
+                end_time: Time::last_minute(),
+            },
+            is_long: false,
+        }
+    }
+
+    fn titles(view: &View) -> Vec<&str> {
+        view.matches().iter().map(|x| x.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_receive_selects_upcoming_match() {
+        let today = Date::new::<2026, 3, 14>();
+        let matches = (0..6).map(|day| found(&day.to_string(), day - 3)).collect();
+        let mut view = View::new();
+        view.receive(Ok(matches), &today, 2);
+
+        // The match of today is the fourth one.  It's shown on the last line.
+        assert_eq!(view.selected, 3);
+        assert_eq!(view.first, 2);
+        assert_eq!(view.find_match(0, 2), Some(2));
+        assert_eq!(view.find_match(2, 2), None);
+
+        let mut view = View::new();
+        view.receive(Ok(vec![found("a", -2), found("b", -1)]), &today, 2);
+        assert_eq!(view.selected, 1);
+        assert_eq!(
+            view.into_selected().map(|x| x.title),
+            Some(String::from("b"))
+        );
+    }
+
+    #[test]
+    fn test_receive_failure() {
+        let mut view = View::new();
+        view.receive(Err(String::from("offline")), &Date::new::<2026, 3, 14>(), 2);
+        assert!(matches!(&view.state, SearchState::Failed { error } if error == "offline"));
+        assert!(view.matches().is_empty());
+        assert!(view.into_selected().is_none());
+    }
+
+    #[test]
+    fn test_select_and_scroll() {
+        let today = Date::new::<2026, 3, 14>();
+        let matches = (0..5).map(|day| found(&day.to_string(), day)).collect();
+        let mut view = View::new();
+        view.receive(Ok(matches), &today, 2);
+        assert_eq!((view.selected, view.first), (0, 0));
+
+        // The selection wraps around the list, and the list follows it.
+        view.select(-1, 2);
+        assert_eq!((view.selected, view.first), (4, 3));
+        view.select(1, 2);
+        assert_eq!((view.selected, view.first), (0, 0));
+
+        view.scroll(10, 2);
+        assert_eq!(view.first, 3);
+        view.scroll(-1, 2);
+        assert_eq!(view.first, 2);
+        view.scroll(-10, 2);
+        assert_eq!(view.first, 0);
+        assert_eq!(titles(&view), ["0", "1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn test_typing_abandons_search() {
+        let handle = std::rc::Rc::new(());
+        let mut view = View::new();
+        view.push_str("stand");
+        view.wait(std::rc::Rc::clone(&handle));
+        assert_eq!(std::rc::Rc::strong_count(&handle), 2);
+        assert!(view.matches().is_empty());
+
+        view.push_str("up");
+        assert_eq!(view.query, "standup");
+        assert!(matches!(view.state, SearchState::Typing));
+        assert_eq!(std::rc::Rc::strong_count(&handle), 1);
+
+        // The match of the former query is dropped once the query changes.
+        view.receive(
+            Ok(vec![found("Standup", 0)]),
+            &Date::new::<2026, 3, 14>(),
+            2,
+        );
+        view.pop();
+        assert!(view.matches().is_empty());
+        assert_eq!(view.query, "standu");
+    }
+}
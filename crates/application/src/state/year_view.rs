@@ -0,0 +1,67 @@
+use super::AgendaSource;
+use super::range_request::RangeRequest;
+use calendar::date::{Date, Weekday};
+
+/// The twelve months of a year.  The days are shaded by the time taken by their events.
+pub struct YearView<Handle, E> {
+    /// The first day of the year.
+    pub year_start: Date,
+    /// The first day of the grid of every month.  See [`calendar::render::YearArguments`].
+    pub grid_starts: [Date; 12],
+    /// The busy minutes of every day of the year.  See [`calendar::obtain::busy_minutes`].
+    pub state: RangeRequest<Handle, E, Vec<u16>>,
+    /// The cells of the days of the year for the current window.  They're dropped once the
+    /// window changes.  The text objects of the view are created along with them.
+    pub cells: Option<Vec<sdl3_sys::SDL_FRect>>,
+}
+
+impl<Handle, E> YearView<Handle, E> {
+    /// Requests the agenda of the year which holds `date`.
+    pub fn request<S>(src: &S, date: &Date, first_weekday: Weekday) -> Self
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        let year_start = date.year_start();
+        let grid_starts = core::array::from_fn(|month| {
+            year_start
+                .add_months(month as i16)
+                .week_start(first_weekday)
+        });
+        let day_count = Date::year_day_count(date.year);
+        let state = RangeRequest::request(src, &year_start, day_count);
+
+        Self {
+            year_start,
+            grid_starts,
+            state,
+            cells: None,
+        }
+    }
+
+    /// Cancels the request of the year if it's still running.
+    pub fn release<S>(self, src: &S)
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        self.state.release(src);
+    }
+
+    /// Counts the busy minutes of the days once the request is ready.  The lanes of the events
+    /// are not computed.
+    pub fn receive<S>(&mut self, src: &S)
+    where
+        S: AgendaSource<RequestHandle = Handle, Error = E>,
+    {
+        let day_count = Date::year_day_count(self.year_start.year);
+        self.state.receive(src, &self.year_start, |agenda| {
+            calendar::obtain::busy_minutes(&agenda.short.event_ranges, &self.year_start, day_count)
+        });
+    }
+
+    /// The date of the `day` counted from the first day of the grid of the `month`.  `None` if
+    /// the day belongs to the month next to it.
+    pub fn date(&self, month: u8, day: u16) -> Option<Date> {
+        let date = self.grid_starts[month as usize].add_days(day as i16);
+        (date.month == month + 1).then_some(date)
+    }
+}
@@ -0,0 +1,144 @@
+//! Watches the directories of the calendars with inotify.  The agenda is refreshed once the
+//! `.ics` files are created, changed or deleted.  A synchronization (e.g. a run of vdirsyncer)
+//! changes a bunch of files at once.  Given that, the changes are reported once they settle down.
+
+use std::ffi::{CString, c_char, c_int};
+use std::fs::File;
+use std::io::Read;
+use std::os::fd::FromRawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+unsafe extern "C" {
+    fn inotify_init1(flags: c_int) -> c_int;
+    fn inotify_add_watch(fd: c_int, pathname: *const c_char, mask: u32) -> c_int;
+}
+
+// NOTE(alex): the flags are the ones of x86 and ARM.  A couple of architectures (e.g. MIPS) have
+// the other values of `O_NONBLOCK` and `O_CLOEXEC`.
+const IN_NONBLOCK: c_int = 0o4000;
+const IN_CLOEXEC: c_int = 0o2000000;
+
+const IN_CLOSE_WRITE: u32 = 0x8;
+const IN_MOVED_FROM: u32 = 0x40;
+const IN_MOVED_TO: u32 = 0x80;
+const IN_CREATE: u32 = 0x100;
+const IN_DELETE: u32 = 0x200;
+const IN_ISDIR: u32 = 0x4000_0000;
+
+/// The size of `struct inotify_event` without the name: `wd`, `mask`, `cookie` and `len`.
+const EVENT_HEADER_SIZE: usize = 16;
+
+/// The time without the changes after which they are reported.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+pub struct Watcher {
+    inotify: File,
+    /// The moment of the last change which is not reported yet.
+    last_change: Option<Instant>,
+}
+
+impl Watcher {
+    pub fn new(directories: impl IntoIterator<Item = PathBuf>) -> Result<Self, std::io::Error> {
+        let fd = unsafe { inotify_init1(IN_NONBLOCK | IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        // SAFETY: the descriptor is just created.  The file is its only owner.
+        let inotify = unsafe { File::from_raw_fd(fd) };
+        // The files are written in place (IN_CLOSE_WRITE) or renamed from a temporary file
+        // (IN_MOVED_TO).
+        let mask = IN_CLOSE_WRITE | IN_MOVED_FROM | IN_MOVED_TO | IN_CREATE | IN_DELETE;
+        for directory in directories {
+            // FIXME(alex): the directory which can't be watched is skipped silently.  Its changes
+            // are shown only once the week is switched.
+            let Ok(path) = CString::new(directory.as_os_str().as_bytes()) else {
+                continue;
+            };
+
+            _ = unsafe { inotify_add_watch(fd, path.as_ptr(), mask) };
+        }
+
+        Ok(Self {
+            inotify,
+            last_change: None,
+        })
+    }
+
+    /// Reads the changes made since the last call.  Returns `true` if the calendar files haven't
+    /// been changed for a while after the last change.
+    pub fn poll(&mut self, now: Instant) -> bool {
+        let mut buffer = [0u8; 4096];
+        // The descriptor is non-blocking.  The error `WouldBlock` means that there are no more
+        // events.
+        while let Ok(size @ 1..) = self.inotify.read(&mut buffer) {
+            if is_calendar_changed(&buffer[..size]) {
+                self.last_change = Some(now);
+            }
+        }
+
+        match self.last_change {
+            Some(last_change) if now.duration_since(last_change) >= DEBOUNCE => {
+                self.last_change = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Checks if any of the inotify `events` is about an `.ics` file.
+// FIXME(alex): the new calendar directories are not watched.
+fn is_calendar_changed(mut events: &[u8]) -> bool {
+    let mut ret = false;
+    while let Some(header) = events.get(..EVENT_HEADER_SIZE) {
+        let field = |offset: usize| {
+            let bytes = [
+                header[offset],
+                header[offset + 1],
+                header[offset + 2],
+                header[offset + 3],
+            ];
+            u32::from_ne_bytes(bytes)
+        };
+
+        let mask = field(4);
+        let name_end = EVENT_HEADER_SIZE + field(12) as usize;
+        // The name is padded with zeroes.
+        let name = events.get(EVENT_HEADER_SIZE..name_end).unwrap_or_default();
+        let name = name.split(|byte| *byte == 0).next().unwrap_or_default();
+        ret |= mask & IN_ISDIR == 0 && name.ends_with(b".ics");
+        events = events.get(name_end..).unwrap_or_default();
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll() {
+        let directory = std::env::temp_dir().join(format!("semana-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let mut watcher = Watcher::new([directory.clone()]).unwrap();
+        let start = Instant::now();
+
+        std::fs::write(directory.join("color"), "#ff0000").unwrap();
+        assert!(!watcher.poll(start));
+        assert!(!watcher.poll(start + DEBOUNCE));
+
+        std::fs::write(directory.join("standup.ics"), "BEGIN:VCALENDAR").unwrap();
+        std::fs::write(directory.join("review.ics"), "BEGIN:VCALENDAR").unwrap();
+        let is_reported_at_once = watcher.poll(start);
+        let is_reported_later = watcher.poll(start + DEBOUNCE);
+        let is_reported_twice = watcher.poll(start + DEBOUNCE * 2);
+        _ = std::fs::remove_dir_all(&directory);
+
+        assert!(!is_reported_at_once);
+        assert!(is_reported_later);
+        assert!(!is_reported_twice);
+    }
+}
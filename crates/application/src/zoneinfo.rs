@@ -0,0 +1,58 @@
+//! Loads the time zones from the time zone database of the system.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use calendar::tz::TimeZone;
+
+fn database_path() -> PathBuf {
+    std::env::var_os("TZDIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/usr/share/zoneinfo"))
+}
+
+fn load_file(path: impl AsRef<std::path::Path>) -> Option<TimeZone> {
+    let bytes = std::fs::read(path).ok()?;
+    TimeZone::parse(&bytes).ok()
+}
+
+/// Loads the zone by its name.  E.g. `Europe/Berlin`.
+pub fn load(name: &str) -> Option<TimeZone> {
+    // The name must not lead out of the database.
+    if name.is_empty() || name.starts_with('/') || name.split('/').any(|part| part == "..") {
+        return None;
+    }
+
+    load_file(database_path().join(name))
+}
+
+/// Loads the zone of the machine.  It's given either by the variable `TZ` or by the file
+/// `/etc/localtime`.  If neither is available, the zone is UTC.
+pub fn load_local() -> TimeZone {
+    let from_env = std::env::var("TZ").ok().and_then(|tz| {
+        let tz = tz.strip_prefix(':').unwrap_or(&tz);
+        match tz.starts_with('/') {
+            true => load_file(tz),
+            false => load(tz),
+        }
+    });
+
+    from_env
+        .or_else(|| load_file("/etc/localtime"))
+        .unwrap_or_else(TimeZone::utc)
+}
+
+/// Loads the zones by their `names`.  The zones which can't be loaded are skipped.
+pub fn load_all<'n>(names: impl IntoIterator<Item = &'n str>) -> BTreeMap<String, TimeZone> {
+    let mut ret = BTreeMap::new();
+    for name in names {
+        if ret.contains_key(name) {
+            continue;
+        }
+
+        if let Some(zone) = load(name) {
+            ret.insert(name.to_owned(), zone);
+        }
+    }
+    ret
+}
@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::num::ParseIntError;
 use core::str::FromStr;
 
@@ -25,6 +26,78 @@ fn increment_date(date: &Date) -> Date {
     }
 }
 
+/// Moves the moment given by `date` and `time` by `minutes`.  The moment can move to another
+/// date.
+pub fn shift_minutes(date: &Date, time: &Time, minutes: i64) -> (Date, Time) {
+    let minutes_per_day = MINUTES_PER_DAY as i64;
+    let total: i64 = date.calculate_total_days() as i64 * minutes_per_day
+        + time.total_minutes().0 as i64
+        + minutes;
+    let days = total.div_euclid(minutes_per_day);
+    let day_minutes = total.rem_euclid(minutes_per_day);
+    let time = Time {
+        hour: (day_minutes / MINUTES_PER_HOUR as i64) as u8,
+        minute: (day_minutes % MINUTES_PER_HOUR as i64) as u8,
+    };
+    (Date::from_days(days as i32), time)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// Monday is 0, Sunday is 6.
+    pub const fn index(self) -> u8 {
+        self as u8
+    }
+
+    pub const fn from_index(index: u8) -> Weekday {
+        Self::ALL[(index % 7) as usize]
+    }
+
+    /// The number of days from `other` to `self` going forward.  E.g. from Friday to Monday is 3.
+    pub const fn days_since(self, other: Weekday) -> u8 {
+        (self.index() + 7 - other.index()) % 7
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Weekday::Monday => "Monday",
+            Weekday::Tuesday => "Tuesday",
+            Weekday::Wednesday => "Wednesday",
+            Weekday::Thursday => "Thursday",
+            Weekday::Friday => "Friday",
+            Weekday::Saturday => "Saturday",
+            Weekday::Sunday => "Sunday",
+        }
+    }
+
+    /// Finds the weekday by its name ignoring the case.  E.g. `sunday`.
+    pub fn from_name(name: &str) -> Option<Weekday> {
+        Self::ALL
+            .into_iter()
+            .find(|weekday| weekday.name().eq_ignore_ascii_case(name))
+    }
+}
+
 pub struct DateStream {
     last_date: Date,
 }
@@ -45,7 +118,7 @@ impl Iterator for DateStream {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, nanoserde::SerBin, nanoserde::DeBin)]
 pub struct Date {
     pub year: u16,
     /// 1 .. 12
@@ -168,7 +241,7 @@ impl Time {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, nanoserde::SerBin, nanoserde::DeBin)]
 pub struct Time {
     pub hour: u8,
     pub minute: u8,
@@ -222,6 +295,145 @@ impl FromStr for Date {
     }
 }
 
+/// The failure to read the date to go to.  See [`Date::from_jump`].
+#[derive(Debug)]
+pub enum ParseJumpError {
+    ParseDateError(ParseDateError),
+    ParseIntError(ParseIntError),
+    UnicodeIsNotSupported,
+    /// The shift is neither in days, nor in weeks, nor in months nor in years.  E.g. `+3x`.
+    IsNotUnit,
+    /// The word before the weekday is neither `next` nor `last`.
+    IsNotDirection,
+    IsNotWeekday,
+    /// The year has no such week.  E.g. `2026-W53`.
+    IsNotWeekOfYear,
+    /// The date is out of the years from 1 to 9999.
+    IsNotInRange,
+    /// The input has more than the date or the date isn't separated by dashes.  E.g.
+    /// `2026-01-01x` or `2026/01/01`.
+    IsNotDate,
+}
+
+impl Date {
+    /// The first and the last day which can be gone to.
+    const JUMP_RANGE: (Date, Date) = (Date::new::<1, 1, 1>(), Date::new::<9999, 12, 31>());
+
+    /// Reads the date to go to.  The input is one of the following:
+    ///
+    /// - the date: `2026-03-14`;
+    /// - the shift from `today` in days, weeks, months or years: `+3w`, `-10d`, `+1m`, `-1y`;
+    /// - the weekday after or before `today`: `next monday`, `last friday`;
+    /// - the ISO week, which gives its Monday: `2026-W11`;
+    /// - `today`.
+    ///
+    /// The case is ignored.
+    pub fn from_jump(s: &str, today: &Date) -> Result<Date, ParseJumpError> {
+        let s = s.trim();
+        if !s.is_ascii() {
+            return Err(ParseJumpError::UnicodeIsNotSupported);
+        }
+
+        let s = s.to_ascii_lowercase();
+        if s == "today" {
+            Ok(today.clone())
+        } else if let Some(shift) = s.strip_prefix('+') {
+            Self::shift_jump(today, shift, 1)
+        } else if let Some(shift) = s.strip_prefix('-') {
+            Self::shift_jump(today, shift, -1)
+        } else if let Some((direction, weekday)) = s.split_once(' ') {
+            let weekday = Weekday::from_name(weekday.trim()).ok_or(ParseJumpError::IsNotWeekday)?;
+            let today_weekday = today.weekday();
+            let (days, sign) = match direction {
+                "next" => (weekday.days_since(today_weekday), 1),
+                "last" => (today_weekday.days_since(weekday), -1),
+                _ => return Err(ParseJumpError::IsNotDirection),
+            };
+            // The weekday of today is a week away.
+            let days = if days == 0 { 7 } else { days as i16 };
+            Self::shift_days(today, days * sign)
+        } else if let Some((year, week)) = s.split_once("-w") {
+            let year = u16::from_str(year).map_err(ParseJumpError::ParseIntError)?;
+            let week = u8::from_str(week).map_err(ParseJumpError::ParseIntError)?;
+            Self::from_iso_week(year, week)
+        } else {
+            // `Date::from_str` reads the first ten characters and ignores the rest.
+            let bytes = s.as_bytes();
+            if bytes.len() > 10 || (bytes.len() == 10 && (bytes[4] != b'-' || bytes[7] != b'-')) {
+                return Err(ParseJumpError::IsNotDate);
+            }
+
+            Date::from_str(&s).map_err(ParseJumpError::ParseDateError)
+        }
+    }
+
+    /// The date `shift` away from `today`.  E.g. `3w` or `10d`.
+    fn shift_jump(today: &Date, shift: &str, sign: i16) -> Result<Date, ParseJumpError> {
+        let Some(unit) = shift.chars().last() else {
+            return Err(ParseJumpError::IsNotUnit);
+        };
+
+        let count = &shift[..shift.len() - unit.len_utf8()];
+        let count = u16::from_str(count).map_err(ParseJumpError::ParseIntError)?;
+        let count = i16::try_from(count).map_err(|_| ParseJumpError::IsNotInRange)? * sign;
+        let scale = |factor: i16| {
+            count
+                .checked_mul(factor)
+                .ok_or(ParseJumpError::IsNotInRange)
+        };
+        match unit {
+            'd' => Self::shift_days(today, count),
+            'w' => Self::shift_days(today, scale(7)?),
+            'm' => Self::shift_months(today, count),
+            'y' => Self::shift_months(today, scale(12)?),
+            _ => Err(ParseJumpError::IsNotUnit),
+        }
+    }
+
+    fn shift_days(today: &Date, days: i16) -> Result<Date, ParseJumpError> {
+        let (first, last) = &Self::JUMP_RANGE;
+        let total = today.calculate_total_days() + days as i32;
+        let range = first.calculate_total_days()..=last.calculate_total_days();
+        match range.contains(&total) {
+            true => Ok(Date::from_days(total)),
+            false => Err(ParseJumpError::IsNotInRange),
+        }
+    }
+
+    fn shift_months(today: &Date, months: i16) -> Result<Date, ParseJumpError> {
+        let (first, last) = &Self::JUMP_RANGE;
+        let index = |date: &Date| date.year as i32 * 12 + date.month as i32;
+        let range = index(first)..=index(last);
+        match range.contains(&(index(today) + months as i32)) {
+            true => Ok(today.add_months(months)),
+            false => Err(ParseJumpError::IsNotInRange),
+        }
+    }
+
+    /// The Monday of the ISO week.  The first week of the year is the one which holds January 4.
+    fn from_iso_week(year: u16, week: u8) -> Result<Date, ParseJumpError> {
+        let (first, last) = &Self::JUMP_RANGE;
+        if !(first.year..=last.year).contains(&year) {
+            return Err(ParseJumpError::IsNotInRange);
+        }
+
+        // The year has 53 weeks if it starts on Thursday, or if it's leap and starts on Wednesday.
+        let week_count = match Date::try_new(year, 1, 1).map(|date| date.weekday()) {
+            Ok(Weekday::Thursday) => 53,
+            Ok(Weekday::Wednesday) if Date::is_leap_year(year) => 53,
+            _ => 52,
+        };
+        if !(1..=week_count).contains(&week) {
+            return Err(ParseJumpError::IsNotWeekOfYear);
+        }
+
+        let first_week = Date::try_new(year, 1, 4)
+            .map_err(|_| ParseJumpError::IsNotInRange)?
+            .week_start(Weekday::Monday);
+        Self::shift_days(&first_week, (week as i16 - 1) * 7)
+    }
+}
+
 pub struct DateString([u8; 10]);
 
 impl DateString {
@@ -252,6 +464,10 @@ impl Date {
         }
     }
 
+    pub const fn year_day_count(year: u16) -> u16 {
+        if Self::is_leap_year(year) { 366 } else { 365 }
+    }
+
     pub const fn month_day_count(year: u16, month: u8) -> u8 {
         match month {
             2 => {
@@ -296,6 +512,55 @@ impl Date {
         eafs::calculate_gregorian_date(n)
     }
 
+    /// The date `months` months away.  The day is cut to the length of the month.  E.g. a month
+    /// after 2025-01-31 is 2025-02-28.
+    pub fn add_months(&self, months: i16) -> Date {
+        let index = self.year as i32 * 12 + self.month as i32 - 1 + months as i32;
+        let year = index.div_euclid(12) as u16;
+        let month = index.rem_euclid(12) as u8 + 1;
+        Date {
+            year,
+            month,
+            day: self.day.min(Self::month_day_count(year, month)),
+        }
+    }
+
+    /// The first day of the year of the date.
+    pub fn year_start(&self) -> Date {
+        Date {
+            year: self.year,
+            month: 1,
+            day: 1,
+        }
+    }
+
+    /// The first day of the month of the date.
+    pub fn month_start(&self) -> Date {
+        Date {
+            year: self.year,
+            month: self.month,
+            day: 1,
+        }
+    }
+
+    pub const fn month_name(&self) -> &'static str {
+        const NAMES: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        NAMES[(self.month as usize + 11) % 12]
+    }
+
     pub fn try_new(year: u16, month: u8, day: u8) -> Result<Date, InvalidInput> {
         if year < 1 {
             return Err(InvalidInput);
@@ -328,6 +593,18 @@ impl Date {
         eafs::calculate_rata_die_from_gregorian_calendar(self)
     }
 
+    pub fn weekday(&self) -> Weekday {
+        // 0001-01-01 is Monday.
+        const MONDAY: i32 = 306;
+        let index = (self.calculate_total_days() - MONDAY).rem_euclid(7);
+        Weekday::from_index(index as u8)
+    }
+
+    /// The first day of the week containing the date.  The week starts on `first_weekday`.
+    pub fn week_start(&self, first_weekday: Weekday) -> Date {
+        self.add_days(-(self.weekday().days_since(first_weekday) as i16))
+    }
+
     pub fn subtract(&self, other: &Date) -> i32 {
         let self_days = self.calculate_total_days();
         let other_days = other.calculate_total_days();
@@ -506,4 +783,109 @@ mod tests {
             assert_eq!(diff, 6)
         }
     }
+
+    #[test]
+    fn test_weekday() {
+        assert_eq!(Date::new::<1, 1, 1>().weekday(), Weekday::Monday);
+        assert_eq!(Date::new::<2025, 11, 3>().weekday(), Weekday::Monday);
+        assert_eq!(Date::new::<2025, 11, 9>().weekday(), Weekday::Sunday);
+        assert_eq!(Date::new::<2024, 2, 29>().weekday(), Weekday::Thursday);
+        assert_eq!(Weekday::Monday.days_since(Weekday::Friday), 3);
+        assert_eq!(Weekday::from_name("sunday"), Some(Weekday::Sunday));
+        assert_eq!(Weekday::from_name("Sun"), None);
+    }
+
+    #[test]
+    fn test_week_start() {
+        let date = Date::new::<2025, 11, 5>();
+        assert_eq!(date.week_start(Weekday::Monday), Date::new::<2025, 11, 3>());
+        assert_eq!(date.week_start(Weekday::Sunday), Date::new::<2025, 11, 2>());
+        assert_eq!(
+            date.week_start(Weekday::Saturday),
+            Date::new::<2025, 11, 1>()
+        );
+        assert_eq!(date.week_start(Weekday::Wednesday), date);
+    }
+
+    #[test]
+    fn test_add_months() {
+        let date = Date::new::<2025, 1, 31>();
+        assert_eq!(date.add_months(1), Date::new::<2025, 2, 28>());
+        assert_eq!(date.add_months(-1), Date::new::<2024, 12, 31>());
+        assert_eq!(date.add_months(13), Date::new::<2026, 2, 28>());
+        assert_eq!(date.month_start(), Date::new::<2025, 1, 1>());
+        assert_eq!(date.month_name(), "January");
+        assert_eq!(
+            Date::new::<2025, 11, 3>().year_start(),
+            Date::new::<2025, 1, 1>()
+        );
+        assert_eq!(Date::year_day_count(2024), 366);
+    }
+
+    #[test]
+    fn test_from_jump() {
+        // Wednesday
+        let today = Date::new::<2026, 3, 4>();
+        let inputs = [
+            ("2026-03-14", Date::new::<2026, 3, 14>()),
+            (" today ", today.clone()),
+            ("+3w", Date::new::<2026, 3, 25>()),
+            ("-10d", Date::new::<2026, 2, 22>()),
+            ("+1M", Date::new::<2026, 4, 4>()),
+            ("-1y", Date::new::<2025, 3, 4>()),
+            ("next monday", Date::new::<2026, 3, 9>()),
+            ("Next Wednesday", Date::new::<2026, 3, 11>()),
+            ("last friday", Date::new::<2026, 2, 27>()),
+            ("last wednesday", Date::new::<2026, 2, 25>()),
+            ("2026-W11", Date::new::<2026, 3, 9>()),
+            ("2026-w01", Date::new::<2025, 12, 29>()),
+            ("2020-W53", Date::new::<2020, 12, 28>()),
+            ("2026-W53", Date::new::<2026, 12, 28>()),
+        ];
+
+        for (i, (input, expected)) in inputs.iter().enumerate() {
+            let date = Date::from_jump(input, &today).unwrap();
+            assert_eq!(date, *expected, "case #{} (0-based) failed", i);
+        }
+
+        let jump = |input| Date::from_jump(input, &today);
+        assert!(matches!(jump("+3x"), Err(ParseJumpError::IsNotUnit)));
+        assert!(matches!(jump("+"), Err(ParseJumpError::IsNotUnit)));
+        assert!(matches!(jump("+w"), Err(ParseJumpError::ParseIntError(_))));
+        assert!(matches!(
+            jump("soon monday"),
+            Err(ParseJumpError::IsNotDirection)
+        ));
+        assert!(matches!(
+            jump("next mon"),
+            Err(ParseJumpError::IsNotWeekday)
+        ));
+        assert!(matches!(
+            jump("2027-W53"),
+            Err(ParseJumpError::IsNotWeekOfYear)
+        ));
+        assert!(matches!(
+            jump("2026-W0"),
+            Err(ParseJumpError::IsNotWeekOfYear)
+        ));
+        assert!(matches!(jump("-3000y"), Err(ParseJumpError::IsNotInRange)));
+        assert!(matches!(jump("+30000w"), Err(ParseJumpError::IsNotInRange)));
+        assert!(matches!(
+            jump("2026-13-01"),
+            Err(ParseJumpError::ParseDateError(_))
+        ));
+        assert!(matches!(
+            jump("2026-01-01x"),
+            Err(ParseJumpError::IsNotDate)
+        ));
+        assert!(matches!(jump("2026/01/01"), Err(ParseJumpError::IsNotDate)));
+        assert!(matches!(
+            jump("2026-01"),
+            Err(ParseJumpError::ParseDateError(_))
+        ));
+        assert!(matches!(
+            jump("héllo wörld"),
+            Err(ParseJumpError::UnicodeIsNotSupported)
+        ));
+    }
 }
@@ -3,8 +3,10 @@
 
 pub mod date;
 pub mod obtain;
+pub mod recurrence;
 pub mod render;
 pub mod types;
+pub mod tz;
 pub mod ui;
 extern crate alloc;
 use core::ops::Range;
@@ -12,14 +14,14 @@ use core::ops::Range;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use nanoserde::DeJson;
+use nanoserde::{DeBin, DeJson, SerBin};
 #[derive(Debug)]
 pub enum Error<'s> {
     InvalidDate(&'s str),
     InvalidTime(&'s str),
 }
 
-#[derive(DeJson)]
+#[derive(DeJson, Clone)]
 pub struct JsonInputEvent {
     description: String,
     title: String,
@@ -38,12 +40,15 @@ pub struct JsonInputEvent {
     url: String,
     location: String,
     calendar: String,
+    /// The unique identifier of the event (`UID`).  It's empty if the source doesn't provide it.
+    #[nserde(default)]
+    uid: String,
 }
 
 #[derive(Clone, Copy)]
 pub struct ColorDiff(pub [f32; 3]);
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, SerBin, DeBin)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct Color(pub u32);
 
@@ -68,6 +73,18 @@ impl Color {
         let val = Color::BLACK.0 | new_r << RED_SHIFT | new_g << GREEN_SHIFT | new_b << BLUE_SHIFT;
         Color(val)
     }
+
+    /// Parses the colors like `#rrggbb` and `#rrggbbaa`.  The alpha channel is opaque if it's
+    /// omitted.
+    pub fn from_hex(s: &str) -> Option<Color> {
+        let digits = s.trim().strip_prefix('#')?;
+        let value = u32::from_str_radix(digits, 16).ok()?;
+        match digits.len() {
+            6 => Some(Color(value << 8 | 0xff)),
+            8 => Some(Color(value)),
+            _ => None,
+        }
+    }
 }
 
 impl From<Color> for u32 {
@@ -97,6 +114,7 @@ impl nanoserde::DeJson for Color {
     }
 }
 
+#[derive(Debug, Clone, SerBin, DeBin)]
 pub struct EventRange {
     pub start_date: date::Date,
     pub start_time: date::Time,
@@ -104,7 +122,7 @@ pub struct EventRange {
     pub end_time: date::Time,
 }
 
-#[derive(Default)]
+#[derive(Default, SerBin, DeBin)]
 pub struct CalendarTable {
     // Would it cause fewer cache misses if the type is u8?
     indexes: Vec<u32>,
@@ -144,11 +162,12 @@ impl CalendarTable {
         // 1. When there's only one calendar.  Given that the loop from above won't run
         // 2. When all of the calendar names except the last one aren't equal to `name`.  Given that,
         //    that last one is checked here.
+        let last_handle = self.indexes.len() as u32 - 1;
         self.indexes.last().and_then(|i| {
             self.names
                 .get(*i as usize..self.names.len())
                 .filter(|x| *x == name)
-                .map(|_| *i)
+                .map(|_| last_handle)
         })
     }
 
@@ -160,7 +179,7 @@ impl CalendarTable {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, SerBin, DeBin)]
 pub struct EventTable {
     pub calendar_colors: Vec<Color>,
     pub event_ranges: Vec<EventRange>,
@@ -182,6 +201,12 @@ pub struct EventTable {
     // use a handle instead of u32
     pub calendar_handles: Vec<u32>,
     pub calendar_table: CalendarTable,
+    pub uids: Vec<String>,
+    /// The ranges of the events as the source provides them.  Unlike [`EventTable::event_ranges`],
+    /// they're cropped neither at midnight nor by the end of the range.
+    pub original_ranges: Vec<EventRange>,
+    /// The event takes entire days.  Its range lasts from the midnight until the last minute.
+    pub all_day_flags: Vec<bool>,
 }
 
 impl EventTable {
@@ -198,6 +223,15 @@ impl EventTable {
         self.event_ranges.get(event as usize)
     }
 
+    /// The range of the event before it's cropped.  See [`EventTable::original_ranges`].
+    pub fn obtain_original_range(&self, event: u32) -> Option<&EventRange> {
+        self.original_ranges.get(event as usize)
+    }
+
+    pub fn is_all_day(&self, event: u32) -> bool {
+        self.all_day_flags.get(event as usize).is_some_and(|x| *x)
+    }
+
     pub fn obtain_url(&self, event: u32) -> Option<&str> {
         let (h, s) = (&self.url_handles, &self.url_strings);
         Self::obtain_sparse_string(event as usize, h, s)
@@ -214,6 +248,12 @@ impl EventTable {
             .and_then(|h| self.calendar_table.get_name(*h))
     }
 
+    /// The identifier the event is stored under.  The string is empty if the source of the agenda
+    /// doesn't provide it.
+    pub fn obtain_uid(&self, event: u32) -> Option<&str> {
+        self.uids.get(event as usize).map(String::as_str)
+    }
+
     fn obtain_sparse_string<'a>(
         handle: usize,
         handles: &'a [u32],
@@ -249,4 +289,23 @@ mod tests {
         assert_eq!(time.hour, 23);
         assert_eq!(time.minute, 58);
     }
+
+    #[test]
+    fn test_calendar_table_handles() {
+        let mut table = crate::CalendarTable::default();
+        let work = table.push("work");
+        let home = table.push("home");
+        assert_eq!(table.get_handle("work"), Some(work));
+        assert_eq!(table.get_handle("home"), Some(home));
+        assert_eq!(table.get_handle("hobby"), None);
+        assert_eq!(table.get_name(home), Some("home"));
+    }
+
+    #[test]
+    fn test_color_from_hex() {
+        assert!(crate::Color::from_hex("#ff8000") == Some(crate::Color(0xff8000ff)));
+        assert!(crate::Color::from_hex("#ff800080") == Some(crate::Color(0xff800080)));
+        assert!(crate::Color::from_hex("ff8000").is_none());
+        assert!(crate::Color::from_hex("#ff80").is_none());
+    }
 }
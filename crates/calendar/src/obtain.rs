@@ -3,7 +3,12 @@ use alloc::string::String;
 use alloc::vec::Vec;
 
 use super::date::{Date, DateStream, MINUTES_PER_DAY, Minutes, Time};
+use super::tz::{self, TimeZone};
 use super::{EventTable, JsonInputEvent};
+
+pub mod caldav;
+pub mod ics;
+
 pub trait JsonParser {
     type Error;
 
@@ -30,15 +35,22 @@ pub enum Error<PE> {
     InvalidUnicode(core::str::Utf8Error),
     Parse(PE),
     DurationIsTooBig,
+    /// The field `all-day` of an event is neither `True` nor `False`.  It carries the value.
+    InvalidAllDay(String),
 }
 
 pub mod khal {
     use super::Date;
     use super::ObtainArguments;
     pub fn week_arguments(from: &Date) -> ObtainArguments<'_> {
+        range_arguments(from, 7)
+    }
+
+    /// The arguments to obtain the agenda of `duration_days` days starting from `from`.
+    pub fn range_arguments(from: &Date, duration_days: u16) -> ObtainArguments<'_> {
         ObtainArguments {
             from,
-            duration_days: 7,
+            duration_days,
             backend_bin_path: "khal",
         }
     }
@@ -48,7 +60,7 @@ pub struct ObtainArguments<'s> {
     // date in the format YYYY-MM-DD
     pub from: &'s Date,
     // date in the format YYYY-MM-DD
-    pub duration_days: u8,
+    pub duration_days: u16,
     // path to khal
     pub backend_bin_path: &'s str,
 }
@@ -137,33 +149,235 @@ pub fn parse_events<OutputParser>(
 where
     OutputParser: JsonParser,
 {
-    let mut long_event_table = EventTable::default();
-    let mut short_event_table = EventTable::default();
-    let date_stream = DateStream::new(start_date.clone()).take(7);
-
-    let agendas = bytes
-        .split('\n')
-        .take(7)
-        .take_while(|p| !p.is_empty())
-        .zip(date_stream);
-
-    let last_day_in_the_range: Date = start_date.add_days(6);
-    for item in agendas {
-        let (agenda_json, date): (&str, Date) = item;
-        let agenda: Vec<JsonInputEvent> = json_parser.parse(agenda_json).map_err(Error::Parse)?;
+    let mut stream = AgendaStream::new(start_date);
+    for line in bytes.split('\n') {
+        stream.push_line(json_parser, line)?;
+    }
+
+    Ok(stream.finish(default_calendar_color))
+}
+
+/// The same as [`parse_events`], but the time of the events is moved from the zone of Khal
+/// (`from`) to the zone `to`.  Khal provides the events in the zone of its configuration, which
+/// might differ from the one of the machine.
+///
+/// FIXME(alex): the events which are moved into the week from the neighbouring weeks are lost.
+/// The output covers only the requested week.  See [`AgendaStream::with_margin`] for the way
+/// around it.
+pub fn parse_events_in_zone<OutputParser>(
+    json_parser: &OutputParser,
+    bytes: &str,
+    start_date: &Date,
+    default_calendar_color: Color,
+    from: &TimeZone,
+    to: &TimeZone,
+) -> Result<WeekScheduleWithLanes, Error<OutputParser::Error>>
+where
+    OutputParser: JsonParser,
+{
+    let mut stream = AgendaStream::new(start_date);
+    for line in bytes.split('\n') {
+        stream.push_line(json_parser, line)?;
+    }
+
+    Ok(stream.finish_in_zone(default_calendar_color, from, to))
+}
+
+/// Parses the output of Khal as it arrives.  A line of the output is the agenda of a day.  The
+/// lines are parsed one by one, and the events are laid out once the output is over.
+pub struct AgendaStream {
+    start_date: Date,
+    /// The number of the days in the requested range.
+    day_count: u16,
+    /// The number of the days which are provided before and after the range.  See
+    /// [`AgendaStream::with_margin`].
+    margin: u16,
+    days: Vec<(Date, Vec<JsonInputEvent>)>,
+    /// An empty line ends the output.  The lines after it are ignored.
+    is_over: bool,
+}
+
+impl AgendaStream {
+    /// The stream of the week which starts on `start_date`.
+    pub fn new(start_date: &Date) -> Self {
+        Self::with_day_count(start_date, 7)
+    }
+
+    /// The stream of `day_count` days which start on `start_date`.
+    pub fn with_day_count(start_date: &Date, day_count: u16) -> Self {
+        Self::with_margin(start_date, day_count, 0)
+    }
+
+    /// The stream of `day_count` days which start on `start_date`.  The output starts `margin`
+    /// days earlier and ends `margin` days later.  The events of these days are taken only if
+    /// they're moved into the range by [`AgendaStream::finish_in_zone`].
+    pub fn with_margin(start_date: &Date, day_count: u16, margin: u16) -> Self {
+        Self {
+            start_date: start_date.clone(),
+            day_count,
+            margin,
+            days: Vec::with_capacity((day_count + 2 * margin) as usize),
+            is_over: false,
+        }
+    }
+
+    /// Parses the agenda of the next day.  The lines after the last day of the range are ignored.
+    pub fn push_line<OutputParser>(
+        &mut self,
+        json_parser: &OutputParser,
+        line: &str,
+    ) -> Result<(), Error<OutputParser::Error>>
+    where
+        OutputParser: JsonParser,
+    {
+        let fetched_day_count = self.day_count + 2 * self.margin;
+        self.is_over |= line.is_empty() || self.days.len() == fetched_day_count as usize;
+        if self.is_over {
+            return Ok(());
+        }
+
+        let agenda: Vec<JsonInputEvent> = json_parser.parse(line).map_err(Error::Parse)?;
+        check_all_day(&agenda)?;
+        let date = self.first_fetched_date().add_days(self.days.len() as i16);
+        self.days.push((date, agenda));
+        Ok(())
+    }
+
+    /// Lays out the parsed days.  See [`parse_events`].
+    pub fn finish(self, default_calendar_color: Color) -> WeekScheduleWithLanes {
+        let mut schedule = WeekScheduleWithLanes::default();
+        let last_day_in_the_range: Date = self.last_date();
+        let days = self.days.into_iter().skip(self.margin as usize);
+        for (date, agenda) in days.take(self.day_count as usize) {
+            schedule.push_day(
+                agenda,
+                &date,
+                &last_day_in_the_range,
+                default_calendar_color,
+            );
+        }
+
+        schedule
+    }
+
+    /// Lays out the parsed days in the zone `to`.  See [`parse_events_in_zone`].
+    pub fn finish_in_zone(
+        self,
+        default_calendar_color: Color,
+        from: &TimeZone,
+        to: &TimeZone,
+    ) -> WeekScheduleWithLanes {
+        let last_day_in_the_range: Date = self.last_date();
+        let first_fetched_date = self.first_fetched_date();
+        let start_date = &self.start_date;
+        let mut events: Vec<JsonInputEvent> = Vec::new();
+        for (date, agenda) in self.days {
+            // Khal repeats an event for every day it takes.  The event is taken once: either on
+            // the day it starts, or on the first provided day if it has started earlier.
+            let agenda = agenda.into_iter().filter(|event| {
+                event.start_date == date || (date == first_fetched_date && event.start_date < date)
+            });
+            events.extend(agenda);
+        }
+
+        for event in events.iter_mut().filter(|event| event.all_day != "True") {
+            let (start_date, start_time) =
+                tz::convert(&event.start_date, &event.start_time, from, to);
+            let (end_date, end_time) = tz::convert(&event.end_date, &event.end_time, from, to);
+            event.start_date = start_date;
+            event.start_time = start_time;
+            event.end_date = end_date;
+            event.end_time = end_time;
+        }
+
+        // The days of the margin are cropped once the events are moved to the zone `to`.
+        events.retain(|event| event.end_date >= *start_date);
+        events.retain(|event| event.start_date <= last_day_in_the_range);
+        schedule_events(events, start_date, self.day_count, default_calendar_color)
+    }
+
+    fn last_date(&self) -> Date {
+        self.start_date.add_days(self.day_count as i16 - 1)
+    }
+
+    fn first_fetched_date(&self) -> Date {
+        self.start_date.add_days(-(self.margin as i16))
+    }
+}
+
+/// Khal provides the field `all-day` as `True` or `False`.  The other values are rejected before
+/// the events are laid out.  See [`short_event_filter`].
+fn check_all_day<PE>(agenda: &[JsonInputEvent]) -> Result<(), Error<PE>> {
+    match agenda
+        .iter()
+        .find(|event| event.all_day != "True" && event.all_day != "False")
+    {
+        Some(event) => Err(Error::InvalidAllDay(event.all_day.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Lays out the `events` over `day_count` days which start on `start_date` the same way Khal does
+/// it.  Unlike the output of Khal, every event is given once.  Given that, an event is repeated
+/// for every day of the range it lasts for.  See [`short_event_filter`] for the reason.
+///
+/// The end of an event is expected to be inclusive.  E.g. an all-day event, which takes the
+/// entire 2025-11-03, ends on 2025-11-03.
+pub(crate) fn schedule_events(
+    mut events: Vec<JsonInputEvent>,
+    start_date: &Date,
+    day_count: u16,
+    default_calendar_color: Color,
+) -> WeekScheduleWithLanes {
+    // The events in the tables have to be sorted by their start.  See [`find_clashes`].
+    events.sort_by(|left, right| {
+        left.start_date.cmp(&right.start_date).then_with(|| {
+            let left = left.start_time.total_minutes();
+            left.cmp(&right.start_time.total_minutes())
+        })
+    });
+
+    let mut schedule = WeekScheduleWithLanes::default();
+    let last_day_in_the_range: Date = start_date.add_days(day_count as i16 - 1);
+    for date in DateStream::new(start_date.clone()).take(day_count as usize) {
+        let agenda = events
+            .iter()
+            .filter(|event| event.start_date <= date && date <= event.end_date)
+            .cloned();
+        schedule.push_day(
+            agenda,
+            &date,
+            &last_day_in_the_range,
+            default_calendar_color,
+        );
+    }
+
+    schedule
+}
+
+impl WeekScheduleWithLanes {
+    /// Adds the events of the given `date` to the tables.
+    fn push_day(
+        &mut self,
+        agenda: impl IntoIterator<Item = JsonInputEvent>,
+        date: &Date,
+        last_day_in_the_range: &Date,
+        default_calendar_color: Color,
+    ) {
         let event_items = agenda
             .into_iter()
-            .filter_map(|event: JsonInputEvent| short_event_filter(event, &date));
+            .filter_map(|event: JsonInputEvent| short_event_filter(event, date));
 
         for item in event_items {
-            let (is_short, mut json_event): (bool, JsonInputEvent) = item;
+            let (is_short, mut json_event, original_range): (bool, JsonInputEvent, EventRange) =
+                item;
             // The end date of event is shortened down to the last day of the week for the case
             // when a long event DOES NOT end by the end of the current week.
             json_event.end_date = json_event.end_date.min(last_day_in_the_range.clone());
             let table_ref: &mut EventTable = if is_short {
-                &mut short_event_table
+                &mut self.short
             } else {
-                &mut long_event_table
+                &mut self.long
             };
 
             let JsonInputEvent {
@@ -173,11 +387,12 @@ where
                 start_time,
                 end_date,
                 end_time,
-                all_day: _,
+                all_day,
                 calendar_color,
                 url,
                 location,
                 calendar,
+                uid,
             } = json_event;
             // FIXME(alex): Don't add empty descrptions.  If an event doesn't have a description
             // its points to -1.  That means that the handles should either negative or optional.
@@ -209,13 +424,11 @@ where
                 .get_handle(&calendar)
                 .unwrap_or_else(|| table_ref.calendar_table.push(&calendar));
             table_ref.calendar_handles.push(handle);
+            table_ref.uids.push(uid);
+            table_ref.original_ranges.push(original_range);
+            table_ref.all_day_flags.push(all_day == "True");
         }
     }
-
-    Ok(WeekScheduleWithLanes {
-        long: long_event_table,
-        short: short_event_table,
-    })
 }
 
 impl EventTable {
@@ -228,6 +441,8 @@ impl EventTable {
     }
 }
 
+/// The schedule is stored in the binary form to show the week before its events are obtained.
+#[derive(Default, nanoserde::SerBin, nanoserde::DeBin)]
 pub struct WeekScheduleWithLanes {
     pub long: EventTable,
     pub short: EventTable,
@@ -243,11 +458,17 @@ impl WeekScheduleWithLanes {
     }
 }
 
-pub fn get_lanes(mut events: WeekScheduleWithLanes, start_date: &Date) -> WeekScheduleWithLanes {
+/// Computes the lanes of the events taking place during `day_count` days since `start_date`.
+pub fn get_lanes(
+    mut events: WeekScheduleWithLanes,
+    start_date: &Date,
+    day_count: u16,
+) -> WeekScheduleWithLanes {
     let long_lanes: Vec<(Lane, Lane)> = find_clashes(
         &events.long.event_ranges,
         &events.long.titles,
         start_date,
+        day_count,
         long_event_clash_condition,
     );
 
@@ -255,6 +476,7 @@ pub fn get_lanes(mut events: WeekScheduleWithLanes, start_date: &Date) -> WeekSc
         &events.short.event_ranges,
         &events.short.titles,
         start_date,
+        day_count,
         short_event_clash_condition,
     );
 
@@ -263,6 +485,192 @@ pub fn get_lanes(mut events: WeekScheduleWithLanes, start_date: &Date) -> WeekSc
     events
 }
 
+/// The minutes of every of `day_count` days since `start_date` which are taken by the short
+/// `events`.  The time taken by several events at once is counted once.  Unlike [`get_lanes`],
+/// the events are not laid out.  The long events are not counted.  They mostly mark the days
+/// rather than take their time.  E.g. holidays or vacations.
+///
+/// # Assumptions
+/// The events are sorted by their start.  The short event doesn't leave its day.
+pub fn busy_minutes(events: &[EventRange], start_date: &Date, day_count: u16) -> Vec<u16> {
+    let mut ret: Vec<u16> = Vec::from_iter(core::iter::repeat_n(0, day_count as usize));
+    // The end of the last counted time of the day.
+    let mut ends: Vec<Minutes> =
+        Vec::from_iter(core::iter::repeat_n(Minutes(0), day_count as usize));
+    for event in events {
+        let Some(day) = usize::try_from(event.start_date.subtract(start_date))
+            .ok()
+            .filter(|day| *day < day_count as usize)
+        else {
+            continue;
+        };
+
+        let start = event.start_time.total_minutes().max(ends[day]);
+        let end = event.end_time.total_minutes();
+        if start < end {
+            ret[day] += end.subtract(start).0;
+            ends[day] = end;
+        }
+    }
+
+    ret
+}
+
+/// The event found by the search.  See [`search`] and [`parse_search_output`].
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    pub title: String,
+    pub calendar: String,
+    /// The unique identifier of the event.  It's empty if the source doesn't provide it.
+    pub uid: String,
+    /// The end of the range is inclusive like in [`EventTable`].
+    pub range: EventRange,
+    /// The event is shown among the long events.  E.g. it takes the entire day.
+    pub is_long: bool,
+}
+
+impl SearchMatch {
+    fn from_event(mut event: JsonInputEvent) -> Self {
+        // The value of the field is checked by [`check_all_day`].
+        let is_all_day = event.all_day == "True";
+        if is_all_day {
+            event.start_time = Time::midnight();
+            event.end_time = Time::last_minute();
+        }
+
+        let is_long = matches!(determine_event_type(&event, is_all_day), EventType::Long);
+        Self {
+            title: event.title,
+            calendar: event.calendar,
+            uid: event.uid,
+            range: EventRange {
+                start_date: event.start_date,
+                start_time: event.start_time,
+                end_date: event.end_date,
+                end_time: event.end_time,
+            },
+            is_long,
+        }
+    }
+}
+
+/// The events of the `schedule` whose title, description or location holds the `query`.  The
+/// case is ignored.  The matches are sorted by their start.
+///
+/// The event which crosses the midnight is found once.  Its second half is not a match.  See
+/// [`crop_event`].
+pub fn search(schedule: &WeekScheduleWithLanes, query: &str) -> Vec<SearchMatch> {
+    let query = query.to_lowercase();
+    let holds_query = |text: Option<&str>| text.is_some_and(|t| t.to_lowercase().contains(&query));
+    let mut found: Vec<SearchMatch> = Vec::new();
+    for (table, is_long) in [(&schedule.long, true), (&schedule.short, false)] {
+        for event in 0..table.titles.len() as u32 {
+            let is_found = holds_query(table.obtain_title(event))
+                || holds_query(table.obtain_description(event))
+                || holds_query(table.obtain_location(event));
+            let Some(range) = table.obtain_range(event).filter(|_| is_found) else {
+                continue;
+            };
+
+            found.push(SearchMatch {
+                title: table.obtain_title(event).unwrap_or_default().into(),
+                calendar: table.obtain_calendar(event).unwrap_or_default().into(),
+                uid: table.obtain_uid(event).unwrap_or_default().into(),
+                range: range.clone(),
+                is_long,
+            });
+        }
+    }
+
+    let is_second_half = |half: &SearchMatch| {
+        let range = &half.range;
+        !half.is_long
+            && range.start_time.total_minutes() == Time::midnight().total_minutes()
+            && found.iter().any(|first| {
+                !first.is_long
+                    && first.uid == half.uid
+                    && first.title == half.title
+                    && range.start_date.subtract(&first.range.end_date) == 1
+                    && first.range.end_time.total_minutes() == Time::last_minute().total_minutes()
+            })
+    };
+    let mut ret: Vec<SearchMatch> = found
+        .iter()
+        .filter(|item| !is_second_half(item))
+        .cloned()
+        .collect();
+    sort_matches(&mut ret);
+    ret
+}
+
+/// Parses the output of the command `search` of Khal.  Unlike the command `list`, a line of the
+/// output is a single event.  The lines which are not JSON are skipped.  E.g. `No events found`.
+pub fn parse_search_output<OutputParser>(
+    json_parser: &OutputParser,
+    bytes: &str,
+) -> Result<Vec<SearchMatch>, Error<OutputParser::Error>>
+where
+    OutputParser: JsonParser,
+{
+    let events = parse_search_events(json_parser, bytes)?;
+    let mut ret: Vec<SearchMatch> = events.into_iter().map(SearchMatch::from_event).collect();
+    sort_matches(&mut ret);
+    Ok(ret)
+}
+
+/// The same as [`parse_search_output`], but the time of the events is moved from the zone of
+/// Khal (`from`) to the zone `to`.  See [`parse_events_in_zone`].
+pub fn parse_search_output_in_zone<OutputParser>(
+    json_parser: &OutputParser,
+    bytes: &str,
+    from: &TimeZone,
+    to: &TimeZone,
+) -> Result<Vec<SearchMatch>, Error<OutputParser::Error>>
+where
+    OutputParser: JsonParser,
+{
+    let mut events = parse_search_events(json_parser, bytes)?;
+    for event in events.iter_mut().filter(|event| event.all_day != "True") {
+        let (start_date, start_time) = tz::convert(&event.start_date, &event.start_time, from, to);
+        let (end_date, end_time) = tz::convert(&event.end_date, &event.end_time, from, to);
+        event.start_date = start_date;
+        event.start_time = start_time;
+        event.end_date = end_date;
+        event.end_time = end_time;
+    }
+
+    let mut ret: Vec<SearchMatch> = events.into_iter().map(SearchMatch::from_event).collect();
+    sort_matches(&mut ret);
+    Ok(ret)
+}
+
+fn parse_search_events<OutputParser>(
+    json_parser: &OutputParser,
+    bytes: &str,
+) -> Result<Vec<JsonInputEvent>, Error<OutputParser::Error>>
+where
+    OutputParser: JsonParser,
+{
+    let mut ret: Vec<JsonInputEvent> = Vec::new();
+    for line in bytes.lines().filter(|line| line.starts_with('[')) {
+        let events: Vec<JsonInputEvent> = json_parser.parse(line).map_err(Error::Parse)?;
+        check_all_day(&events)?;
+        ret.extend(events);
+    }
+
+    Ok(ret)
+}
+
+fn sort_matches(matches: &mut [SearchMatch]) {
+    matches.sort_by(|left, right| {
+        let (left, right) = (&left.range, &right.range);
+        left.start_date.cmp(&right.start_date).then_with(|| {
+            let left = left.start_time.total_minutes();
+            left.cmp(&right.start_time.total_minutes())
+        })
+    });
+}
+
 type ClashCondition = fn(is_new_day: bool, event_end: Minutes, clash_end: Minutes) -> bool;
 
 fn short_event_clash_condition(is_new_day: bool, event_start: Minutes, clash_end: Minutes) -> bool {
@@ -277,6 +685,7 @@ fn find_clashes(
     events: &[EventRange],
     titles: &[impl AsRef<str>],
     start_date: &Date,
+    day_count: u16,
     condition: ClashCondition,
 ) -> Vec<(Lane, Lane)> {
     '_ensure_events_sorted_by_start_date: {
@@ -306,13 +715,14 @@ fn find_clashes(
         // the difference between the first day of the week (start_day) and the end day of the
         // event.
         let end_day_diff: i32 = event.end_date.subtract(start_date);
+        let days = 0..i32::from(day_count);
         assert!(
-            (0..7).contains(&start_day_diff),
-            "the events must start within the week"
+            days.contains(&start_day_diff),
+            "the events must start within the range"
         );
         assert!(
-            (0..7).contains(&end_day_diff),
-            "the events must finish within the week"
+            days.contains(&end_day_diff),
+            "the events must finish within the range"
         );
         let start_date_days: Minutes = Minutes(start_day_diff as u16 * MINUTES_PER_DAY);
         let total_event_start: Minutes = event.start_time.total_minutes().add(start_date_days);
@@ -400,31 +810,40 @@ fn find_clashes(
 /// equals to `date`.  The tail is cropped if the ending date of `event` equal to `date`.  This
 /// algorithm is based on the _assumption_ that the function `short_event_filter` is called for an
 /// event of this kind _twice_.
-fn short_event_filter(mut event: JsonInputEvent, date: &Date) -> Option<(bool, JsonInputEvent)> {
-    let is_all_day: bool = match event.all_day.as_str() {
-        "True" => true,
-        "False" => false,
-        x => panic!("unexpected string in the field \"all-day\": {:?}", x),
-    };
+///
+/// The range of `event` before it's cropped is returned along with the event.
+fn short_event_filter(
+    mut event: JsonInputEvent,
+    date: &Date,
+) -> Option<(bool, JsonInputEvent, EventRange)> {
+    // The value of the field is checked by [`check_all_day`].
+    let is_all_day: bool = event.all_day == "True";
 
     if is_all_day {
         event.start_time = Time::midnight();
         event.end_time = Time::last_minute();
     }
 
+    let original_range = EventRange {
+        start_date: event.start_date.clone(),
+        start_time: event.start_time.clone(),
+        end_date: event.end_date.clone(),
+        end_time: event.end_time.clone(),
+    };
+
     let event_type: EventType = determine_event_type(&event, is_all_day);
     match event_type {
-        EventType::Short => Some((true, event)),
+        EventType::Short => Some((true, event, original_range)),
         EventType::Long => {
             if event.start_date == *date {
-                Some((false, event))
+                Some((false, event, original_range))
             } else {
                 None
             }
         }
         EventType::CrossNight => {
             let cropped_event: JsonInputEvent = crop_event(date, event);
-            Some((true, cropped_event))
+            Some((true, cropped_event, original_range))
         }
     }
 }
@@ -470,6 +889,7 @@ fn crop_event(date: &Date, event: JsonInputEvent) -> JsonInputEvent {
             url: event.url,
             location: event.location,
             calendar: event.calendar,
+            uid: event.uid,
         }
     } else if date == &event.end_date {
         JsonInputEvent {
@@ -484,6 +904,7 @@ fn crop_event(date: &Date, event: JsonInputEvent) -> JsonInputEvent {
             url: event.url,
             location: event.location,
             calendar: event.calendar,
+            uid: event.uid,
         }
     } else {
         panic!("only an event which shorter than 24 hours can be cropped")
@@ -539,6 +960,39 @@ mod tests {
         }
     }
 
+    /// The event as Khal prints it in JSON.  `start` and `end` look like "2025-11-03 10:00".
+    fn khal_event(title: &str, start: &str, end: &str) -> String {
+        khal_event_with(title, "", "False", start, end)
+    }
+
+    /// The event as Khal prints it in JSON with the location and the `all-day` value.
+    #[track_caller]
+    fn khal_event_with(
+        title: &str,
+        location: &str,
+        all_day: &str,
+        start: &str,
+        end: &str,
+    ) -> String {
+        let (start_date, start_time) = start.split_once(' ').expect("start must have a time");
+        let (end_date, end_time) = end.split_once(' ').expect("end must have a time");
+        alloc::format!(
+            r##"{{"title": "{title}", "start-date": "{start_date}", "start-time": "{start_time}", "end-date": "{end_date}", "end-time": "{end_time}", "all-day": "{all_day}", "calendar-color": "#ff0000ff", "description": "", "url": "", "location": "{location}", "calendar": "work", "uid": "{title}"}}"##
+        )
+    }
+
+    /// The line of Khal's output with the only event which lasts from 10:00 till 11:00 of `date`.
+    fn morning(title: &str, date: &str) -> String {
+        let start = alloc::format!("{date} 10:00");
+        let end = alloc::format!("{date} 11:00");
+        khal_line(&[&khal_event(title, &start, &end)])
+    }
+
+    /// The line of Khal's output which lists the events of a day.
+    fn khal_line(events: &[&str]) -> String {
+        alloc::format!("[{}]", events.join(", "))
+    }
+
     #[test]
     fn test_short_event_clash() {
         let create_event = |start_time: &str, end_time: &str| EventRange {
@@ -558,7 +1012,7 @@ mod tests {
         let titles: Vec<&str> = Vec::from_iter(["first", "second", "third", "separated"]);
 
         let start = create_date("2025-11-03");
-        let lanes = find_clashes(&events, &titles, &start, short_event_clash_condition);
+        let lanes = find_clashes(&events, &titles, &start, 7, short_event_clash_condition);
         let [
             first_event_lane,
             second_event_lane,
@@ -575,6 +1029,246 @@ mod tests {
         assert!(matches!(separated_event_lane, (0, 1)));
     }
 
+    #[test]
+    fn test_busy_minutes() {
+        let create_event = |date: &str, start_time: &str, end_time: &str| EventRange {
+            start_date: create_date(date),
+            start_time: create_time(start_time),
+            end_date: create_date(date),
+            end_time: create_time(end_time),
+        };
+
+        // The second event overlaps the first one by half an hour.  The third is inside of the
+        // second.  The last one is out of the range.
+        let events: Vec<EventRange> = Vec::from_iter([
+            create_event("2025-11-03", "10:00", "11:00"),
+            create_event("2025-11-03", "10:30", "12:00"),
+            create_event("2025-11-03", "11:00", "11:30"),
+            create_event("2025-11-05", "09:00", "09:45"),
+            create_event("2025-11-06", "09:00", "10:00"),
+        ]);
+
+        let minutes = busy_minutes(&events, &create_date("2025-11-03"), 3);
+        assert_eq!(minutes, [120, 0, 45]);
+    }
+
+    #[test]
+    fn test_search() {
+        // The night event is provided by Khal twice: on Monday and on Tuesday.
+        let night = khal_event("Night shift", "2025-11-03 22:00", "2025-11-04 02:00");
+        let standup = khal_event_with(
+            "Standup",
+            "Room 1",
+            "False",
+            "2025-11-04 09:00",
+            "2025-11-04 09:15",
+        );
+        let lunch = khal_event("Lunch", "2025-11-04 12:00", "2025-11-04 13:00");
+        let output = alloc::format!("[{night}]\n[{night}, {standup}, {lunch}]\n[]\n");
+        let schedule = parse_events(
+            &NanoSerde,
+            &output,
+            &create_date("2025-11-03"),
+            Color(0xffffffff),
+        )
+        .unwrap();
+
+        let found = search(&schedule, "SHIFT");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title, "Night shift");
+        assert_eq!(found[0].range.start_time.hour, 22);
+
+        // The location is searched as well.
+        let found = search(&schedule, "room");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].uid, "Standup");
+        assert!(!found[0].is_long);
+        assert!(search(&schedule, "dinner").is_empty());
+    }
+
+    #[test]
+    fn test_original_ranges() {
+        let night = khal_event("night", "2025-11-03 22:00", "2025-11-04 02:00");
+        let holiday = khal_event_with(
+            "holiday",
+            "",
+            "True",
+            "2025-11-05 00:00",
+            "2025-11-05 00:00",
+        );
+        let night = khal_line(&[&night]);
+        let output = alloc::format!("{night}\n{night}\n{}\n", khal_line(&[&holiday]));
+        let schedule = parse_events(
+            &NanoSerde,
+            &output,
+            &create_date("2025-11-03"),
+            Color(0xffffffff),
+        )
+        .unwrap();
+
+        // Both halves of the night are cropped at midnight, but they keep the entire range.
+        let table = &schedule.short;
+        assert_eq!(table.titles, ["night", "night"]);
+        assert_eq!(table.all_day_flags, [false, false]);
+        assert_eq!(table.event_ranges[1].start_time.hour, 0);
+        for original in table.original_ranges.iter() {
+            assert_eq!(original.start_date, create_date("2025-11-03"));
+            assert_eq!(original.start_time.hour, 22);
+            assert_eq!(original.end_date, create_date("2025-11-04"));
+            assert_eq!(original.end_time.hour, 2);
+        }
+        assert_eq!(schedule.long.titles, ["holiday"]);
+        assert_eq!(schedule.long.all_day_flags, [true]);
+    }
+
+    #[test]
+    fn test_parse_search_output() {
+        let event = |title: &str, date: &str, all_day: &str| {
+            let start = alloc::format!("{date} 10:00");
+            let end = alloc::format!("{date} 11:00");
+            khal_line(&[&khal_event_with(title, "", all_day, &start, &end)])
+        };
+
+        let output = alloc::format!(
+            "{}\n{}\n",
+            event("review", "2025-11-05", "False"),
+            event("holiday", "2025-11-03", "True"),
+        );
+        let found = parse_search_output(&NanoSerde, &output).unwrap();
+        let titles: Vec<&str> = found.iter().map(|found| found.title.as_str()).collect();
+        assert_eq!(titles, ["holiday", "review"]);
+        assert!(found[0].is_long);
+        assert_eq!(found[0].range.end_time.hour, 23);
+        assert!(!found[1].is_long);
+
+        let found = parse_search_output(&NanoSerde, "No events found\n").unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_parse_events_in_zone() {
+        // The night event is provided by Khal twice: on Monday and on Tuesday.
+        let night = khal_line(&[&khal_event("night", "2025-11-03 22:00", "2025-11-04 02:00")]);
+        let morning = khal_line(&[&khal_event(
+            "morning",
+            "2025-11-05 09:00",
+            "2025-11-05 10:00",
+        )]);
+        let output = alloc::format!("{night}\n{night}\n{morning}\n[]\n[]\n[]\n[]\n");
+
+        let berlin = TimeZone::fixed(3600);
+        let new_york = TimeZone::fixed(-5 * 3600);
+        let start_date = create_date("2025-11-03");
+        let schedule = parse_events_in_zone(
+            &NanoSerde,
+            &output,
+            &start_date,
+            Color(0xffffffff),
+            &berlin,
+            &new_york,
+        )
+        .unwrap();
+
+        // The night event doesn't cross the midnight in New York.
+        assert_eq!(schedule.short.titles, ["night", "morning"]);
+        let night = &schedule.short.event_ranges[0];
+        assert_eq!(night.start_date, create_date("2025-11-03"));
+        assert_eq!(night.start_time.hour, 16);
+        assert_eq!(night.end_time.hour, 20);
+        let morning = &schedule.short.event_ranges[1];
+        assert_eq!(morning.start_time.hour, 3);
+    }
+
+    #[test]
+    fn test_agenda_stream() {
+        let mut stream = AgendaStream::new(&create_date("2025-11-03"));
+        let lines = [
+            morning("monday", "2025-11-03"),
+            String::from("[]"),
+            morning("wednesday", "2025-11-05"),
+            String::new(),
+            morning("ignored", "2025-11-07"),
+        ];
+        for line in lines.iter() {
+            assert!(stream.push_line(&NanoSerde, line).is_ok());
+        }
+
+        let schedule = stream.finish(Color(0xffffffff));
+        assert_eq!(schedule.short.titles, ["monday", "wednesday"]);
+        let wednesday = &schedule.short.event_ranges[1];
+        assert_eq!(wednesday.start_date, create_date("2025-11-05"));
+    }
+
+    #[test]
+    fn test_agenda_stream_with_margin() {
+        let event =
+            |title: &str, start: &str, end: &str| khal_line(&[&khal_event(title, start, end)]);
+        // The first event is moved to Monday in Berlin, the last one is moved to the next week.
+        let early = khal_event("early", "2025-11-02 20:00", "2025-11-02 21:00");
+        let sunday = khal_event("sunday", "2025-11-02 10:00", "2025-11-02 11:00");
+        let late = event("late", "2025-11-09 20:00", "2025-11-09 21:00");
+        let mut lines = Vec::from([khal_line(&[&sunday, &early])]);
+        lines.extend(core::iter::repeat_n(String::from("[]"), 6));
+        lines.extend([
+            event("evening", "2025-11-09 10:00", "2025-11-09 11:00"),
+            late,
+        ]);
+        lines.push(String::from("[]"));
+
+        let new_york = TimeZone::fixed(-5 * 3600);
+        let berlin = TimeZone::fixed(3600);
+        let start_date = create_date("2025-11-03");
+        let mut stream = AgendaStream::with_margin(&start_date, 7, 1);
+        for line in lines.iter() {
+            assert!(stream.push_line(&NanoSerde, line).is_ok());
+        }
+
+        let schedule = stream.finish_in_zone(Color(0xffffffff), &new_york, &berlin);
+        assert_eq!(schedule.short.titles, ["early", "evening"]);
+        let early = &schedule.short.event_ranges[0];
+        assert_eq!(early.start_date, start_date);
+        assert_eq!(early.start_time.hour, 2);
+
+        // Without the conversion the days of the margin are skipped.
+        let mut stream = AgendaStream::with_margin(&start_date, 7, 1);
+        for line in lines.iter() {
+            assert!(stream.push_line(&NanoSerde, line).is_ok());
+        }
+
+        let schedule = stream.finish(Color(0xffffffff));
+        assert_eq!(schedule.short.titles, ["evening"]);
+    }
+
+    #[test]
+    fn test_parse_events_from_sunday() {
+        let sunday = morning("sunday", "2025-11-02");
+        let saturday = morning("saturday", "2025-11-08");
+        let output = alloc::format!("{sunday}\n[]\n[]\n[]\n[]\n[]\n{saturday}\n");
+        let start_date = create_date("2025-11-02");
+        let schedule = parse_events(&NanoSerde, &output, &start_date, Color(0xffffffff)).unwrap();
+
+        assert_eq!(schedule.short.titles, ["sunday", "saturday"]);
+        let saturday = &schedule.short.event_ranges[1];
+        assert_eq!(saturday.start_date, create_date("2025-11-08"));
+    }
+
+    #[test]
+    fn test_parse_events_with_invalid_all_day() {
+        let odd = khal_event_with("odd", "", "Maybe", "2025-11-03 10:00", "2025-11-03 11:00");
+        let output = alloc::format!("{}\n", khal_line(&[&odd]));
+        let zone = TimeZone::fixed(0);
+        let start_date = create_date("2025-11-03");
+        let result = parse_events_in_zone(
+            &NanoSerde,
+            &output,
+            &start_date,
+            Color(0xffffffff),
+            &zone,
+            &zone,
+        );
+        assert!(matches!(result, Err(Error::InvalidAllDay(x)) if x == "Maybe"));
+    }
+
     //#[test]
     //fn test_long_event_clash() {
     //    let create_event = |title: &str, start_date: &str, end_date: &str| Event {
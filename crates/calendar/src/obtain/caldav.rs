@@ -0,0 +1,533 @@
+//! The bodies of the CalDAV requests (RFC 4791) and the parser of their responses.  The responses
+//! are WebDAV multistatus documents (RFC 4918).  The calendar objects found in them are parsed by
+//! [`super::ics`].
+//!
+//! The XML parser supports only what the multistatus documents need: the elements, the
+//! namespaces, the text, CDATA and the predefined entities.  The document type and the processing
+//! instructions are skipped.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Color;
+use crate::date::Date;
+
+const DAV: &str = "DAV:";
+const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+const APPLE: &str = "http://apple.com/ns/ical/";
+
+#[derive(Debug)]
+pub enum Error {
+    /// The document is not well-formed.
+    InvalidXml,
+    /// The root element of the document is not `DAV:multistatus`.
+    NotMultistatus,
+}
+
+/// The body of `PROPFIND` which discovers the calendars.  The request is sent with `Depth: 1` to
+/// the calendar home.
+pub const PROPFIND_CALENDARS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:a="http://apple.com/ns/ical/">
+  <d:prop>
+    <d:resourcetype/>
+    <d:displayname/>
+    <a:calendar-color/>
+  </d:prop>
+</d:propfind>
+"#;
+
+/// The body of `REPORT` which requests the events taking place between `start` and `end`.  The
+/// dates are taken as the midnight in UTC.  The server returns the entire object of a repeating
+/// event if any of its occurrences takes place within the range.
+pub fn calendar_query(start: &Date, end: &Date) -> String {
+    let format_date = |date: &Date| {
+        let Date { year, month, day } = date;
+        format!("{year:04}{month:02}{day:02}T000000Z")
+    };
+
+    let filter = format!(
+        r#"<c:comp-filter name="VEVENT"><c:time-range start="{}" end="{}"/></c:comp-filter>"#,
+        format_date(start),
+        format_date(end),
+    );
+    query(&filter)
+}
+
+/// The body of `REPORT` which requests the event with the `uid`.
+pub fn uid_query(uid: &str) -> String {
+    let filter = format!(
+        r#"<c:comp-filter name="VEVENT"><c:prop-filter name="UID"><c:text-match collation="i;octet">{}</c:text-match></c:prop-filter></c:comp-filter>"#,
+        escape(uid),
+    );
+    query(&filter)
+}
+
+fn query(event_filter: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:getetag/>
+    <c:calendar-data/>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">{event_filter}</c:comp-filter>
+  </c:filter>
+</c:calendar-query>
+"#
+    )
+}
+
+/// The calendar collection found by [`parse_calendars`].
+pub struct CalendarCollection {
+    /// The path of the collection on the server.  E.g. `/alex/work/`.
+    pub href: String,
+    /// The display name of the collection.  If it's absent, the last segment of `href` is taken.
+    pub name: String,
+    pub color: Option<Color>,
+}
+
+/// The calendar object (a.k.a. the `.ics` file) found by [`parse_calendar_objects`].
+#[derive(Debug)]
+pub struct CalendarObject {
+    pub href: String,
+    /// The version of the object.  It's required to change the object safely.
+    pub etag: Option<String>,
+    pub data: String,
+}
+
+/// Finds the calendar collections in the response to [`PROPFIND_CALENDARS`].  The other resources
+/// (e.g. the address books or the calendar objects) are skipped.
+pub fn parse_calendars(multistatus: &str) -> Result<Vec<CalendarCollection>, Error> {
+    let root = parse_multistatus(multistatus)?;
+    let calendars = root
+        .children(DAV, "response")
+        .filter_map(|response| {
+            let href = response.child(DAV, "href")?.text();
+            let properties = found_properties(response);
+            let is_calendar = properties
+                .clone()
+                .filter_map(|prop| prop.child(DAV, "resourcetype"))
+                .any(|resource_type| resource_type.child(CALDAV, "calendar").is_some());
+            if !is_calendar {
+                return None;
+            }
+
+            let name = properties
+                .clone()
+                .filter_map(|prop| prop.child(DAV, "displayname"))
+                .map(Element::text)
+                .find(|name| !name.is_empty())
+                .unwrap_or_else(|| {
+                    let segment = href.trim_end_matches('/').rsplit('/').next();
+                    String::from(segment.unwrap_or_default())
+                });
+            let color = properties
+                .filter_map(|prop| prop.child(APPLE, "calendar-color"))
+                .find_map(|color| Color::from_hex(&color.text()));
+            Some(CalendarCollection { href, name, color })
+        })
+        .collect();
+    Ok(calendars)
+}
+
+/// Takes the calendar objects from the response to [`calendar_query`] or [`uid_query`].
+pub fn parse_calendar_objects(multistatus: &str) -> Result<Vec<CalendarObject>, Error> {
+    let root = parse_multistatus(multistatus)?;
+    let objects = root
+        .children(DAV, "response")
+        .filter_map(|response| {
+            let href = response.child(DAV, "href")?.text();
+            let data = found_properties(response)
+                .filter_map(|prop| prop.child(CALDAV, "calendar-data"))
+                .map(Element::text)
+                .next()?;
+            let etag = found_properties(response)
+                .filter_map(|prop| prop.child(DAV, "getetag"))
+                .map(Element::text)
+                .next();
+            Some(CalendarObject { href, etag, data })
+        })
+        .collect();
+    Ok(objects)
+}
+
+fn parse_multistatus(input: &str) -> Result<Element, Error> {
+    let root = parse_document(input)?;
+    if root.namespace != DAV || root.name != "multistatus" {
+        return Err(Error::NotMultistatus);
+    }
+
+    Ok(root)
+}
+
+/// The properties (`DAV:prop`) of the response which are found.  The properties which are not
+/// found are given with a status other than 200.
+fn found_properties(response: &Element) -> impl Iterator<Item = &Element> + Clone {
+    response
+        .children(DAV, "propstat")
+        .filter(|propstat| {
+            propstat
+                .child(DAV, "status")
+                .is_some_and(|status| status.text().split(' ').nth(1) == Some("200"))
+        })
+        .filter_map(|propstat| propstat.child(DAV, "prop"))
+}
+
+#[derive(Debug)]
+struct Element {
+    namespace: String,
+    name: String,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn children<'e>(
+        &'e self,
+        namespace: &'e str,
+        name: &'e str,
+    ) -> impl Iterator<Item = &'e Element> + Clone {
+        self.children.iter().filter_map(move |node| match node {
+            Node::Element(e) if e.namespace == namespace && e.name == name => Some(e),
+            _ => None,
+        })
+    }
+
+    fn child<'e>(&'e self, namespace: &'e str, name: &'e str) -> Option<&'e Element> {
+        self.children(namespace, name).next()
+    }
+
+    /// The text of the element without the leading and trailing whitespace.
+    fn text(&self) -> String {
+        let mut ret = String::new();
+        for node in self.children.iter() {
+            if let Node::Text(text) = node {
+                ret.push_str(text);
+            }
+        }
+        String::from(ret.trim())
+    }
+}
+
+/// The element being parsed along with the namespaces it declares.
+struct OpenElement<'x> {
+    qualified_name: &'x str,
+    element: Element,
+    /// The pairs of the prefix and the namespace.  The default namespace has the empty prefix.
+    namespaces: Vec<(&'x str, String)>,
+}
+
+fn resolve_namespace(stack: &[OpenElement], prefix: &str) -> String {
+    stack
+        .iter()
+        .rev()
+        .flat_map(|open| open.namespaces.iter())
+        .find(|(p, _)| *p == prefix)
+        .map(|(_, namespace)| namespace.clone())
+        .unwrap_or_default()
+}
+
+fn parse_document(input: &str) -> Result<Element, Error> {
+    let mut stack: Vec<OpenElement> = Vec::new();
+    let mut root: Option<Element> = None;
+    let mut rest = input;
+    loop {
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..text_end];
+        if let Some(open) = stack.last_mut() {
+            open.element.children.push(Node::Text(unescape(text)?));
+        } else if !text.trim().is_empty() {
+            return Err(Error::InvalidXml);
+        }
+
+        rest = &rest[text_end..];
+        if rest.is_empty() {
+            break;
+        }
+
+        let skipped = [("<?", "?>"), ("<!--", "-->")];
+        if let Some((_, end)) = skipped.iter().find(|(start, _)| rest.starts_with(start)) {
+            let end_index = rest.find(end).ok_or(Error::InvalidXml)?;
+            rest = &rest[end_index + end.len()..];
+            continue;
+        }
+
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end_index = cdata.find("]]>").ok_or(Error::InvalidXml)?;
+            let open = stack.last_mut().ok_or(Error::InvalidXml)?;
+            let text = String::from(&cdata[..end_index]);
+            open.element.children.push(Node::Text(text));
+            rest = &cdata[end_index + 3..];
+            continue;
+        }
+
+        if rest.starts_with("<!") {
+            let end_index = rest.find('>').ok_or(Error::InvalidXml)?;
+            rest = &rest[end_index + 1..];
+            continue;
+        }
+
+        if let Some(end_tag) = rest.strip_prefix("</") {
+            let end_index = end_tag.find('>').ok_or(Error::InvalidXml)?;
+            let open = stack.pop().ok_or(Error::InvalidXml)?;
+            if open.qualified_name != end_tag[..end_index].trim() {
+                return Err(Error::InvalidXml);
+            }
+
+            rest = &end_tag[end_index + 1..];
+            match stack.last_mut() {
+                Some(parent) => parent.element.children.push(Node::Element(open.element)),
+                None => root = Some(open.element),
+            }
+            continue;
+        }
+
+        let (tag, after_tag) = split_start_tag(&rest[1..])?;
+        rest = after_tag;
+        let (tag, is_empty) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let qualified_name = &tag[..name_end];
+        let mut namespaces: Vec<(&str, String)> = Vec::new();
+        for (name, value) in parse_attributes(&tag[name_end..])? {
+            if name == "xmlns" {
+                namespaces.push(("", value));
+            } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+                namespaces.push((prefix, value));
+            }
+        }
+
+        let (prefix, name) = qualified_name
+            .split_once(':')
+            .unwrap_or(("", qualified_name));
+        let namespace = namespaces
+            .iter()
+            .find(|(p, _)| *p == prefix)
+            .map(|(_, namespace)| namespace.clone())
+            .unwrap_or_else(|| resolve_namespace(&stack, prefix));
+        let element = Element {
+            namespace,
+            name: String::from(name),
+            children: Vec::new(),
+        };
+
+        if is_empty {
+            match stack.last_mut() {
+                Some(parent) => parent.element.children.push(Node::Element(element)),
+                None => root = Some(element),
+            }
+        } else {
+            stack.push(OpenElement {
+                qualified_name,
+                element,
+                namespaces,
+            });
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(Error::InvalidXml);
+    }
+
+    root.ok_or(Error::InvalidXml)
+}
+
+/// Splits the start tag (without the leading `<`) from the rest of the input.  The `>` inside of
+/// the quoted values of the attributes doesn't end the tag.
+fn split_start_tag(input: &str) -> Result<(&str, &str), Error> {
+    let mut quote: Option<char> = None;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Ok((input[..i].trim_end(), &input[i + 1..])),
+            _ => (),
+        }
+    }
+
+    Err(Error::InvalidXml)
+}
+
+fn parse_attributes(input: &str) -> Result<Vec<(&str, String)>, Error> {
+    let mut ret = Vec::new();
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        let (name, after_name) = rest.split_once('=').ok_or(Error::InvalidXml)?;
+        let after_name = after_name.trim_start();
+        let quote = after_name.chars().next().ok_or(Error::InvalidXml)?;
+        if quote != '"' && quote != '\'' {
+            return Err(Error::InvalidXml);
+        }
+
+        let value_and_rest = &after_name[1..];
+        let value_end = value_and_rest.find(quote).ok_or(Error::InvalidXml)?;
+        ret.push((name.trim(), unescape(&value_and_rest[..value_end])?));
+        rest = value_and_rest[value_end + 1..].trim_start();
+    }
+
+    Ok(ret)
+}
+
+fn unescape(input: &str) -> Result<String, Error> {
+    let mut ret = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        ret.push_str(&rest[..start]);
+        let end = rest[start..].find(';').ok_or(Error::InvalidXml)? + start;
+        let entity = &rest[start + 1..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16),
+                    None => entity.strip_prefix('#').ok_or(Error::InvalidXml)?.parse(),
+                };
+                code.ok()
+                    .and_then(char::from_u32)
+                    .ok_or(Error::InvalidXml)?
+            }
+        };
+        ret.push(c);
+        rest = &rest[end + 1..];
+    }
+
+    ret.push_str(rest);
+    Ok(ret)
+}
+
+fn escape(input: &str) -> String {
+    let mut ret = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '&' => ret.push_str("&amp;"),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDARS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<multistatus xmlns="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <response>
+    <href>/alex/</href>
+    <propstat>
+      <prop><resourcetype><collection/></resourcetype></prop>
+      <status>HTTP/1.1 200 OK</status>
+    </propstat>
+  </response>
+  <response>
+    <href>/alex/work/</href>
+    <propstat>
+      <prop>
+        <resourcetype><collection/><C:calendar/></resourcetype>
+        <displayname>Work &amp; Co</displayname>
+        <ICAL:calendar-color xmlns:ICAL="http://apple.com/ns/ical/">#ff8000</ICAL:calendar-color>
+      </prop>
+      <status>HTTP/1.1 200 OK</status>
+    </propstat>
+  </response>
+  <response>
+    <href>/alex/home/</href>
+    <propstat>
+      <prop><resourcetype><collection/><C:calendar/></resourcetype></prop>
+      <status>HTTP/1.1 200 OK</status>
+    </propstat>
+    <propstat>
+      <prop><displayname>Ignored</displayname></prop>
+      <status>HTTP/1.1 404 Not Found</status>
+    </propstat>
+  </response>
+</multistatus>
+"#;
+
+    #[test]
+    fn test_parse_calendars() {
+        let calendars = parse_calendars(CALENDARS).unwrap();
+        let [work, home] = calendars.as_slice() else {
+            panic!("there must be 2 calendars");
+        };
+
+        assert_eq!(work.href, "/alex/work/");
+        assert_eq!(work.name, "Work & Co");
+        assert!(work.color == Some(Color(0xff8000ff)));
+        assert_eq!(home.href, "/alex/home/");
+        assert_eq!(home.name, "home");
+        assert!(home.color.is_none());
+    }
+
+    #[test]
+    fn test_parse_calendar_objects() {
+        let input = r#"<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+<d:response>
+<d:href>/alex/work/standup.ics</d:href>
+<d:propstat>
+<d:prop>
+<d:getetag>"1"</d:getetag>
+<cal:calendar-data><![CDATA[BEGIN:VCALENDAR
+END:VCALENDAR]]></cal:calendar-data>
+</d:prop>
+<d:status>HTTP/1.1 200 OK</d:status>
+</d:propstat>
+</d:response>
+<d:response>
+<d:href>/alex/work/review.ics</d:href>
+<d:propstat>
+<d:prop><cal:calendar-data>SUMMARY:a &lt; b</cal:calendar-data></d:prop>
+<d:status>HTTP/1.1 200 OK</d:status>
+</d:propstat>
+</d:response>
+</d:multistatus>"#;
+
+        let objects = parse_calendar_objects(input).unwrap();
+        let [standup, review] = objects.as_slice() else {
+            panic!("there must be 2 objects");
+        };
+
+        assert_eq!(standup.href, "/alex/work/standup.ics");
+        assert_eq!(standup.etag.as_deref(), Some("\"1\""));
+        assert_eq!(standup.data, "BEGIN:VCALENDAR\nEND:VCALENDAR");
+        assert!(review.etag.is_none());
+        assert_eq!(review.data, "SUMMARY:a < b");
+    }
+
+    #[test]
+    fn test_parse_invalid_document() {
+        assert!(matches!(
+            parse_calendars("<d:multistatus xmlns:d=\"DAV:\"><d:response>"),
+            Err(Error::InvalidXml)
+        ));
+        assert!(matches!(
+            parse_calendars("<propfind xmlns=\"DAV:\"/>"),
+            Err(Error::NotMultistatus)
+        ));
+    }
+
+    #[test]
+    fn test_calendar_query() {
+        let query = calendar_query(&Date::new::<2025, 11, 1>(), &Date::new::<2025, 11, 12>());
+        assert!(
+            query.contains(r#"<c:time-range start="20251101T000000Z" end="20251112T000000Z"/>"#)
+        );
+        assert!(uid_query("a&b@example.com").contains(">a&amp;b@example.com<"));
+    }
+}
@@ -0,0 +1,1197 @@
+//! The parser of the iCalendar format (RFC 5545).  It turns the `VEVENT` components into the same
+//! [`WeekScheduleWithLanes`] which is built from the output of Khal.  The parser is meant for the
+//! calendars stored in a directory tree (a.k.a. vdir) by vdirsyncer.  A vdir has a directory per
+//! calendar.  Every directory keeps the events in `.ics` files.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use super::{WeekScheduleWithLanes, schedule_events};
+use crate::date::{Date, MINUTES_PER_DAY, MINUTES_PER_HOUR, Time, shift_minutes};
+use crate::recurrence::{self, Recurrence};
+use crate::tz::{self, TimeZone, TimeZoneSource};
+use crate::{Color, EventRange, JsonInputEvent};
+
+#[derive(Debug)]
+pub enum Error {
+    /// `BEGIN` and `END` of a component don't match.
+    UnbalancedComponent,
+    /// A content line misses the colon between its name and its value.
+    InvalidContentLine,
+    InvalidDateTime,
+    InvalidDuration,
+    /// An event misses `DTSTART`.
+    MissingStart,
+    /// The object has no event with the given UID.
+    EventIsNotFound,
+    /// The time of a repeating event can't be changed.  See [`update_event`].
+    RepeatingEventIsNotMovable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Zone {
+    /// The time is not bound to any time zone.  E.g. `DTSTART:20251103T100000`.
+    Floating,
+    /// E.g. `DTSTART:20251103T100000Z`.
+    Utc,
+    /// E.g. `DTSTART;TZID=Europe/Berlin:20251103T100000`.
+    Named(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DateTime {
+    pub date: Date,
+    /// The value is `None` if the property is a date (`VALUE=DATE`).  Such an event takes entire
+    /// days.
+    pub time: Option<Time>,
+    pub zone: Zone,
+}
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub location: String,
+    pub url: String,
+    pub start: DateTime,
+    /// Either `DTEND` or `DTSTART` plus `DURATION`.  The end is exclusive as RFC 5545 requires.
+    pub end: DateTime,
+    pub recurrence: Recurrence,
+    /// The date of the occurrence of a repeating event which is replaced by this event
+    /// (`RECURRENCE-ID`).  The replaced event has the same UID.
+    pub recurrence_id: Option<Date>,
+}
+
+/// A calendar of a vdir with its events.
+pub struct Collection<'c> {
+    pub name: &'c str,
+    pub color: Option<Color>,
+    pub events: &'c [Event],
+}
+
+struct ContentLine<'l> {
+    name: &'l str,
+    /// The parameters including the leading semicolon.  E.g. `;TZID=Europe/Berlin;VALUE=DATE`.
+    parameters: &'l str,
+    value: &'l str,
+}
+
+#[derive(Default)]
+struct EventBuilder {
+    uid: String,
+    summary: String,
+    description: String,
+    location: String,
+    url: String,
+    start: Option<DateTime>,
+    end: Option<DateTime>,
+    duration_minutes: Option<i64>,
+    recurrence: Recurrence,
+    recurrence_id: Option<Date>,
+}
+
+impl EventBuilder {
+    fn build(self) -> Result<Event, Error> {
+        let start = self.start.ok_or(Error::MissingStart)?;
+        let end = match (self.end, self.duration_minutes) {
+            (Some(end), _) => end,
+            (None, Some(minutes)) => add_minutes(&start, minutes),
+            // RFC 5545: an event with a date lasts for a day, an event with a date-time ends when
+            // it starts.
+            (None, None) => match start.time {
+                None => add_minutes(&start, MINUTES_PER_DAY as i64),
+                Some(_) => start.clone(),
+            },
+        };
+
+        Ok(Event {
+            uid: self.uid,
+            summary: self.summary,
+            description: self.description,
+            location: self.location,
+            url: self.url,
+            start,
+            end,
+            recurrence: self.recurrence,
+            recurrence_id: self.recurrence_id,
+        })
+    }
+}
+
+fn add_minutes(value: &DateTime, minutes: i64) -> DateTime {
+    let time = value.time.clone().unwrap_or(Time::midnight());
+    let (date, time) = shift_minutes(&value.date, &time, minutes);
+    DateTime {
+        date,
+        time: value.time.as_ref().map(|_| time),
+        zone: value.zone.clone(),
+    }
+}
+
+/// Joins the lines which are split (a.k.a. folded) to fit 75 octets.  A line which starts with a
+/// space or a tab continues the previous one.
+fn unfold(input: &str) -> String {
+    let mut ret = String::with_capacity(input.len());
+    for line in input.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match line.strip_prefix([' ', '\t']) {
+            Some(continuation) => ret.push_str(continuation),
+            None => {
+                if !ret.is_empty() {
+                    ret.push('\n');
+                }
+                ret.push_str(line);
+            }
+        }
+    }
+    ret
+}
+
+fn parse_content_line(line: &str) -> Result<ContentLine<'_>, Error> {
+    let name_end = line.find([';', ':']).ok_or(Error::InvalidContentLine)?;
+    // The colon inside of a quoted parameter value doesn't separate the value.  E.g.
+    // `ALTREP="http://example.com"`.
+    let mut is_quoted = false;
+    let value_start = line[name_end..]
+        .char_indices()
+        .find(|(_, c)| match c {
+            '"' => {
+                is_quoted = !is_quoted;
+                false
+            }
+            ':' => !is_quoted,
+            _ => false,
+        })
+        .map(|(i, _)| name_end + i)
+        .ok_or(Error::InvalidContentLine)?;
+
+    Ok(ContentLine {
+        name: &line[..name_end],
+        parameters: &line[name_end..value_start],
+        value: &line[value_start + 1..],
+    })
+}
+
+fn find_parameter<'l>(parameters: &'l str, name: &str) -> Option<&'l str> {
+    let mut is_quoted = false;
+    parameters
+        .split(|c| match c {
+            '"' => {
+                is_quoted = !is_quoted;
+                false
+            }
+            ';' => !is_quoted,
+            _ => false,
+        })
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim_matches('"'))
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut ret = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            ret.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => ret.push('\n'),
+            Some(escaped) => ret.push(escaped),
+            None => ret.push('\\'),
+        }
+    }
+    ret
+}
+
+/// The date of `20251103` or `20251103T100000`.  See [`recurrence::parse_date`].
+fn parse_date(value: &str) -> Result<Date, Error> {
+    recurrence::parse_date(value).map_err(|_| Error::InvalidDateTime)
+}
+
+pub(crate) fn parse_date_time(parameters: &str, value: &str) -> Result<DateTime, Error> {
+    let date = parse_date(value)?;
+    let is_date = find_parameter(parameters, "VALUE").is_some_and(|v| v == "DATE");
+    if is_date || value.len() == 8 {
+        return Ok(DateTime {
+            date,
+            time: None,
+            zone: Zone::Floating,
+        });
+    }
+
+    // format: 20251103T100000 or 20251103T100000Z
+    let time_str = value
+        .get(8..)
+        .and_then(|v| v.strip_prefix('T'))
+        .filter(|v| v.len() >= 4)
+        .ok_or(Error::InvalidDateTime)?;
+    let hour = u8::from_str(&time_str[0..2]).map_err(|_| Error::InvalidDateTime)?;
+    let minute = u8::from_str(&time_str[2..4]).map_err(|_| Error::InvalidDateTime)?;
+    let time = Time::try_new(hour, minute).map_err(|_| Error::InvalidDateTime)?;
+    let zone = if time_str.ends_with('Z') {
+        Zone::Utc
+    } else {
+        match find_parameter(parameters, "TZID") {
+            Some(tzid) => Zone::Named(String::from(tzid)),
+            None => Zone::Floating,
+        }
+    };
+
+    Ok(DateTime {
+        date,
+        time: Some(time),
+        zone,
+    })
+}
+
+/// Parses the list of the dates like `20251103T100000,20251110T100000`.  The time is dropped.
+fn parse_dates(value: &str) -> Result<Vec<Date>, Error> {
+    value.split(',').map(parse_date).collect()
+}
+
+/// Parses the duration like `PT1H30M`, `P1D`, `P2W` or `-PT15M` into minutes.  The seconds are
+/// dropped.
+pub(crate) fn parse_duration(value: &str) -> Result<i64, Error> {
+    let (sign, value) = match value.as_bytes().first() {
+        Some(b'-') => (-1, &value[1..]),
+        Some(b'+') => (1, &value[1..]),
+        _ => (1, value),
+    };
+
+    let value = value.strip_prefix('P').ok_or(Error::InvalidDuration)?;
+    let mut minutes: i64 = 0;
+    let mut number: Option<i64> = None;
+    let mut is_time = false;
+    for c in value.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            number = Some(number.unwrap_or(0) * 10 + digit as i64);
+            continue;
+        }
+
+        if c == 'T' {
+            is_time = true;
+            continue;
+        }
+
+        let n = number.take().ok_or(Error::InvalidDuration)?;
+        minutes += match (c, is_time) {
+            ('W', false) => n * 7 * MINUTES_PER_DAY as i64,
+            ('D', false) => n * MINUTES_PER_DAY as i64,
+            ('H', true) => n * MINUTES_PER_HOUR as i64,
+            ('M', true) => n,
+            ('S', true) => n / 60,
+            _ => return Err(Error::InvalidDuration),
+        };
+    }
+
+    if number.is_some() {
+        return Err(Error::InvalidDuration);
+    }
+
+    Ok(sign * minutes)
+}
+
+/// Parses the events (`VEVENT`) of an iCalendar object.  The other components, and the
+/// components nested into the events (e.g. `VALARM`) are skipped.
+pub fn parse_events(input: &str) -> Result<Vec<Event>, Error> {
+    let unfolded = unfold(input);
+    let mut events: Vec<Event> = Vec::new();
+    let mut event: Option<EventBuilder> = None;
+    // The depth of the components inside of the event being parsed.
+    let mut nested_depth: u32 = 0;
+    for line in unfolded.split('\n').filter(|line| !line.is_empty()) {
+        let content_line = parse_content_line(line)?;
+        let ContentLine {
+            name,
+            parameters,
+            value,
+        } = content_line;
+
+        if name.eq_ignore_ascii_case("BEGIN") {
+            match event {
+                Some(_) => nested_depth += 1,
+                None if value.eq_ignore_ascii_case("VEVENT") => {
+                    event = Some(EventBuilder::default())
+                }
+                None => (),
+            }
+            continue;
+        }
+
+        if name.eq_ignore_ascii_case("END") {
+            if nested_depth > 0 {
+                nested_depth -= 1;
+            } else if value.eq_ignore_ascii_case("VEVENT") {
+                let builder = event.take().ok_or(Error::UnbalancedComponent)?;
+                events.push(builder.build()?);
+            }
+            continue;
+        }
+
+        let Some(builder) = event.as_mut().filter(|_| nested_depth == 0) else {
+            continue;
+        };
+
+        match name.to_ascii_uppercase().as_str() {
+            "UID" => builder.uid = String::from(value),
+            "SUMMARY" => builder.summary = unescape_text(value),
+            "DESCRIPTION" => builder.description = unescape_text(value),
+            "LOCATION" => builder.location = unescape_text(value),
+            "URL" => builder.url = String::from(value),
+            "DTSTART" => builder.start = Some(parse_date_time(parameters, value)?),
+            "DTEND" => builder.end = Some(parse_date_time(parameters, value)?),
+            "DURATION" => builder.duration_minutes = Some(parse_duration(value)?),
+            // FIXME(alex): the rule which is not supported is dropped rather than the event.  The
+            // event is shown on DTSTART and RDATE only, and the user isn't told about it.
+            "RRULE" => builder.recurrence.rule = recurrence::Rule::from_str(value).ok(),
+            "RDATE" => builder.recurrence.dates.extend(parse_dates(value)?),
+            "EXDATE" => builder.recurrence.exceptions.extend(parse_dates(value)?),
+            "RECURRENCE-ID" => builder.recurrence_id = Some(parse_date(value)?),
+            _ => (),
+        }
+    }
+
+    if event.is_some() || nested_depth > 0 {
+        return Err(Error::UnbalancedComponent);
+    }
+
+    Ok(events)
+}
+
+/// The longest content line in octets.  A longer line is folded.
+const MAX_LINE_LENGTH: usize = 75;
+
+fn escape_text(value: &str) -> String {
+    let mut ret = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                ret.push('\\');
+                ret.push(c);
+            }
+            '\n' => ret.push_str("\\n"),
+            '\r' => (),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+/// Appends the content line to `output` and terminates it with CRLF.  The line is folded if it's
+/// longer than [`MAX_LINE_LENGTH`].  A line is never split within a UTF-8 sequence.
+fn push_content_line(output: &mut String, line: &str) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            output.push_str("\r\n ");
+            // The space of the continuation takes an octet.
+            length = 1;
+        }
+        output.push(c);
+        length += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+fn push_parsed_content_line(output: &mut String, line: &ContentLine) {
+    let ContentLine {
+        name,
+        parameters,
+        value,
+    } = line;
+    push_content_line(output, &format!("{name}{parameters}:{value}"));
+}
+
+fn format_date_time(name: &str, value: &DateTime) -> String {
+    let Date { year, month, day } = &value.date;
+    match (&value.time, &value.zone) {
+        (None, _) => format!("{name};VALUE=DATE:{year:04}{month:02}{day:02}"),
+        (Some(Time { hour, minute }), Zone::Floating) => {
+            format!("{name}:{year:04}{month:02}{day:02}T{hour:02}{minute:02}00")
+        }
+        (Some(Time { hour, minute }), Zone::Utc) => {
+            format!("{name}:{year:04}{month:02}{day:02}T{hour:02}{minute:02}00Z")
+        }
+        (Some(Time { hour, minute }), Zone::Named(tzid)) => {
+            format!("{name};TZID={tzid}:{year:04}{month:02}{day:02}T{hour:02}{minute:02}00")
+        }
+    }
+}
+
+/// Serializes the event into an iCalendar object with a single `VEVENT`.  `stamp` is the moment
+/// the object is created (`DTSTAMP`).  RFC 5545 requires it to be in UTC.
+///
+/// The recurrence of the event is not written.  The function is meant for the events created by
+/// the user which don't repeat.
+pub fn write_event(event: &Event, stamp: &DateTime) -> String {
+    let mut ret = String::new();
+    push_content_line(&mut ret, "BEGIN:VCALENDAR");
+    push_content_line(&mut ret, "VERSION:2.0");
+    push_content_line(&mut ret, "PRODID:-//Semana//Semana//EN");
+    push_content_line(&mut ret, "BEGIN:VEVENT");
+    push_content_line(&mut ret, &format!("UID:{}", event.uid));
+    push_content_line(&mut ret, &format_date_time("DTSTAMP", stamp));
+    push_content_line(&mut ret, &format_date_time("DTSTART", &event.start));
+    push_content_line(&mut ret, &format_date_time("DTEND", &event.end));
+    push_content_line(
+        &mut ret,
+        &format!("SUMMARY:{}", escape_text(&event.summary)),
+    );
+
+    let optional_texts = [
+        ("DESCRIPTION", &event.description),
+        ("LOCATION", &event.location),
+    ];
+    for (name, value) in optional_texts {
+        if !value.is_empty() {
+            push_content_line(&mut ret, &format!("{name}:{}", escape_text(value)));
+        }
+    }
+
+    if !event.url.is_empty() {
+        push_content_line(&mut ret, &format!("URL:{}", event.url));
+    }
+
+    push_content_line(&mut ret, "END:VEVENT");
+    push_content_line(&mut ret, "END:VCALENDAR");
+    ret
+}
+
+/// The new values of the properties of a stored event.  See [`update_event`].
+pub struct EventPatch<'p> {
+    pub uid: &'p str,
+    pub summary: &'p str,
+    pub description: &'p str,
+    pub location: &'p str,
+    pub url: &'p str,
+    /// The new start and end of the event.  `None` keeps the time of the event.
+    pub range: Option<(DateTime, DateTime)>,
+}
+
+/// Replaces the properties of the event `patch.uid` in the iCalendar object.  The rest of the
+/// object is kept as it is, e.g. the alarms and the attendees.  The separate events which replace
+/// the occurrences of a repeating event (`RECURRENCE-ID`) are not changed.  The time of a repeating
+/// event is not changed either because the occurrence, which is shown to the user, is not the
+/// start of the series.
+///
+/// The lines of the object are folded anew.
+pub fn update_event(input: &str, patch: &EventPatch) -> Result<String, Error> {
+    let unfolded = unfold(input);
+    let mut ret = String::with_capacity(input.len());
+    let mut is_found = false;
+    // The lines of the event being read.  The event is written once it's known whether it's the
+    // one to update.
+    let mut event: Option<Vec<ContentLine>> = None;
+    let mut nested_depth: u32 = 0;
+    for line in unfolded.split('\n').filter(|line| !line.is_empty()) {
+        let content_line = parse_content_line(line)?;
+        let Some(lines) = event.as_mut() else {
+            if content_line.name.eq_ignore_ascii_case("BEGIN")
+                && content_line.value.eq_ignore_ascii_case("VEVENT")
+            {
+                event = Some(Vec::from([content_line]));
+            } else {
+                push_content_line(&mut ret, line);
+            }
+            continue;
+        };
+
+        let name = content_line.name;
+        let is_end = name.eq_ignore_ascii_case("END");
+        let is_event_end = is_end && nested_depth == 0;
+        if name.eq_ignore_ascii_case("BEGIN") {
+            nested_depth += 1;
+        } else if is_end && nested_depth > 0 {
+            nested_depth -= 1;
+        }
+
+        lines.push(content_line);
+        if !is_event_end {
+            continue;
+        }
+
+        let lines = event.take().ok_or(Error::UnbalancedComponent)?;
+        if is_patched_event(&lines, patch.uid) {
+            is_found = true;
+            write_patched_event(&mut ret, &lines, patch)?;
+        } else {
+            for line in lines {
+                push_parsed_content_line(&mut ret, &line);
+            }
+        }
+    }
+
+    if event.is_some() {
+        return Err(Error::UnbalancedComponent);
+    }
+
+    if !is_found {
+        return Err(Error::EventIsNotFound);
+    }
+
+    Ok(ret)
+}
+
+/// The properties of the event which are given by the lines.  The lines of the nested components
+/// are skipped.  The first and the last lines are `BEGIN:VEVENT` and `END:VEVENT`.
+fn event_properties<'l, 'c>(
+    lines: &'l [ContentLine<'c>],
+) -> impl Iterator<Item = &'l ContentLine<'c>> {
+    let mut nested_depth: u32 = 0;
+    lines
+        .get(1..lines.len().saturating_sub(1))
+        .unwrap_or_default()
+        .iter()
+        .filter(move |line| {
+            if line.name.eq_ignore_ascii_case("BEGIN") {
+                nested_depth += 1;
+            } else if line.name.eq_ignore_ascii_case("END") {
+                nested_depth -= 1;
+                return false;
+            }
+
+            nested_depth == 0
+        })
+}
+
+fn is_patched_event(lines: &[ContentLine], uid: &str) -> bool {
+    let mut is_uid_matched = false;
+    for line in event_properties(lines) {
+        if line.name.eq_ignore_ascii_case("UID") && line.value == uid {
+            is_uid_matched = true;
+        } else if line.name.eq_ignore_ascii_case("RECURRENCE-ID") {
+            return false;
+        }
+    }
+
+    is_uid_matched
+}
+
+fn write_patched_event(
+    output: &mut String,
+    lines: &[ContentLine],
+    patch: &EventPatch,
+) -> Result<(), Error> {
+    const TEXTS: [&str; 4] = ["SUMMARY", "DESCRIPTION", "LOCATION", "URL"];
+    const TIMES: [&str; 3] = ["DTSTART", "DTEND", "DURATION"];
+    const RECURRENCE: [&str; 2] = ["RRULE", "RDATE"];
+    let is_one_of = |line: &ContentLine, names: &[&str]| {
+        names
+            .iter()
+            .any(|name| line.name.eq_ignore_ascii_case(name))
+    };
+
+    if patch.range.is_some() && event_properties(lines).any(|l| is_one_of(l, &RECURRENCE)) {
+        return Err(Error::RepeatingEventIsNotMovable);
+    }
+
+    let mut new_properties: Vec<String> =
+        Vec::from([format!("SUMMARY:{}", escape_text(patch.summary))]);
+    let optional_texts = [
+        ("DESCRIPTION", patch.description),
+        ("LOCATION", patch.location),
+    ];
+    for (name, value) in optional_texts {
+        if !value.is_empty() {
+            new_properties.push(format!("{name}:{}", escape_text(value)));
+        }
+    }
+
+    if !patch.url.is_empty() {
+        new_properties.push(format!("URL:{}", patch.url));
+    }
+
+    if let Some((start, end)) = &patch.range {
+        new_properties.push(format_date_time("DTSTART", start));
+        new_properties.push(format_date_time("DTEND", end));
+    }
+
+    // The new properties are written before the nested components as RFC 5545 requires.
+    let mut is_written = false;
+    let mut nested_depth: u32 = 0;
+    let last = lines.len().saturating_sub(1);
+    for (i, line) in lines.iter().enumerate() {
+        let is_nested_begin = i > 0 && line.name.eq_ignore_ascii_case("BEGIN");
+        if !is_written && (is_nested_begin || i == last) {
+            for property in new_properties.iter() {
+                push_content_line(output, property);
+            }
+            is_written = true;
+        }
+
+        if is_nested_begin {
+            nested_depth += 1;
+        } else if i != last && line.name.eq_ignore_ascii_case("END") {
+            nested_depth -= 1;
+        }
+
+        let is_replaced = nested_depth == 0
+            && (is_one_of(line, &TEXTS) || (patch.range.is_some() && is_one_of(line, &TIMES)));
+        if !is_replaced {
+            push_parsed_content_line(output, line);
+        }
+    }
+
+    Ok(())
+}
+
+/// The range of the event as it's given in the file.  The time is the local time of the zone of
+/// the event.  The end is exclusive.
+fn event_range(event: &Event) -> EventRange {
+    EventRange {
+        start_date: event.start.date.clone(),
+        start_time: event.start.time.clone().unwrap_or(Time::midnight()),
+        end_date: event.end.date.clone(),
+        end_time: event.end.time.clone().unwrap_or(Time::midnight()),
+    }
+}
+
+struct ZoneConverter<'z, S> {
+    local: &'z TimeZone,
+    utc: TimeZone,
+    zones: &'z S,
+}
+
+impl<'z, S: TimeZoneSource> ZoneConverter<'z, S> {
+    fn convert(&self, date: Date, time: Time, zone: &Zone) -> (Date, Time) {
+        let source: Option<&TimeZone> = match zone {
+            Zone::Floating => None,
+            Zone::Utc => Some(&self.utc),
+            Zone::Named(name) => self.zones.find(name),
+        };
+
+        // FIXME(alex): the zones which are not found (e.g. the Windows names like "W. Europe
+        // Standard Time") are treated as the local one.
+        match source {
+            Some(source) => tz::convert(&date, &time, source, self.local),
+            None => (date, time),
+        }
+    }
+
+    /// Moves the occurrence of the `event` to the local time zone.  The all-day events take the
+    /// same dates in every zone.
+    fn convert_range(&self, range: EventRange, event: &Event) -> EventRange {
+        if event.start.time.is_none() {
+            return range;
+        }
+
+        let EventRange {
+            start_date,
+            start_time,
+            end_date,
+            end_time,
+        } = range;
+        let (start_date, start_time) = self.convert(start_date, start_time, &event.start.zone);
+        let (end_date, end_time) = self.convert(end_date, end_time, &event.end.zone);
+        EventRange {
+            start_date,
+            start_time,
+            end_date,
+            end_time,
+        }
+    }
+}
+
+/// Converts the occurrence of the event into the form of Khal's output.  Unlike iCalendar, Khal
+/// provides the end of an event inclusively.  E.g. an all-day event which takes only 2025-11-03
+/// ends on 2025-11-03 rather than 2025-11-04.
+fn create_input_event(event: &Event, collection: &Collection, range: EventRange) -> JsonInputEvent {
+    let is_all_day = event.start.time.is_none();
+    let EventRange {
+        start_date,
+        start_time,
+        end_date,
+        end_time,
+    } = range;
+
+    let (end_date, end_time) = if is_all_day {
+        (
+            end_date.add_days(-1).max(start_date.clone()),
+            Time::last_minute(),
+        )
+    } else if end_date > start_date && end_time.total_minutes().0 == 0 {
+        // The event which ends at midnight doesn't take the following day.
+        (end_date.add_days(-1), Time::last_minute())
+    } else if (&end_date, end_time.total_minutes()) < (&start_date, start_time.total_minutes()) {
+        (start_date.clone(), start_time.clone())
+    } else {
+        (end_date, end_time)
+    };
+
+    JsonInputEvent {
+        description: event.description.clone(),
+        title: event.summary.clone(),
+        start_date,
+        start_time,
+        end_date,
+        end_time,
+        all_day: String::from(if is_all_day { "True" } else { "False" }),
+        calendar_color: collection.color,
+        url: event.url.clone(),
+        location: event.location.clone(),
+        calendar: String::from(collection.name),
+        uid: event.uid.clone(),
+    }
+}
+
+/// Builds the schedule of the week which starts on `start_date` from the events of the
+/// `collections`.  The events which don't take place during the week are skipped.  The time of
+/// the events is moved to the `local_zone`.  The zones of the events are looked for in `zones`.
+pub fn week_schedule<'c>(
+    collections: impl IntoIterator<Item = Collection<'c>>,
+    start_date: &Date,
+    default_calendar_color: Color,
+    local_zone: &TimeZone,
+    zones: &impl TimeZoneSource,
+) -> WeekScheduleWithLanes {
+    range_schedule(
+        collections,
+        start_date,
+        7,
+        default_calendar_color,
+        local_zone,
+        zones,
+    )
+}
+
+/// The same as [`week_schedule`], but the events are laid out over `day_count` days.
+pub fn range_schedule<'c>(
+    collections: impl IntoIterator<Item = Collection<'c>>,
+    start_date: &Date,
+    day_count: u16,
+    default_calendar_color: Color,
+    local_zone: &TimeZone,
+    zones: &impl TimeZoneSource,
+) -> WeekScheduleWithLanes {
+    let converter = ZoneConverter {
+        local: local_zone,
+        utc: TimeZone::utc(),
+        zones,
+    };
+
+    let last_day_in_the_range: Date = start_date.add_days(day_count as i16 - 1);
+    // The difference between two zones can reach 26 hours.  Given that, an occurrence of another
+    // zone can move into the range from 2 days away.
+    let window_start = start_date.add_days(-2);
+    let window_end = last_day_in_the_range.add_days(2);
+    let mut events: Vec<JsonInputEvent> = Vec::new();
+    for collection in collections {
+        // The occurrences of the repeating events which are replaced by the separate events.
+        let replaced: Vec<(&str, &Date)> = collection
+            .events
+            .iter()
+            .filter_map(|e| Some((e.uid.as_str(), e.recurrence_id.as_ref()?)))
+            .collect();
+
+        for event in collection.events {
+            let first = event_range(event);
+            let occurrences: Vec<EventRange> = if event.recurrence.is_empty() {
+                Vec::from([first])
+            } else {
+                event
+                    .recurrence
+                    .expand(&first, &window_start, &window_end)
+                    .into_iter()
+                    .filter(|o| !replaced.contains(&(event.uid.as_str(), &o.start_date)))
+                    .collect()
+            };
+
+            let week_events = occurrences
+                .into_iter()
+                .map(|occurrence| converter.convert_range(occurrence, event))
+                .map(|occurrence| create_input_event(event, &collection, occurrence))
+                .filter(|e| e.end_date >= *start_date)
+                .filter(|e| e.start_date <= last_day_in_the_range);
+            events.extend(week_events);
+        }
+    }
+
+    schedule_events(events, start_date, day_count, default_calendar_color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obtain::get_lanes;
+    use alloc::collections::BTreeMap;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//test//test//EN\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTART;TZID=Europe/Berlin:20251103T100000\r
+DTEND;TZID=Europe/Berlin:20251103T101500\r
+SUMMARY:Stand\r
+ up\r
+DESCRIPTION:First line\\nsecond line\\, with a comma\r
+LOCATION:Room 1\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Reminder\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:holiday@example.com\r
+DTSTART;VALUE=DATE:20251105\r
+DTEND;VALUE=DATE:20251107\r
+SUMMARY:Holiday\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:night@example.com\r
+DTSTART:20251106T220000Z\r
+DURATION:PT4H\r
+SUMMARY:Deploy\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_events() {
+        let events = parse_events(CALENDAR).unwrap();
+        let [standup, holiday, night] = events.as_slice() else {
+            panic!("there must be 3 events");
+        };
+
+        assert_eq!(standup.uid, "standup@example.com");
+        assert_eq!(standup.summary, "Standup");
+        assert_eq!(standup.description, "First line\nsecond line, with a comma");
+        assert_eq!(standup.location, "Room 1");
+        assert_eq!(
+            standup.start.zone,
+            Zone::Named(String::from("Europe/Berlin"))
+        );
+        assert_eq!(standup.end.time.as_ref().map(|t| t.minute), Some(15));
+
+        assert!(holiday.start.time.is_none());
+        assert_eq!(holiday.end.date, Date::new::<2025, 11, 7>());
+
+        assert_eq!(night.start.zone, Zone::Utc);
+        assert_eq!(night.end.date, Date::new::<2025, 11, 7>());
+        assert_eq!(night.end.time.as_ref().map(|t| t.hour), Some(2));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M").unwrap(), 90);
+        assert_eq!(parse_duration("P1D").unwrap(), 1440);
+        assert_eq!(parse_duration("P2W").unwrap(), 2 * 7 * 1440);
+        assert_eq!(parse_duration("-PT15M").unwrap(), -15);
+        assert!(parse_duration("PT1M2").is_err());
+        assert!(parse_duration("P1M").is_err());
+    }
+
+    #[test]
+    fn test_write_event() {
+        let event = Event {
+            uid: String::from("new@semana"),
+            summary: String::from("Review; part 1, part 2"),
+            description: "Ночь\n".repeat(20),
+            location: String::new(),
+            url: String::from("https://example.com"),
+            start: DateTime {
+                date: Date::new::<2025, 11, 3>(),
+                time: Some(Time {
+                    hour: 9,
+                    minute: 15,
+                }),
+                zone: Zone::Utc,
+            },
+            end: DateTime {
+                date: Date::new::<2025, 11, 3>(),
+                time: Some(Time {
+                    hour: 10,
+                    minute: 0,
+                }),
+                zone: Zone::Named(String::from("Europe/Berlin")),
+            },
+            recurrence: Recurrence::default(),
+            recurrence_id: None,
+        };
+
+        let stamp = DateTime {
+            date: Date::new::<2025, 11, 1>(),
+            time: Some(Time::midnight()),
+            zone: Zone::Utc,
+        };
+
+        let output = write_event(&event, &stamp);
+        assert!(output.contains("DTSTART:20251103T091500Z\r\n"));
+        assert!(output.contains("DTEND;TZID=Europe/Berlin:20251103T100000\r\n"));
+        assert!(output.contains("SUMMARY:Review\\; part 1\\, part 2\r\n"));
+        assert!(!output.contains("LOCATION"));
+        assert!(
+            output
+                .split("\r\n")
+                .all(|line| line.len() <= MAX_LINE_LENGTH)
+        );
+
+        let events = parse_events(&output).unwrap();
+        let [parsed] = events.as_slice() else {
+            panic!("there must be 1 event");
+        };
+        assert_eq!(parsed.uid, event.uid);
+        assert_eq!(parsed.summary, event.summary);
+        assert_eq!(parsed.description, event.description);
+        assert_eq!(parsed.url, event.url);
+        assert_eq!(parsed.start.zone, Zone::Utc);
+        assert_eq!(parsed.end.time.as_ref().map(|t| t.hour), Some(10));
+    }
+
+    #[test]
+    fn test_update_event() {
+        let patch = EventPatch {
+            uid: "standup@example.com",
+            summary: "Stand-up",
+            description: "",
+            location: "Room 2",
+            url: "https://example.com",
+            range: Some((
+                DateTime {
+                    date: Date::new::<2025, 11, 4>(),
+                    time: Some(Time {
+                        hour: 11,
+                        minute: 0,
+                    }),
+                    zone: Zone::Named(String::from("Europe/Berlin")),
+                },
+                DateTime {
+                    date: Date::new::<2025, 11, 4>(),
+                    time: Some(Time {
+                        hour: 11,
+                        minute: 30,
+                    }),
+                    zone: Zone::Named(String::from("Europe/Berlin")),
+                },
+            )),
+        };
+
+        let output = update_event(CALENDAR, &patch).unwrap();
+        // The alarm is kept, and the properties are written before it.
+        let alarm = output.find("BEGIN:VALARM").unwrap();
+        assert!(output.find("SUMMARY:Stand-up\r\n").unwrap() < alarm);
+        assert!(output.contains("DESCRIPTION:Reminder\r\n"));
+        assert!(!output.contains("First line"));
+
+        let events = parse_events(&output).unwrap();
+        let [standup, holiday, night] = events.as_slice() else {
+            panic!("there must be 3 events");
+        };
+        assert_eq!(standup.summary, "Stand-up");
+        assert_eq!(standup.description, "");
+        assert_eq!(standup.location, "Room 2");
+        assert_eq!(standup.url, "https://example.com");
+        assert_eq!(standup.start.date, Date::new::<2025, 11, 4>());
+        assert_eq!(standup.end.time.as_ref().map(|t| t.minute), Some(30));
+        assert_eq!(holiday.summary, "Holiday");
+        assert_eq!(night.summary, "Deploy");
+
+        let missing = EventPatch {
+            uid: "missing@example.com",
+            ..patch
+        };
+        assert!(matches!(
+            update_event(CALENDAR, &missing),
+            Err(Error::EventIsNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_unbalanced_event() {
+        let input = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20251103T100000\nEND:VCALENDAR\n";
+        assert!(matches!(
+            parse_events(input),
+            Err(Error::UnbalancedComponent)
+        ));
+    }
+
+    #[test]
+    fn test_week_schedule() {
+        let events = parse_events(CALENDAR).unwrap();
+        let collection = Collection {
+            name: "work",
+            color: Color::from_hex("#ff0000"),
+            events: &events,
+        };
+
+        let week_start = Date::new::<2025, 11, 3>();
+        let schedule = week_schedule(
+            [collection],
+            &week_start,
+            Color(0xffffffff),
+            &TimeZone::utc(),
+            &BTreeMap::new(),
+        );
+        let schedule = get_lanes(schedule, &week_start, 7);
+
+        assert_eq!(schedule.long.titles, ["Holiday"]);
+        let holiday = &schedule.long.event_ranges[0];
+        assert_eq!(holiday.end_date, Date::new::<2025, 11, 6>());
+
+        // The deployment crosses the midnight.  Given that, it's split into two short events.
+        assert_eq!(schedule.short.titles, ["Standup", "Deploy", "Deploy"]);
+        assert_eq!(schedule.short.obtain_calendar(0), Some("work"));
+        assert_eq!(schedule.short.obtain_location(0), Some("Room 1"));
+        let tail = &schedule.short.event_ranges[2];
+        assert_eq!(tail.start_date, Date::new::<2025, 11, 7>());
+        assert_eq!(tail.end_time.hour, 2);
+    }
+
+    #[test]
+    fn test_week_schedule_skips_other_weeks() {
+        let events = parse_events(CALENDAR).unwrap();
+        let collection = Collection {
+            name: "work",
+            color: None,
+            events: &events,
+        };
+
+        let week_start = Date::new::<2025, 11, 10>();
+        let schedule = week_schedule(
+            [collection],
+            &week_start,
+            Color(0xffffffff),
+            &TimeZone::utc(),
+            &BTreeMap::new(),
+        );
+        assert!(schedule.long.titles.is_empty());
+        assert!(schedule.short.titles.is_empty());
+    }
+
+    #[test]
+    fn test_week_schedule_repeating_event() {
+        let input = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:sync@example.com
+DTSTART:20251006T090000
+DTEND:20251006T093000
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR
+EXDATE:20251105T090000
+SUMMARY:Sync
+END:VEVENT
+BEGIN:VEVENT
+UID:sync@example.com
+RECURRENCE-ID:20251107T090000
+DTSTART:20251107T150000
+DTEND:20251107T153000
+SUMMARY:Sync (moved)
+END:VEVENT
+END:VCALENDAR
+";
+        let events = parse_events(input).unwrap();
+        let collection = Collection {
+            name: "work",
+            color: None,
+            events: &events,
+        };
+
+        let week_start = Date::new::<2025, 11, 3>();
+        let schedule = week_schedule(
+            [collection],
+            &week_start,
+            Color(0xffffffff),
+            &TimeZone::utc(),
+            &BTreeMap::new(),
+        );
+        assert_eq!(schedule.short.titles, ["Sync", "Sync (moved)"]);
+        let moved = &schedule.short.event_ranges[1];
+        assert_eq!(moved.start_date, Date::new::<2025, 11, 7>());
+        assert_eq!(moved.start_time.hour, 15);
+    }
+
+    #[test]
+    fn test_week_schedule_unsupported_recurrence() {
+        let input = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:watch@example.com
+DTSTART:20251103T090000
+DTEND:20251103T090100
+RRULE:FREQ=HOURLY
+RDATE:20251105T090000
+SUMMARY:Watch
+END:VEVENT
+BEGIN:VEVENT
+UID:lunch@example.com
+DTSTART:20251104T120000
+DTEND:20251104T130000
+SUMMARY:Lunch
+END:VEVENT
+END:VCALENDAR
+";
+        // The object is kept.  The event is shown on its start and on the additional date.
+        let events = parse_events(input).unwrap();
+        let collection = Collection {
+            name: "work",
+            color: None,
+            events: &events,
+        };
+
+        let week_start = Date::new::<2025, 11, 3>();
+        let schedule = week_schedule(
+            [collection],
+            &week_start,
+            Color(0xffffffff),
+            &TimeZone::utc(),
+            &BTreeMap::new(),
+        );
+        assert_eq!(schedule.short.titles, ["Watch", "Lunch", "Watch"]);
+        let rdate = &schedule.short.event_ranges[2];
+        assert_eq!(rdate.start_date, Date::new::<2025, 11, 5>());
+    }
+
+    #[test]
+    fn test_week_schedule_time_zones() {
+        let input = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:berlin@example.com
+DTSTART;TZID=Europe/Berlin:20251103T030000
+DTEND;TZID=Europe/Berlin:20251103T040000
+SUMMARY:Berlin
+END:VEVENT
+BEGIN:VEVENT
+UID:utc@example.com
+DTSTART:20251104T120000Z
+DTEND:20251104T130000Z
+SUMMARY:UTC
+END:VEVENT
+BEGIN:VEVENT
+UID:floating@example.com
+DTSTART:20251105T120000
+DTEND:20251105T130000
+SUMMARY:Floating
+END:VEVENT
+END:VCALENDAR
+";
+        let events = parse_events(input).unwrap();
+        let collection = Collection {
+            name: "work",
+            color: None,
+            events: &events,
+        };
+
+        let zones = BTreeMap::from([(String::from("Europe/Berlin"), TimeZone::fixed(3600))]);
+        let new_york = TimeZone::fixed(-5 * 3600);
+        let week_start = Date::new::<2025, 11, 3>();
+        let schedule = week_schedule(
+            [collection],
+            &week_start,
+            Color(0xffffffff),
+            &new_york,
+            &zones,
+        );
+
+        // The event in Berlin moves to the previous day which is out of the week.
+        assert_eq!(schedule.short.titles, ["UTC", "Floating"]);
+        let utc = &schedule.short.event_ranges[0];
+        assert_eq!(utc.start_time.hour, 7);
+        let floating = &schedule.short.event_ranges[1];
+        assert_eq!(floating.start_time.hour, 12);
+    }
+}
@@ -0,0 +1,682 @@
+//! The expansion of the repeating events (RFC 5545, section 3.3.10).  Khal expands the repeating
+//! events itself.  The other sources provide the rule (`RRULE`) along with the extra dates
+//! (`RDATE`) and the excluded dates (`EXDATE`).  Given that, the occurrences are computed here.
+//!
+//! The supported parts of the rule are `FREQ` (daily, weekly, monthly and yearly), `INTERVAL`,
+//! `COUNT`, `UNTIL`, `BYDAY`, `BYMONTHDAY`, `BYMONTH`, `BYSETPOS` and `WKST`.  An event repeats
+//! at most once a day.  Given that, the occurrences are dates, and the time of the occurrence is
+//! the time of the first one.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use crate::EventRange;
+use crate::date::{Date, DateStream, Weekday};
+
+#[derive(Debug)]
+pub enum Error {
+    MissingFrequency,
+    /// The frequency is either unknown or not supported.  E.g. `HOURLY`.
+    UnsupportedFrequency,
+    /// The part of the rule is either unknown or not supported.  E.g. `BYWEEKNO`.
+    UnsupportedPart,
+    InvalidValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// An item of `BYDAY`.  E.g. `MO`, `-1FR` or `+2TU`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeekdayNum {
+    /// The number of the weekday within the month or the year.  A negative number counts from
+    /// the end.  The value 0 means every weekday in the period.
+    pub ordinal: i8,
+    pub weekday: Weekday,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    /// The last date an occurrence can take place on.  It's inclusive.
+    pub until: Option<Date>,
+    pub by_day: Vec<WeekdayNum>,
+    /// The days of the month.  A negative number counts from the end of the month.
+    pub by_month_day: Vec<i8>,
+    pub by_month: Vec<u8>,
+    /// The positions of the occurrences within a period.  A negative number counts from the end.
+    pub by_set_pos: Vec<i16>,
+    pub week_start: Weekday,
+}
+
+/// All of the dates an event repeats on.
+#[derive(Debug, Clone, Default)]
+pub struct Recurrence {
+    pub rule: Option<Rule>,
+    /// The extra dates (`RDATE`).
+    pub dates: Vec<Date>,
+    /// The dates to skip (`EXDATE`).
+    pub exceptions: Vec<Date>,
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, Error> {
+    let weekday = match value {
+        "MO" => Weekday::Monday,
+        "TU" => Weekday::Tuesday,
+        "WE" => Weekday::Wednesday,
+        "TH" => Weekday::Thursday,
+        "FR" => Weekday::Friday,
+        "SA" => Weekday::Saturday,
+        "SU" => Weekday::Sunday,
+        _ => return Err(Error::InvalidValue),
+    };
+    Ok(weekday)
+}
+
+fn parse_weekday_num(value: &str) -> Result<WeekdayNum, Error> {
+    if value.len() < 2 || !value.is_ascii() {
+        return Err(Error::InvalidValue);
+    }
+
+    let (ordinal, weekday) = value.split_at(value.len() - 2);
+    let ordinal = match ordinal {
+        "" => 0,
+        // i8::from_str doesn't accept the plus
+        ordinal => i8::from_str(ordinal.strip_prefix('+').unwrap_or(ordinal))
+            .map_err(|_| Error::InvalidValue)?,
+    };
+
+    Ok(WeekdayNum {
+        ordinal,
+        weekday: parse_weekday(weekday)?,
+    })
+}
+
+fn parse_list<T: FromStr>(value: &str) -> Result<Vec<T>, Error> {
+    value
+        .split(',')
+        .map(|item| T::from_str(item.strip_prefix('+').unwrap_or(item)))
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(|_| Error::InvalidValue)
+}
+
+/// Parses the date part of `UNTIL`, `RDATE` or `EXDATE`.  E.g. `20251103` or `20251103T100000Z`.
+pub fn parse_date(value: &str) -> Result<Date, Error> {
+    let digits = value.get(0..8).ok_or(Error::InvalidValue)?;
+    let year = u16::from_str(&digits[0..4]).map_err(|_| Error::InvalidValue)?;
+    let month = u8::from_str(&digits[4..6]).map_err(|_| Error::InvalidValue)?;
+    let day = u8::from_str(&digits[6..8]).map_err(|_| Error::InvalidValue)?;
+    Date::try_new(year, month, day).map_err(|_| Error::InvalidValue)
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    /// Parses the value of `RRULE`.  E.g. `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut frequency: Option<Frequency> = None;
+        let mut rule = Rule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Monday,
+        };
+
+        for part in s.trim().split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or(Error::InvalidValue)?;
+            match String::from(key).to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(Error::UnsupportedFrequency),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = u32::from_str(value)
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or(Error::InvalidValue)?
+                }
+                "COUNT" => {
+                    rule.count = Some(u32::from_str(value).map_err(|_| Error::InvalidValue)?)
+                }
+                "UNTIL" => rule.until = Some(parse_date(value)?),
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(parse_weekday_num)
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => rule.by_month_day = parse_list(value)?,
+                "BYMONTH" => rule.by_month = parse_list(value)?,
+                "BYSETPOS" => rule.by_set_pos = parse_list(value)?,
+                "WKST" => rule.week_start = parse_weekday(value)?,
+                _ => return Err(Error::UnsupportedPart),
+            }
+        }
+
+        rule.frequency = frequency.ok_or(Error::MissingFrequency)?;
+        Ok(rule)
+    }
+}
+
+/// Resolves the day of the month which might be counted from the end.  E.g. -1 is the last day
+/// of the month.
+fn resolve_month_day(year: u16, month: u8, day: i8) -> Option<u8> {
+    let day_count = Date::month_day_count(year, month) as i16;
+    let day = match day as i16 {
+        d if d > 0 => d,
+        d => day_count + 1 + d,
+    };
+
+    (1..=day_count).contains(&day).then_some(day as u8)
+}
+
+/// Finds the `weekday` within the dates from `first` to `last` inclusively.
+fn find_weekdays(first: &Date, last: &Date, weekday: &WeekdayNum) -> Vec<Date> {
+    let offset = weekday.weekday.days_since(first.weekday());
+    let dates: Vec<Date> = DateStream::new(first.add_days(offset as i16))
+        .step_by(7)
+        .take_while(|date| date <= last)
+        .collect();
+
+    select_ordinal(dates, weekday.ordinal as i16)
+}
+
+fn select_ordinal(mut dates: Vec<Date>, ordinal: i16) -> Vec<Date> {
+    let index = match ordinal {
+        0 => return dates,
+        o if o > 0 => o as usize - 1,
+        o => match dates.len().checked_sub(o.unsigned_abs() as usize) {
+            Some(index) => index,
+            None => return Vec::new(),
+        },
+    };
+
+    if index < dates.len() {
+        Vec::from([dates.swap_remove(index)])
+    } else {
+        Vec::new()
+    }
+}
+
+impl Rule {
+    fn matches_weekday(&self, date: &Date) -> bool {
+        let weekday = date.weekday();
+        self.by_day.is_empty() || self.by_day.iter().any(|d| d.weekday == weekday)
+    }
+
+    fn matches_month_day(&self, date: &Date) -> bool {
+        self.by_month_day.is_empty()
+            || self
+                .by_month_day
+                .iter()
+                .any(|d| resolve_month_day(date.year, date.month, *d) == Some(date.day))
+    }
+
+    fn matches_month(&self, date: &Date) -> bool {
+        self.by_month.is_empty() || self.by_month.contains(&date.month)
+    }
+
+    /// The first day of the period which is `number` periods later than the one of `start`.
+    fn period_start(&self, start: &Date, number: u32) -> Option<Date> {
+        let date = match self.frequency {
+            Frequency::Daily => {
+                let days = start.calculate_total_days().checked_add_unsigned(number)?;
+                Date::from_days(days)
+            }
+            Frequency::Weekly => {
+                let week_start =
+                    start.add_days(-(start.weekday().days_since(self.week_start) as i16));
+                let days = (number as i32).checked_mul(7)?;
+                Date::from_days(week_start.calculate_total_days().checked_add(days)?)
+            }
+            Frequency::Monthly => {
+                let months =
+                    (start.year as u32 * 12 + start.month as u32 - 1).checked_add(number)?;
+                let year = u16::try_from(months / 12).ok()?;
+                Date::try_new(year, (months % 12) as u8 + 1, 1).ok()?
+            }
+            Frequency::Yearly => {
+                let year = u16::try_from(start.year as u32 + number).ok()?;
+                Date::try_new(year, 1, 1).ok()?
+            }
+        };
+
+        (date.year <= 9999).then_some(date)
+    }
+
+    fn month_candidates(&self, start: &Date, year: u16, month: u8) -> Vec<Date> {
+        let create = |day: u8| Date::try_new(year, month, day).ok();
+        if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .filter_map(|day| resolve_month_day(year, month, *day))
+                .filter_map(create)
+                .filter(|date| self.matches_weekday(date))
+                .collect()
+        } else if !self.by_day.is_empty() {
+            let first = Date::try_new(year, month, 1).expect("the first day of the month is valid");
+            let last = create(Date::month_day_count(year, month))
+                .expect("the last day of the month is valid");
+            self.by_day
+                .iter()
+                .flat_map(|weekday| find_weekdays(&first, &last, weekday))
+                .collect()
+        } else {
+            create(start.day).into_iter().collect()
+        }
+    }
+
+    /// The dates of the period which starts on `period_start`.  The dates aren't sorted.
+    fn candidates(&self, start: &Date, period_start: &Date) -> Vec<Date> {
+        match self.frequency {
+            Frequency::Daily => Some(period_start.clone())
+                .filter(|date| self.matches_month(date))
+                .filter(|date| self.matches_month_day(date))
+                .filter(|date| self.matches_weekday(date))
+                .into_iter()
+                .collect(),
+            Frequency::Weekly => {
+                let weekdays: Vec<Weekday> = match self.by_day.as_slice() {
+                    [] => Vec::from([start.weekday()]),
+                    by_day => by_day.iter().map(|d| d.weekday).collect(),
+                };
+
+                weekdays
+                    .into_iter()
+                    .map(|w| period_start.add_days(w.days_since(self.week_start) as i16))
+                    .filter(|date| self.matches_month(date))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                if !self.matches_month(period_start) {
+                    return Vec::new();
+                }
+
+                self.month_candidates(start, period_start.year, period_start.month)
+            }
+            Frequency::Yearly => {
+                let year = period_start.year;
+                if self.by_month.is_empty()
+                    && self.by_month_day.is_empty()
+                    && !self.by_day.is_empty()
+                {
+                    // The weekdays are numbered within the year.  E.g. the 20th Monday.
+                    let last =
+                        Date::try_new(year, 12, 31).expect("the last day of the year is valid");
+                    return self
+                        .by_day
+                        .iter()
+                        .flat_map(|weekday| find_weekdays(period_start, &last, weekday))
+                        .collect();
+                }
+
+                let months: Vec<u8> = match self.by_month.as_slice() {
+                    [] if self.by_month_day.is_empty() && self.by_day.is_empty() => {
+                        Vec::from([start.month])
+                    }
+                    [] => (1..=12).collect(),
+                    by_month => Vec::from(by_month),
+                };
+
+                months
+                    .into_iter()
+                    .flat_map(|month| self.month_candidates(start, year, month))
+                    .collect()
+            }
+        }
+    }
+
+    fn select_positions(&self, candidates: Vec<Date>) -> Vec<Date> {
+        if self.by_set_pos.is_empty() {
+            return candidates;
+        }
+
+        let mut ret: Vec<Date> = self
+            .by_set_pos
+            .iter()
+            .flat_map(|position| select_ordinal(candidates.clone(), *position))
+            .collect();
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+
+    /// Computes the dates of the occurrences from `from` to `to` inclusively.  The first
+    /// occurrence is `start`.  It's the value of `DTSTART`.
+    pub fn occurrences(&self, start: &Date, from: &Date, to: &Date) -> Vec<Date> {
+        let mut ret: Vec<Date> = Vec::new();
+        // RFC 5545: DTSTART is always the first occurrence even if it doesn't match the rule.
+        if start >= from && start <= to {
+            ret.push(start.clone());
+        }
+
+        let mut count: u32 = 1;
+        let mut number: u32 = 0;
+        while let Some(period_start) = self.period_start(start, number) {
+            if period_start > *to {
+                break;
+            }
+
+            let mut candidates = self.candidates(start, &period_start);
+            candidates.sort();
+            candidates.dedup();
+            for date in self.select_positions(candidates) {
+                if date <= *start {
+                    continue;
+                }
+
+                let is_over = self.count.is_some_and(|c| count >= c)
+                    || self.until.as_ref().is_some_and(|until| date > *until)
+                    || date > *to;
+                if is_over {
+                    return ret;
+                }
+
+                count += 1;
+                if date >= *from {
+                    ret.push(date);
+                }
+            }
+
+            let Some(next) = number.checked_add(self.interval) else {
+                break;
+            };
+            number = next;
+        }
+
+        ret
+    }
+}
+
+impl Recurrence {
+    pub fn is_empty(&self) -> bool {
+        self.rule.is_none() && self.dates.is_empty()
+    }
+
+    /// Computes the occurrences of the event which takes place within the dates from
+    /// `window_start` to `window_end` inclusively.  The occurrence which starts before the window
+    /// but ends within it is included as well.  `first` is the first occurrence of the event.
+    pub fn expand(
+        &self,
+        first: &EventRange,
+        window_start: &Date,
+        window_end: &Date,
+    ) -> Vec<EventRange> {
+        let duration_days: i32 = first.end_date.subtract(&first.start_date).max(0);
+        let earliest_start: Date =
+            Date::from_days((window_start.calculate_total_days() - duration_days).max(0));
+
+        let mut starts: Vec<Date> = match &self.rule {
+            Some(rule) => rule.occurrences(&first.start_date, &earliest_start, window_end),
+            None => Some(first.start_date.clone())
+                .filter(|date| *date >= earliest_start && date <= window_end)
+                .into_iter()
+                .collect(),
+        };
+
+        starts.extend(
+            self.dates
+                .iter()
+                .filter(|date| **date >= earliest_start && *date <= window_end)
+                .cloned(),
+        );
+        starts.sort();
+        starts.dedup();
+        starts
+            .into_iter()
+            .filter(|date| !self.exceptions.contains(date))
+            .map(|start_date| EventRange {
+                end_date: Date::from_days(start_date.calculate_total_days() + duration_days),
+                start_date,
+                start_time: first.start_time.clone(),
+                end_time: first.end_time.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::Time;
+
+    fn occurrences(rule: &str, start: Date, from: Date, to: Date) -> Vec<Date> {
+        let rule = Rule::from_str(rule).unwrap();
+        rule.occurrences(&start, &from, &to)
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule =
+            Rule::from_str("FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR,+2TU,WE;UNTIL=20261231T235959Z")
+                .unwrap();
+        assert_eq!(rule.frequency, Frequency::Monthly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.until, Some(Date::new::<2026, 12, 31>()));
+        assert_eq!(
+            rule.by_day,
+            [
+                WeekdayNum {
+                    ordinal: -1,
+                    weekday: Weekday::Friday
+                },
+                WeekdayNum {
+                    ordinal: 2,
+                    weekday: Weekday::Tuesday
+                },
+                WeekdayNum {
+                    ordinal: 0,
+                    weekday: Weekday::Wednesday
+                },
+            ]
+        );
+
+        assert!(matches!(
+            Rule::from_str("INTERVAL=2"),
+            Err(Error::MissingFrequency)
+        ));
+        assert!(matches!(
+            Rule::from_str("FREQ=HOURLY"),
+            Err(Error::UnsupportedFrequency)
+        ));
+        assert!(matches!(
+            Rule::from_str("FREQ=YEARLY;BYWEEKNO=20"),
+            Err(Error::UnsupportedPart)
+        ));
+    }
+
+    #[test]
+    fn test_daily_with_count() {
+        let dates = occurrences(
+            "FREQ=DAILY;INTERVAL=2;COUNT=3",
+            Date::new::<2025, 11, 3>(),
+            Date::new::<2025, 11, 1>(),
+            Date::new::<2025, 11, 30>(),
+        );
+        assert_eq!(
+            dates,
+            [
+                Date::new::<2025, 11, 3>(),
+                Date::new::<2025, 11, 5>(),
+                Date::new::<2025, 11, 7>(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly_by_day_until() {
+        let dates = occurrences(
+            "FREQ=WEEKLY;BYDAY=MO,FR;UNTIL=20251114",
+            Date::new::<2025, 11, 3>(),
+            Date::new::<2025, 11, 1>(),
+            Date::new::<2025, 11, 30>(),
+        );
+        assert_eq!(
+            dates,
+            [
+                Date::new::<2025, 11, 3>(),
+                Date::new::<2025, 11, 7>(),
+                Date::new::<2025, 11, 10>(),
+                Date::new::<2025, 11, 14>(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_starts_from_the_first_occurrence() {
+        // The window starts after the 2 occurrences.  Given that, only the third one is left.
+        let dates = occurrences(
+            "FREQ=WEEKLY;COUNT=3",
+            Date::new::<2025, 11, 3>(),
+            Date::new::<2025, 11, 15>(),
+            Date::new::<2025, 12, 31>(),
+        );
+        assert_eq!(dates, [Date::new::<2025, 11, 17>()]);
+    }
+
+    #[test]
+    fn test_monthly_last_weekday() {
+        // The last working day of the month
+        let dates = occurrences(
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+            Date::new::<2025, 10, 31>(),
+            Date::new::<2025, 10, 1>(),
+            Date::new::<2026, 1, 31>(),
+        );
+        assert_eq!(
+            dates,
+            [
+                Date::new::<2025, 10, 31>(),
+                Date::new::<2025, 11, 28>(),
+                Date::new::<2025, 12, 31>(),
+                Date::new::<2026, 1, 30>(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monthly_by_month_day_skips_short_months() {
+        let dates = occurrences(
+            "FREQ=MONTHLY;BYMONTHDAY=31",
+            Date::new::<2025, 1, 31>(),
+            Date::new::<2025, 1, 1>(),
+            Date::new::<2025, 5, 31>(),
+        );
+        assert_eq!(
+            dates,
+            [
+                Date::new::<2025, 1, 31>(),
+                Date::new::<2025, 3, 31>(),
+                Date::new::<2025, 5, 31>(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yearly_by_month_and_day() {
+        // US Thanksgiving: the fourth Thursday of November
+        let dates = occurrences(
+            "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH",
+            Date::new::<2024, 11, 28>(),
+            Date::new::<2025, 1, 1>(),
+            Date::new::<2026, 12, 31>(),
+        );
+        assert_eq!(
+            dates,
+            [Date::new::<2025, 11, 27>(), Date::new::<2026, 11, 26>()]
+        );
+    }
+
+    #[test]
+    fn test_yearly_leap_day() {
+        let dates = occurrences(
+            "FREQ=YEARLY",
+            Date::new::<2024, 2, 29>(),
+            Date::new::<2024, 1, 1>(),
+            Date::new::<2032, 12, 31>(),
+        );
+        assert_eq!(
+            dates,
+            [
+                Date::new::<2024, 2, 29>(),
+                Date::new::<2028, 2, 29>(),
+                Date::new::<2032, 2, 29>(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let first = EventRange {
+            start_date: Date::new::<2025, 10, 30>(),
+            start_time: Time::try_new(22, 0).unwrap(),
+            end_date: Date::new::<2025, 10, 31>(),
+            end_time: Time::try_new(2, 0).unwrap(),
+        };
+
+        let recurrence = Recurrence {
+            rule: Some(Rule::from_str("FREQ=DAILY").unwrap()),
+            dates: Vec::new(),
+            exceptions: Vec::from([Date::new::<2025, 11, 4>()]),
+        };
+
+        let window_start = Date::new::<2025, 11, 3>();
+        let window_end = Date::new::<2025, 11, 5>();
+        let ranges = recurrence.expand(&first, &window_start, &window_end);
+        let starts: Vec<Date> = ranges.iter().map(|r| r.start_date.clone()).collect();
+        // The occurrence of 2025-11-02 ends within the window.
+        assert_eq!(
+            starts,
+            [
+                Date::new::<2025, 11, 2>(),
+                Date::new::<2025, 11, 3>(),
+                Date::new::<2025, 11, 5>(),
+            ]
+        );
+        assert_eq!(ranges[2].end_date, Date::new::<2025, 11, 6>());
+        assert_eq!(ranges[2].end_time.hour, 2);
+    }
+
+    #[test]
+    fn test_expand_extra_dates() {
+        let first = EventRange {
+            start_date: Date::new::<2025, 11, 3>(),
+            start_time: Time::try_new(10, 0).unwrap(),
+            end_date: Date::new::<2025, 11, 3>(),
+            end_time: Time::try_new(11, 0).unwrap(),
+        };
+
+        let recurrence = Recurrence {
+            rule: None,
+            dates: Vec::from([Date::new::<2025, 11, 6>(), Date::new::<2025, 12, 1>()]),
+            exceptions: Vec::new(),
+        };
+
+        let window_start = Date::new::<2025, 11, 3>();
+        let window_end = Date::new::<2025, 11, 9>();
+        let ranges = recurrence.expand(&first, &window_start, &window_end);
+        let starts: Vec<Date> = ranges.iter().map(|r| r.start_date.clone()).collect();
+        assert_eq!(
+            starts,
+            [Date::new::<2025, 11, 3>(), Date::new::<2025, 11, 6>()]
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use crate::EventRange;
 use alloc::vec::Vec;
 
-use super::date::{Date, MINUTES_PER_DAY, MINUTES_PER_HOUR, Time};
-use super::types::{FPoint, FSize};
+use super::date::{Date, DateStream, MINUTES_PER_DAY, MINUTES_PER_HOUR, Time, Weekday};
+use super::types::{FPoint, FRect, FSize};
 use super::{Color, EventTable, Lane};
 
 pub struct Arguments {
@@ -307,6 +307,394 @@ pub fn short_event_rectangles(
         })
 }
 
+/// The number of the weeks in the grid of the month view.  Six weeks fit any month.
+pub const MONTH_WEEKS: u8 = 6;
+
+/// The number of the days in the grid of the month view.
+pub const MONTH_DAYS: u16 = MONTH_WEEKS as u16 * 7;
+
+/// The grid of the month view.  It has [`MONTH_WEEKS`] rows of seven cells.
+pub struct MonthArguments {
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    /// The height of a line of a cell.  The first line holds the date of the cell.  The rest hold
+    /// the events.
+    pub line_height: f32,
+}
+
+impl MonthArguments {
+    /// The rectangle of the cell of the `day` counted from the first day of the grid.
+    pub fn cell(&self, day: u16) -> FRect {
+        FRect {
+            x: self.offset_x + (day % 7) as f32 * self.cell_width,
+            y: self.offset_y + (day / 7) as f32 * self.cell_height,
+            w: self.cell_width,
+            h: self.cell_height,
+        }
+    }
+
+    /// The day of the cell under `position`.
+    pub fn find_cell(&self, position: &FPoint) -> Option<u16> {
+        let column = ((position.x - self.offset_x) / self.cell_width).floor();
+        let row = ((position.y - self.offset_y) / self.cell_height).floor();
+        let is_inside = (0. ..7.).contains(&column) && (0. ..MONTH_WEEKS as f32).contains(&row);
+        is_inside.then_some(row as u16 * 7 + column as u16)
+    }
+
+    fn line(&self, day: u16, line: usize) -> FPoint {
+        let cell = self.cell(day);
+        FPoint {
+            x: cell.x,
+            y: cell.y + (line + 1) as f32 * self.line_height,
+        }
+    }
+}
+
+/// An event shown in the month view.
+pub struct MonthEntry {
+    /// The index of the event in its table.
+    pub event: u32,
+    pub rectangle: Rectangle,
+}
+
+/// The cell of the month view which can't fit all of its events.
+pub struct MonthOverflow {
+    /// The number of the events which are not shown.
+    pub count: u32,
+    /// The last line of the cell where the number is shown.
+    pub at: FPoint,
+}
+
+pub struct MonthLayout {
+    /// The bars of the long events.  A bar is split by the weeks.  Given that, an event might
+    /// have several bars.
+    pub bars: Vec<MonthEntry>,
+    /// The lines of the short events stacked in the cells of their days.
+    pub lines: Vec<MonthEntry>,
+    pub overflows: Vec<MonthOverflow>,
+}
+
+/// Lays out the events over the grid of the month view which starts on `first_date`.  The long
+/// events take the top lines of the cells.  Their lanes are shared by the days of a week.  The
+/// short events are stacked below them.  If the events of a day don't fit its cell, the last line
+/// of the cell tells the number of the hidden events.
+///
+/// # Assumptions
+/// The events in the tables are sorted by their start.
+pub fn month_layout(
+    long_events: &EventTable,
+    short_events: &EventTable,
+    first_date: &Date,
+    arguments: &MonthArguments,
+) -> MonthLayout {
+    const DAYS: usize = MONTH_DAYS as usize;
+    let day_of = |date: &Date| usize::try_from(date.subtract(first_date)).ok();
+    let line_count = ((arguments.cell_height / arguments.line_height) as usize).saturating_sub(1);
+
+    // (event, first day, last day, lane)
+    let mut segments: Vec<(u32, usize, usize, usize)> = Vec::new();
+    // The lanes of the bars taken in a day including the empty ones.
+    let mut bar_lanes = [0usize; DAYS];
+    for week_start in (0..DAYS).step_by(7) {
+        // The last day taken in a lane.
+        let mut lane_ends: Vec<usize> = Vec::new();
+        for (event, range) in long_events.event_ranges.iter().enumerate() {
+            let first = day_of(&range.start_date).unwrap_or(0).max(week_start);
+            let last = day_of(&range.end_date).map(|day| day.min(week_start + 6));
+            let Some(last) = last.filter(|last| first <= *last) else {
+                continue;
+            };
+
+            let lane = match lane_ends.iter().position(|end| *end < first) {
+                Some(lane) => lane,
+                None => {
+                    lane_ends.push(0);
+                    lane_ends.len() - 1
+                }
+            };
+            lane_ends[lane] = last;
+            segments.push((event as u32, first, last, lane));
+            for lanes in &mut bar_lanes[first..=last] {
+                *lanes = (*lanes).max(lane + 1);
+            }
+        }
+    }
+
+    let short_days: Vec<Option<usize>> = short_events
+        .event_ranges
+        .iter()
+        .map(|range| day_of(&range.start_date).filter(|day| *day < DAYS))
+        .collect();
+    let mut short_counts = [0usize; DAYS];
+    for day in short_days.iter().flatten() {
+        short_counts[*day] += 1;
+    }
+
+    // The number of the lines of a day which show the events.
+    let visible: [usize; DAYS] = core::array::from_fn(|day| {
+        let total = bar_lanes[day] + short_counts[day];
+        if total <= line_count {
+            total
+        } else {
+            line_count.saturating_sub(1)
+        }
+    });
+
+    let mut hidden = [0u32; DAYS];
+    let mut bars: Vec<MonthEntry> = Vec::new();
+    for (event, first, last, lane) in segments {
+        // The bar is cut where its lane is hidden.
+        let mut run_start: Option<usize> = None;
+        for day in first..=last + 1 {
+            let is_shown = day <= last && lane < visible[day];
+            if day <= last && !is_shown {
+                hidden[day] += 1;
+            }
+
+            match (run_start, is_shown) {
+                (None, true) => run_start = Some(day),
+                (Some(start), false) => {
+                    let at = arguments.line(start as u16, lane);
+                    bars.push(MonthEntry {
+                        event,
+                        rectangle: Rectangle {
+                            at,
+                            size: FSize {
+                                x: (day - start) as f32 * arguments.cell_width,
+                                y: arguments.line_height,
+                            },
+                            color: long_events.calendar_colors[event as usize],
+                        },
+                    });
+                    run_start = None;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut next_lines = bar_lanes;
+    let mut lines: Vec<MonthEntry> = Vec::new();
+    for (event, day) in short_days.into_iter().enumerate() {
+        let Some(day) = day else {
+            continue;
+        };
+
+        let line = next_lines[day];
+        next_lines[day] += 1;
+        if line < visible[day] {
+            lines.push(MonthEntry {
+                event: event as u32,
+                rectangle: Rectangle {
+                    at: arguments.line(day as u16, line),
+                    size: FSize {
+                        x: arguments.cell_width,
+                        y: arguments.line_height,
+                    },
+                    color: short_events.calendar_colors[event],
+                },
+            });
+        } else {
+            hidden[day] += 1;
+        }
+    }
+
+    let overflows = hidden
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0 && line_count > 0)
+        .map(|(day, count)| MonthOverflow {
+            count: *count,
+            at: arguments.line(day as u16, visible[day]),
+        })
+        .collect();
+
+    MonthLayout {
+        bars,
+        lines,
+        overflows,
+    }
+}
+
+/// A line of the agenda view.
+#[derive(Debug, PartialEq)]
+pub enum AgendaEntry {
+    /// The caption of the day whose events follow.
+    Date(Date),
+    /// The index of the event in the table of the long or the short events.
+    Event { event: u32, is_long: bool },
+}
+
+/// Lists the events of `day_count` days starting from `first_date` day by day.  The long events
+/// are listed on every day they last before the short events of the day.  The days without
+/// events are skipped.
+///
+/// # Assumptions
+/// The events in the tables are sorted by their start.
+pub fn agenda_entries(
+    long_events: &EventTable,
+    short_events: &EventTable,
+    first_date: &Date,
+    day_count: u16,
+) -> Vec<AgendaEntry> {
+    let day_of = |date: &Date| date.subtract(first_date);
+    let mut entries: Vec<AgendaEntry> = Vec::new();
+    for day in 0..day_count as i32 {
+        let long = long_events
+            .event_ranges
+            .iter()
+            .enumerate()
+            .filter(|(_, range)| day_of(&range.start_date) <= day && day <= day_of(&range.end_date))
+            .map(|(event, _)| AgendaEntry::Event {
+                event: event as u32,
+                is_long: true,
+            });
+        let short = short_events
+            .event_ranges
+            .iter()
+            .enumerate()
+            .filter(|(_, range)| day_of(&range.start_date) == day)
+            .map(|(event, _)| AgendaEntry::Event {
+                event: event as u32,
+                is_long: false,
+            });
+
+        let caption = entries.len();
+        entries.extend(long.chain(short));
+        if entries.len() > caption {
+            entries.insert(caption, AgendaEntry::Date(first_date.add_days(day as i16)));
+        }
+    }
+
+    entries
+}
+
+/// The number of the columns of the months in the year view.
+pub const YEAR_COLUMNS: u8 = 4;
+
+/// The grid of the year view.  The twelve months take [`YEAR_COLUMNS`] columns.  A month is its
+/// title above [`MONTH_WEEKS`] rows of seven cells like in the month view.
+pub struct YearArguments {
+    pub month_width: f32,
+    pub month_height: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    /// The height of the title of a month.
+    pub title_height: f32,
+}
+
+impl YearArguments {
+    /// The rectangle of the `month` counted from zero.  The rectangle includes the title.
+    pub fn month(&self, month: u8) -> FRect {
+        FRect {
+            x: self.offset_x + (month % YEAR_COLUMNS) as f32 * self.month_width,
+            y: self.offset_y + (month / YEAR_COLUMNS) as f32 * self.month_height,
+            w: self.month_width,
+            h: self.month_height,
+        }
+    }
+
+    /// The cell of the `day` counted from the first day of the grid of the `month`.
+    pub fn cell(&self, month: u8, day: u16) -> FRect {
+        let month = self.month(month);
+        let (width, height) = self.cell_size();
+        FRect {
+            x: month.x + (day % 7) as f32 * width,
+            y: month.y + self.title_height + (day / 7) as f32 * height,
+            w: width,
+            h: height,
+        }
+    }
+
+    /// The month and the day of the cell under `position`.  They're counted like in
+    /// [`Self::cell`].
+    pub fn find_cell(&self, position: &FPoint) -> Option<(u8, u16)> {
+        let column = ((position.x - self.offset_x) / self.month_width).floor();
+        let row = ((position.y - self.offset_y) / self.month_height).floor();
+        let row_count = (12 / YEAR_COLUMNS) as f32;
+        if !(0. ..YEAR_COLUMNS as f32).contains(&column) || !(0. ..row_count).contains(&row) {
+            return None;
+        }
+
+        let month = row as u8 * YEAR_COLUMNS + column as u8;
+        let origin = self.month(month);
+        let (width, height) = self.cell_size();
+        let column = ((position.x - origin.x) / width).floor();
+        let row = ((position.y - origin.y - self.title_height) / height).floor();
+        let is_inside = (0. ..7.).contains(&column) && (0. ..MONTH_WEEKS as f32).contains(&row);
+        is_inside.then_some((month, row as u16 * 7 + column as u16))
+    }
+
+    fn cell_size(&self) -> (f32, f32) {
+        let height = (self.month_height - self.title_height) / MONTH_WEEKS as f32;
+        (self.month_width / 7., height)
+    }
+}
+
+/// The compact month which navigates the week view.  Unlike [`MonthArguments`], the grid holds
+/// only the days of the month in the rows of their weeks.
+pub struct MiniMonth {
+    /// The first day of the month.
+    pub month_start: Date,
+    /// The weekday of the first column.
+    pub first_weekday: Weekday,
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+}
+
+impl MiniMonth {
+    /// The number of the rows which the days of the month take.
+    pub fn week_count(month_start: &Date, first_weekday: Weekday) -> u8 {
+        let leading = month_start.weekday().days_since(first_weekday);
+        let day_count = Date::month_day_count(month_start.year, month_start.month);
+        (leading + day_count).div_ceil(7)
+    }
+
+    /// The days of the month along with their cells.
+    pub fn cells(&self) -> impl Iterator<Item = (Date, FRect)> + '_ {
+        let day_count = Date::month_day_count(self.month_start.year, self.month_start.month);
+        let leading = self.leading_day_count();
+        DateStream::new(self.month_start.clone())
+            .take(day_count as usize)
+            .enumerate()
+            .map(move |(day, date)| {
+                let slot = leading + day as u8;
+                let cell = FRect {
+                    x: self.offset_x + (slot % 7) as f32 * self.cell_width,
+                    y: self.offset_y + (slot / 7) as f32 * self.cell_height,
+                    w: self.cell_width,
+                    h: self.cell_height,
+                };
+                (date, cell)
+            })
+    }
+
+    /// The date of the cell under `position`.  `None` if the cell doesn't belong to the month.
+    pub fn find_date(&self, position: &FPoint) -> Option<Date> {
+        let column = ((position.x - self.offset_x) / self.cell_width).floor();
+        let row = ((position.y - self.offset_y) / self.cell_height).floor();
+        if !(0. ..7.).contains(&column) || row < 0. {
+            return None;
+        }
+
+        let slot = row as i32 * 7 + column as i32;
+        let day = slot - self.leading_day_count() as i32;
+        let day_count = Date::month_day_count(self.month_start.year, self.month_start.month);
+        (0..day_count as i32)
+            .contains(&day)
+            .then(|| self.month_start.add_days(day as i16))
+    }
+
+    /// The number of the empty cells before the first day.
+    fn leading_day_count(&self) -> u8 {
+        self.month_start.weekday().days_since(self.first_weekday)
+    }
+}
+
 pub trait RenderRectangles {
     type Result;
     fn render_rectangles<'r, I>(&self, data: I) -> Self::Result
@@ -416,6 +804,7 @@ mod tests {
             calendar_colors: Vec::from_iter([Color::BLACK]),
             description_handles: Vec::from_iter([1]),
             description_strings: Vec::from_iter([String::from("aorisent")]),
+            ..Default::default()
         };
 
         let ret: Rectangles =
@@ -443,4 +832,168 @@ mod tests {
             x.size.y,
         );
     }
+
+    #[test]
+    fn test_year_arguments() {
+        let arguments = YearArguments {
+            month_width: 140.,
+            month_height: 80.,
+            offset_x: 10.,
+            offset_y: 20.,
+            title_height: 20.,
+        };
+
+        // June is the second month of the second row.
+        let cell = arguments.cell(5, 8);
+        assert_eq!((cell.x, cell.y, cell.w, cell.h), (170., 130., 20., 10.));
+        let center = FPoint { x: 175., y: 135. };
+        assert_eq!(arguments.find_cell(&center), Some((5, 8)));
+        // The title of a month is not a cell.
+        assert_eq!(arguments.find_cell(&FPoint { x: 175., y: 105. }), None);
+        assert_eq!(arguments.find_cell(&FPoint { x: 575., y: 35. }), None);
+    }
+
+    #[test]
+    fn test_mini_month() {
+        // 2026-03-01 is Sunday.
+        let month_start = create_date("2026-03-01");
+        assert_eq!(MiniMonth::week_count(&month_start, Weekday::Monday), 6);
+        assert_eq!(MiniMonth::week_count(&month_start, Weekday::Sunday), 5);
+        let month = MiniMonth {
+            month_start,
+            first_weekday: Weekday::Monday,
+            cell_width: 10.,
+            cell_height: 8.,
+            offset_x: 5.,
+            offset_y: 5.,
+        };
+
+        let cells: Vec<(Date, FRect)> = month.cells().collect();
+        assert_eq!(cells.len(), 31);
+        let (date, cell) = &cells[0];
+        assert_eq!(*date, create_date("2026-03-01"));
+        assert_eq!((cell.x, cell.y), (65., 5.));
+        let (date, cell) = &cells[1];
+        assert_eq!(*date, create_date("2026-03-02"));
+        assert_eq!((cell.x, cell.y), (5., 13.));
+
+        let position = |x, y| FPoint { x, y };
+        assert_eq!(
+            month.find_date(&position(66., 6.)),
+            Some(create_date("2026-03-01"))
+        );
+        assert_eq!(
+            month.find_date(&position(16., 14.)),
+            Some(create_date("2026-03-03"))
+        );
+        // The cells before the first day and after the last one are empty.
+        assert_eq!(month.find_date(&position(6., 6.)), None);
+        assert_eq!(month.find_date(&position(26., 46.)), None);
+        assert_eq!(month.find_date(&position(76., 6.)), None);
+        assert_eq!(month.find_date(&position(4., 14.)), None);
+    }
+
+    #[test]
+    fn test_agenda_entries() {
+        let range = |start: &str, end: &str| EventRange {
+            start_date: create_date(start),
+            start_time: create_time("10:00"),
+            end_date: create_date(end),
+            end_time: create_time("11:00"),
+        };
+        let table = |event_ranges: Vec<EventRange>| EventTable {
+            event_ranges,
+            ..Default::default()
+        };
+
+        // The trip lasts from Sunday until Tuesday.  Nothing happens on Thursday.
+        let long = table(Vec::from([range("2025-11-02", "2025-11-04")]));
+        let short = table(Vec::from([
+            range("2025-11-03", "2025-11-03"),
+            range("2025-11-05", "2025-11-05"),
+            range("2025-11-05", "2025-11-05"),
+            range("2025-11-09", "2025-11-09"),
+        ]));
+        let entries = agenda_entries(&long, &short, &create_date("2025-11-03"), 4);
+
+        let event = |event, is_long| AgendaEntry::Event { event, is_long };
+        assert_eq!(
+            entries,
+            [
+                AgendaEntry::Date(create_date("2025-11-03")),
+                event(0, true),
+                event(0, false),
+                AgendaEntry::Date(create_date("2025-11-04")),
+                event(0, true),
+                AgendaEntry::Date(create_date("2025-11-05")),
+                event(1, false),
+                event(2, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_month_layout() {
+        let range = |start: &str, end: &str| EventRange {
+            start_date: create_date(start),
+            start_time: create_time("10:00"),
+            end_date: create_date(end),
+            end_time: create_time("11:00"),
+        };
+        let table = |event_ranges: Vec<EventRange>| EventTable {
+            calendar_colors: event_ranges.iter().map(|_| Color::BLACK).collect(),
+            titles: event_ranges.iter().map(|_| String::from("event")).collect(),
+            event_ranges,
+            ..Default::default()
+        };
+
+        // The vacation lasts from Friday until Tuesday.  Given that, it's split by the week.
+        let long = table(Vec::from([range("2025-11-07", "2025-11-11")]));
+        let short = table(Vec::from([
+            range("2025-11-03", "2025-11-03"),
+            range("2025-11-10", "2025-11-10"),
+            range("2025-11-10", "2025-11-10"),
+            range("2025-11-10", "2025-11-10"),
+        ]));
+        // A cell has a line of the date and three lines of the events.
+        let arguments = MonthArguments {
+            cell_width: 100.,
+            cell_height: 80.,
+            offset_x: 0.,
+            offset_y: 50.,
+            line_height: 20.,
+        };
+        let first_date = create_date("2025-11-03");
+        let layout = month_layout(&long, &short, &first_date, &arguments);
+
+        let bars: Vec<(u32, f32, f32, f32)> = layout
+            .bars
+            .iter()
+            .map(|bar| {
+                (
+                    bar.event,
+                    bar.rectangle.at.x,
+                    bar.rectangle.at.y,
+                    bar.rectangle.size.x,
+                )
+            })
+            .collect();
+        assert_eq!(bars, [(0, 400., 70., 300.), (0, 0., 150., 200.)]);
+
+        // The vacation and the three events don't fit Monday, 2025-11-10.
+        let lines: Vec<(u32, f32, f32)> = layout
+            .lines
+            .iter()
+            .map(|line| (line.event, line.rectangle.at.x, line.rectangle.at.y))
+            .collect();
+        assert_eq!(lines, [(0, 0., 70.), (1, 0., 170.)]);
+        let [overflow] = layout.overflows.as_slice() else {
+            panic!("a single cell must overflow");
+        };
+        assert_eq!(overflow.count, 2);
+        assert_eq!((overflow.at.x, overflow.at.y), (0., 190.));
+
+        assert_eq!(arguments.find_cell(&FPoint { x: 150., y: 140. }), Some(8));
+        assert_eq!(arguments.find_cell(&FPoint { x: 150., y: 40. }), None);
+    }
 }
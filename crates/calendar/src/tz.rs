@@ -0,0 +1,695 @@
+//! The time zones.  The zones are read from the files of the time zone database (TZif, RFC 8536).
+//! The files are usually stored in `/usr/share/zoneinfo`.  Reading the files is left to the
+//! caller.
+//!
+//! The time beyond the last transition of a file is computed with the rule from the footer of the
+//! file.  E.g. `CET-1CEST,M3.5.0,M10.5.0/3`.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::date::{Date, MINUTES_PER_HOUR, SECONDS_PER_DAY, Time};
+
+const SECONDS_PER_HOUR: i32 = 3600;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The data doesn't start with `TZif`.
+    InvalidMagic,
+    UnexpectedEnd,
+    InvalidData,
+    /// The rule in the footer (POSIX TZ string) can't be parsed.
+    InvalidRule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalTimeType {
+    /// The number of seconds to add to UTC.
+    pub utc_offset: i32,
+    pub is_dst: bool,
+}
+
+/// The day of the year when the daylight saving time starts or ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RuleDate {
+    /// `Jn`: the day from 1 to 365.  February 29 is never counted.
+    Julian(u16),
+    /// `n`: the day from 0 to 365.  February 29 is counted in the leap years.
+    Zero(u16),
+    /// `Mm.w.d`: the day `d` (0 is Sunday) of the week `w` (5 is the last week) of the month `m`.
+    Month { month: u8, week: u8, weekday: u8 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct DaylightSaving {
+    utc_offset: i32,
+    start: RuleDate,
+    /// The local time of the start in seconds.  It's the standard time.
+    start_time: i32,
+    end: RuleDate,
+    /// The local time of the end in seconds.  It's the daylight saving time.
+    end_time: i32,
+}
+
+/// The rule from the footer of TZif (POSIX TZ string).
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    utc_offset: i32,
+    daylight_saving: Option<DaylightSaving>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeZone {
+    /// The moments (Unix time) the local time changes.  They are sorted.
+    transitions: Vec<i64>,
+    /// The indexes in `local_time_types`.  The items correspond to the ones of `transitions`.
+    transition_types: Vec<u8>,
+    local_time_types: Vec<LocalTimeType>,
+    rule: Option<Rule>,
+}
+
+/// A moment along with the offset of the time zone it's observed in.
+#[derive(Debug, Clone)]
+pub struct TzTime {
+    pub date: Date,
+    pub time: Time,
+    /// The number of seconds to add to UTC to get `date` and `time`.
+    pub utc_offset: i32,
+}
+
+fn unix_epoch_days() -> i32 {
+    Date::new::<1970, 1, 1>().calculate_total_days()
+}
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, count: usize) -> Result<&'b [u8], Error> {
+        if self.bytes.len() < count {
+            return Err(Error::UnexpectedEnd);
+        }
+
+        let (ret, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(ret)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let bytes = self.take(N)?;
+        Ok(bytes
+            .try_into()
+            .expect("the slice is taken by the size of the array"))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        self.take_array().map(u32::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Result<i32, Error> {
+        self.take_array().map(i32::from_be_bytes)
+    }
+
+    fn i64(&mut self) -> Result<i64, Error> {
+        self.take_array().map(i64::from_be_bytes)
+    }
+}
+
+struct Header {
+    version: u8,
+    is_ut_count: usize,
+    is_std_count: usize,
+    leap_count: usize,
+    time_count: usize,
+    type_count: usize,
+    char_count: usize,
+}
+
+impl Header {
+    fn read(reader: &mut Reader) -> Result<Header, Error> {
+        if reader.take(4)? != b"TZif" {
+            return Err(Error::InvalidMagic);
+        }
+
+        let version = reader.take(1)?[0];
+        reader.take(15)?;
+        let mut count = || reader.u32().map(|x| x as usize);
+        Ok(Header {
+            version,
+            is_ut_count: count()?,
+            is_std_count: count()?,
+            leap_count: count()?,
+            time_count: count()?,
+            type_count: count()?,
+            char_count: count()?,
+        })
+    }
+
+    /// The size of the data block which follows the header.  `time_size` is 4 in the first
+    /// version of the data block, and 8 in the second one.
+    fn data_size(&self, time_size: usize) -> usize {
+        self.time_count * time_size
+            + self.time_count
+            + self.type_count * 6
+            + self.char_count
+            + self.leap_count * (time_size + 4)
+            + self.is_std_count
+            + self.is_ut_count
+    }
+}
+
+impl TimeZone {
+    /// The zone without any offset.
+    pub fn utc() -> TimeZone {
+        Self::fixed(0)
+    }
+
+    /// The zone with the constant offset in seconds.
+    pub fn fixed(utc_offset: i32) -> TimeZone {
+        TimeZone {
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            local_time_types: Vec::from([LocalTimeType {
+                utc_offset,
+                is_dst: false,
+            }]),
+            rule: None,
+        }
+    }
+
+    /// Parses the content of a TZif file.
+    pub fn parse(bytes: &[u8]) -> Result<TimeZone, Error> {
+        let mut reader = Reader { bytes };
+        let mut header = Header::read(&mut reader)?;
+        let mut time_size = 4;
+        if header.version >= b'2' {
+            // The first data block is kept for the old readers.  The second one has the 64-bit
+            // time.
+            reader.take(header.data_size(time_size))?;
+            header = Header::read(&mut reader)?;
+            time_size = 8;
+        }
+
+        let mut transitions: Vec<i64> = Vec::with_capacity(header.time_count);
+        for _ in 0..header.time_count {
+            let time = match time_size {
+                4 => reader.i32()? as i64,
+                _ => reader.i64()?,
+            };
+            transitions.push(time);
+        }
+
+        let transition_types: Vec<u8> = Vec::from(reader.take(header.time_count)?);
+        let mut local_time_types: Vec<LocalTimeType> = Vec::with_capacity(header.type_count);
+        for _ in 0..header.type_count {
+            let utc_offset = reader.i32()?;
+            let [is_dst, _abbreviation_index] = reader.take_array()?;
+            local_time_types.push(LocalTimeType {
+                utc_offset,
+                is_dst: is_dst != 0,
+            });
+        }
+
+        let is_type_valid = |index: &u8| (*index as usize) < local_time_types.len();
+        if local_time_types.is_empty() || !transition_types.iter().all(is_type_valid) {
+            return Err(Error::InvalidData);
+        }
+
+        // The names of the zones, the leap seconds and the indicators aren't used.
+        reader.take(
+            header.char_count
+                + header.leap_count * (time_size + 4)
+                + header.is_std_count
+                + header.is_ut_count,
+        )?;
+
+        let rule = if time_size == 8 {
+            parse_footer(reader.bytes)?
+        } else {
+            None
+        };
+
+        Ok(TimeZone {
+            transitions,
+            transition_types,
+            local_time_types,
+            rule,
+        })
+    }
+
+    /// Returns the number of seconds to add to UTC at the moment `unix_time`.
+    pub fn utc_offset(&self, unix_time: i64) -> i32 {
+        let index = self.transitions.partition_point(|t| *t <= unix_time);
+        match (index, &self.rule) {
+            (i, Some(rule)) if i == self.transitions.len() => rule.utc_offset(unix_time),
+            (0, _) => {
+                // RFC 8536: the first standard time type is used before the first transition.
+                let first_standard = self.local_time_types.iter().find(|t| !t.is_dst);
+                first_standard
+                    .unwrap_or(&self.local_time_types[0])
+                    .utc_offset
+            }
+            (i, _) => {
+                let local_time_type = self.transition_types[i - 1] as usize;
+                self.local_time_types[local_time_type].utc_offset
+            }
+        }
+    }
+}
+
+fn parse_footer(bytes: &[u8]) -> Result<Option<Rule>, Error> {
+    let footer = core::str::from_utf8(bytes).map_err(|_| Error::InvalidRule)?;
+    let rule = footer.trim_matches('\n');
+    if rule.is_empty() {
+        return Ok(None);
+    }
+
+    RuleParser { rest: rule }.parse().map(Some)
+}
+
+struct RuleParser<'s> {
+    rest: &'s str,
+}
+
+impl<'s> RuleParser<'s> {
+    fn parse(&mut self) -> Result<Rule, Error> {
+        self.name()?;
+        // POSIX counts the offset to the west.  Given that, the sign is inverted.
+        let utc_offset = -self.time()?;
+        if self.rest.is_empty() {
+            return Ok(Rule {
+                utc_offset,
+                daylight_saving: None,
+            });
+        }
+
+        self.name()?;
+        let dst_utc_offset = match self.rest.as_bytes().first() {
+            Some(b',') | None => utc_offset + SECONDS_PER_HOUR,
+            Some(_) => -self.time()?,
+        };
+
+        // The rule of the United States is the default one.
+        let (start, start_time, end, end_time) = if self.rest.is_empty() {
+            let start = RuleDate::Month {
+                month: 3,
+                week: 2,
+                weekday: 0,
+            };
+            let end = RuleDate::Month {
+                month: 11,
+                week: 1,
+                weekday: 0,
+            };
+            (start, 2 * SECONDS_PER_HOUR, end, 2 * SECONDS_PER_HOUR)
+        } else {
+            self.expect(',')?;
+            let (start, start_time) = self.transition()?;
+            self.expect(',')?;
+            let (end, end_time) = self.transition()?;
+            (start, start_time, end, end_time)
+        };
+
+        if !self.rest.is_empty() {
+            return Err(Error::InvalidRule);
+        }
+
+        Ok(Rule {
+            utc_offset,
+            daylight_saving: Some(DaylightSaving {
+                utc_offset: dst_utc_offset,
+                start,
+                start_time,
+                end,
+                end_time,
+            }),
+        })
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        self.rest = self.rest.strip_prefix(c).ok_or(Error::InvalidRule)?;
+        Ok(())
+    }
+
+    /// Skips the name of the zone.  E.g. `CET` or `<+03>`.
+    fn name(&mut self) -> Result<(), Error> {
+        let end = match self.rest.strip_prefix('<') {
+            Some(quoted) => quoted.find('>').ok_or(Error::InvalidRule)? + 2,
+            None => self
+                .rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(self.rest.len()),
+        };
+
+        if end < 3 {
+            return Err(Error::InvalidRule);
+        }
+
+        self.rest = &self.rest[end..];
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<i32, Error> {
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let (digits, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        digits.parse().map_err(|_| Error::InvalidRule)
+    }
+
+    /// The same as [`RuleParser::number`], but the number which doesn't fit `T` is rejected.
+    fn small_number<T: TryFrom<i32>>(&mut self) -> Result<T, Error> {
+        T::try_from(self.number()?).map_err(|_| Error::InvalidRule)
+    }
+
+    /// Parses the time like `-1`, `5:30` or `+25:00:00` into seconds.
+    fn time(&mut self) -> Result<i32, Error> {
+        let sign = match self.rest.as_bytes().first() {
+            Some(b'-') => -1,
+            _ => 1,
+        };
+        self.rest = self.rest.trim_start_matches(['+', '-']);
+
+        let mut seconds = self.number()? * SECONDS_PER_HOUR;
+        let mut unit = MINUTES_PER_HOUR as i32;
+        while let Some(rest) = self.rest.strip_prefix(':') {
+            self.rest = rest;
+            seconds += self.number()? * unit;
+            unit /= MINUTES_PER_HOUR as i32;
+        }
+
+        Ok(sign * seconds)
+    }
+
+    /// Parses the day of the transition.  E.g. `J60`, `59` or `M3.5.0`.
+    fn transition(&mut self) -> Result<(RuleDate, i32), Error> {
+        let date = if let Some(rest) = self.rest.strip_prefix('J') {
+            self.rest = rest;
+            let day = self.small_number::<u16>()?;
+            if !(1..=365).contains(&day) {
+                return Err(Error::InvalidRule);
+            }
+            RuleDate::Julian(day)
+        } else if let Some(rest) = self.rest.strip_prefix('M') {
+            self.rest = rest;
+            let month = self.small_number::<u8>()?;
+            self.expect('.')?;
+            let week = self.small_number::<u8>()?;
+            self.expect('.')?;
+            let weekday = self.small_number::<u8>()?;
+            if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                return Err(Error::InvalidRule);
+            }
+            RuleDate::Month {
+                month,
+                week,
+                weekday,
+            }
+        } else {
+            let day = self.small_number::<u16>()?;
+            if day > 365 {
+                return Err(Error::InvalidRule);
+            }
+            RuleDate::Zero(day)
+        };
+
+        let time = match self.rest.strip_prefix('/') {
+            Some(rest) => {
+                self.rest = rest;
+                self.time()?
+            }
+            None => 2 * SECONDS_PER_HOUR,
+        };
+
+        Ok((date, time))
+    }
+}
+
+impl RuleDate {
+    /// The number of the days from the Unix epoch.
+    fn unix_days(&self, year: u16) -> i32 {
+        let first_day = Date {
+            year,
+            month: 1,
+            day: 1,
+        }
+        .calculate_total_days()
+            - unix_epoch_days();
+        match *self {
+            RuleDate::Julian(day) => {
+                let leap_day = (Date::is_leap_year(year) && day >= 60) as i32;
+                first_day + day as i32 - 1 + leap_day
+            }
+            RuleDate::Zero(day) => first_day + day as i32,
+            RuleDate::Month {
+                month,
+                week,
+                weekday,
+            } => {
+                let month_start = Date {
+                    year,
+                    month,
+                    day: 1,
+                };
+                // 0 is Sunday in the rule, but 0 is Monday in Weekday.
+                let first_weekday = (month_start.weekday().index() + 1) % 7;
+                let mut day = 1 + (weekday + 7 - first_weekday) % 7 + (week - 1) * 7;
+                let day_count = Date::month_day_count(year, month);
+                while day > day_count {
+                    day -= 7;
+                }
+                month_start.calculate_total_days() - unix_epoch_days() + day as i32 - 1
+            }
+        }
+    }
+}
+
+impl Rule {
+    fn utc_offset(&self, unix_time: i64) -> i32 {
+        let Some(dst) = &self.daylight_saving else {
+            return self.utc_offset;
+        };
+
+        let local_days = (unix_time + self.utc_offset as i64).div_euclid(SECONDS_PER_DAY as i64);
+        let year = Date::from_days(local_days as i32 + unix_epoch_days()).year;
+        let start = dst.start.unix_days(year) as i64 * SECONDS_PER_DAY as i64
+            + (dst.start_time - self.utc_offset) as i64;
+        let end = dst.end.unix_days(year) as i64 * SECONDS_PER_DAY as i64
+            + (dst.end_time - dst.utc_offset) as i64;
+
+        let is_dst = if start < end {
+            start <= unix_time && unix_time < end
+        } else {
+            // The southern hemisphere: the daylight saving time takes the end of the year.
+            !(end <= unix_time && unix_time < start)
+        };
+
+        if is_dst {
+            dst.utc_offset
+        } else {
+            self.utc_offset
+        }
+    }
+}
+
+impl TzTime {
+    pub fn from_unix(unix_time: i64, zone: &TimeZone) -> TzTime {
+        let utc_offset = zone.utc_offset(unix_time);
+        let local = unix_time + utc_offset as i64;
+        let days = local.div_euclid(SECONDS_PER_DAY as i64) as i32;
+        let minutes = local.rem_euclid(SECONDS_PER_DAY as i64) / 60;
+        TzTime {
+            date: Date::from_days(days + unix_epoch_days()),
+            time: Time {
+                hour: (minutes / MINUTES_PER_HOUR as i64) as u8,
+                minute: (minutes % MINUTES_PER_HOUR as i64) as u8,
+            },
+            utc_offset,
+        }
+    }
+
+    /// Creates the moment from the local time of the `zone`.  The time which is skipped by the
+    /// transition to the daylight saving time is moved forward.  E.g. 02:30 becomes 03:30.  The
+    /// time which is repeated by the transition from the daylight saving time is taken the second
+    /// time.
+    pub fn from_local(date: &Date, time: &Time, zone: &TimeZone) -> TzTime {
+        let local = (date.calculate_total_days() - unix_epoch_days()) as i64
+            * SECONDS_PER_DAY as i64
+            + time.total_minutes().0 as i64 * 60;
+        let utc_offset = zone.utc_offset(local - zone.utc_offset(local) as i64);
+        Self::from_unix(local - utc_offset as i64, zone)
+    }
+
+    pub fn to_unix(&self) -> i64 {
+        let days = (self.date.calculate_total_days() - unix_epoch_days()) as i64;
+        days * SECONDS_PER_DAY as i64 + self.time.total_minutes().0 as i64 * 60
+            - self.utc_offset as i64
+    }
+
+    /// Returns the same moment observed in the `zone`.
+    pub fn to_zone(&self, zone: &TimeZone) -> TzTime {
+        Self::from_unix(self.to_unix(), zone)
+    }
+}
+
+/// Moves the local time `date` and `time` of the zone `from` to the zone `to`.
+pub fn convert(date: &Date, time: &Time, from: &TimeZone, to: &TimeZone) -> (Date, Time) {
+    let TzTime { date, time, .. } = TzTime::from_local(date, time, from).to_zone(to);
+    (date, time)
+}
+
+/// Provides the time zones by their names.  E.g. `Europe/Berlin`.
+pub trait TimeZoneSource {
+    fn find(&self, name: &str) -> Option<&TimeZone>;
+}
+
+impl TimeZoneSource for BTreeMap<String, TimeZone> {
+    fn find(&self, name: &str) -> Option<&TimeZone> {
+        self.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN_RULE: &[u8] = b"\nCET-1CEST,M3.5.0,M10.5.0/3\n";
+
+    fn push_header(bytes: &mut Vec<u8>, version: u8, time_count: u32, type_count: u32) {
+        bytes.extend_from_slice(b"TZif");
+        bytes.push(version);
+        bytes.extend_from_slice(&[0; 15]);
+        // isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt
+        for count in [0, 0, 0, time_count, type_count, 4] {
+            bytes.extend_from_slice(&u32::to_be_bytes(count));
+        }
+    }
+
+    /// Creates the data of TZif of the second version.  The first data block is empty.
+    fn create_tzif(transitions: &[(i64, u8)], types: &[(i32, bool)], footer: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        push_header(&mut bytes, b'2', 0, 1);
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(b"UTC\0");
+
+        push_header(
+            &mut bytes,
+            b'2',
+            transitions.len() as u32,
+            types.len() as u32,
+        );
+        for (time, _) in transitions {
+            bytes.extend_from_slice(&time.to_be_bytes());
+        }
+        for (_, index) in transitions {
+            bytes.push(*index);
+        }
+        for (offset, is_dst) in types {
+            bytes.extend_from_slice(&offset.to_be_bytes());
+            bytes.push(*is_dst as u8);
+            bytes.push(0);
+        }
+        bytes.extend_from_slice(b"CET\0");
+        bytes.extend_from_slice(footer);
+        bytes
+    }
+
+    fn local(date: Date, hour: u8, minute: u8, zone: &TimeZone) -> TzTime {
+        TzTime::from_local(&date, &Time::try_new(hour, minute).unwrap(), zone)
+    }
+
+    #[test]
+    fn test_footer_rule() {
+        let data = create_tzif(&[], &[(3600, false)], BERLIN_RULE);
+        let berlin = TimeZone::parse(&data).unwrap();
+
+        let winter = local(Date::new::<2026, 1, 15>(), 12, 0, &berlin);
+        assert_eq!(winter.utc_offset, 3600);
+        let summer = local(Date::new::<2026, 7, 15>(), 12, 0, &berlin);
+        assert_eq!(summer.utc_offset, 7200);
+
+        // 2026-03-29 is the last Sunday of March.  The clock jumps from 02:00 to 03:00.
+        let before = local(Date::new::<2026, 3, 29>(), 1, 59, &berlin);
+        assert_eq!(before.utc_offset, 3600);
+        let skipped = local(Date::new::<2026, 3, 29>(), 2, 30, &berlin);
+        assert_eq!((skipped.time.hour, skipped.time.minute), (3, 30));
+        assert_eq!(skipped.utc_offset, 7200);
+
+        // 2026-10-25 is the last Sunday of October.  The clock goes back from 03:00 to 02:00.
+        let after = local(Date::new::<2026, 10, 25>(), 3, 0, &berlin);
+        assert_eq!(after.utc_offset, 3600);
+    }
+
+    #[test]
+    fn test_transitions() {
+        // 2025-03-30T01:00:00Z and 2025-10-26T01:00:00Z
+        let transitions = [(1743296400, 1), (1761440400, 0)];
+        let data = create_tzif(&transitions, &[(3600, false), (7200, true)], b"\n\n");
+        let berlin = TimeZone::parse(&data).unwrap();
+
+        assert_eq!(berlin.utc_offset(1743296399), 3600);
+        assert_eq!(berlin.utc_offset(1743296400), 7200);
+        assert_eq!(berlin.utc_offset(1761440400), 3600);
+        // There is no rule in the footer.  Given that, the last transition is kept.
+        assert_eq!(berlin.utc_offset(1900000000), 3600);
+    }
+
+    #[test]
+    fn test_convert() {
+        let data = create_tzif(&[], &[(3600, false)], BERLIN_RULE);
+        let berlin = TimeZone::parse(&data).unwrap();
+        let new_york_data = create_tzif(&[], &[(-18000, false)], b"\nEST5EDT,M3.2.0,M11.1.0\n");
+        let new_york = TimeZone::parse(&new_york_data).unwrap();
+
+        // The United States switch to the daylight saving time 2 weeks earlier than Europe.
+        let time = Time::try_new(10, 0).unwrap();
+        let (date, time) = convert(&Date::new::<2026, 3, 16>(), &time, &berlin, &new_york);
+        assert_eq!(date, Date::new::<2026, 3, 16>());
+        assert_eq!((time.hour, time.minute), (5, 0));
+
+        let time = Time::try_new(10, 0).unwrap();
+        let (_, time) = convert(&Date::new::<2026, 4, 16>(), &time, &berlin, &new_york);
+        assert_eq!((time.hour, time.minute), (4, 0));
+
+        // The date changes
+        let time = Time::try_new(2, 30).unwrap();
+        let (date, time) = convert(&Date::new::<2026, 1, 1>(), &time, &berlin, &new_york);
+        assert_eq!(date, Date::new::<2025, 12, 31>());
+        assert_eq!((time.hour, time.minute), (20, 30));
+    }
+
+    #[test]
+    fn test_southern_hemisphere() {
+        let data = create_tzif(&[], &[(36000, false)], b"\nAEST-10AEDT,M10.1.0,M4.1.0/3\n");
+        let sydney = TimeZone::parse(&data).unwrap();
+        let january = local(Date::new::<2026, 1, 15>(), 12, 0, &sydney);
+        assert_eq!(january.utc_offset, 39600);
+        let july = local(Date::new::<2026, 7, 15>(), 12, 0, &sydney);
+        assert_eq!(july.utc_offset, 36000);
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        assert!(matches!(TimeZone::parse(b"TZjf"), Err(Error::InvalidMagic)));
+        assert!(matches!(
+            TimeZone::parse(b"TZif2"),
+            Err(Error::UnexpectedEnd)
+        ));
+        let data = create_tzif(&[], &[(3600, false)], b"\nCET-1CEST,M13.5.0\n");
+        assert!(matches!(TimeZone::parse(&data), Err(Error::InvalidRule)));
+        // The numbers which don't fit the fields of the rule are not truncated.
+        for footer in [
+            &b"\nCET-1CEST,M268.5.0,M10.5.0/3\n"[..],
+            b"\nCET-1CEST,J0,J300\n",
+            b"\nCET-1CEST,J65596,J300\n",
+            b"\nCET-1CEST,366,300\n",
+        ] {
+            let data = create_tzif(&[], &[(3600, false)], footer);
+            assert!(matches!(TimeZone::parse(&data), Err(Error::InvalidRule)));
+        }
+    }
+}
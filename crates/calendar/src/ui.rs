@@ -1,7 +1,8 @@
 use crate::EventTable;
 use crate::Lane;
-use crate::date::Date;
+use crate::date::{Date, MINUTES_PER_DAY};
 use crate::render;
+use alloc::vec::Vec;
 
 use super::render::RenderWeekCaptionsArgs;
 use super::render::TextRender;
@@ -11,12 +12,12 @@ use super::types::{FPoint, FRect};
 /// The structure contains the text objects to be rendered.  See
 /// [`TextObjectFactory::BackendResult`].
 pub struct Week<Text> {
-    /// The names of the days of the week. (E.g.  Sunday, Monday etc.)
-    pub days: [Text; 7],
+    /// The names of the shown days. (E.g.  Sunday, Monday etc.)
+    pub days: Vec<Text>,
     /// The hours through out a day. (E.g. 00:00, 01:00, 02:00 etc.)
     pub hours: [Text; 24],
-    /// The dates of the days of the week.  (E.g.  2025-11-17, 2025-11-18 etc.)
-    pub dates: [Text; 7],
+    /// The dates of the shown days.  (E.g.  2025-11-17, 2025-11-18 etc.)
+    pub dates: Vec<Text>,
 }
 
 impl<Text> Week<Text> {
@@ -53,6 +54,8 @@ pub struct View {
     pub cell_width: f32,
     /// The height of a short event.
     pub cell_height: f32,
+    /// The number of the days on the surface.
+    pub day_count: u16,
 }
 
 #[inline]
@@ -79,19 +82,81 @@ impl View {
     }
 
     const LINE_HEIGHT: u8 = 15;
-    pub fn new(viewport_size: FPoint, adjustment: &SurfaceAdjustment) -> Self {
+    pub fn new(viewport_size: FPoint, adjustment: &SurfaceAdjustment, day_count: u16) -> Self {
         let event_surface: FRect = compute_event_surface(
             &viewport_size,
             adjustment.vertical_scale,
             adjustment.vertical_offset,
         );
-        let cell_width: f32 = event_surface.w / 7.;
+        let cell_width: f32 = event_surface.w / day_count as f32;
 
         let cell_height = event_surface.h / 24.;
         Self {
             cell_height,
             cell_width,
             short_event_surface: event_surface,
+            day_count,
+        }
+    }
+}
+
+impl View {
+    /// Stretches the surface so that `day` takes the width of the viewport.  The rest of the
+    /// days are moved out of the viewport.
+    pub fn focus_day(self, day: u8) -> Self {
+        let cell_width = self.short_event_surface.w;
+        let short_event_surface = FRect {
+            x: self.short_event_surface.x - day as f32 * cell_width,
+            w: cell_width * self.day_count as f32,
+            ..self.short_event_surface
+        };
+
+        Self {
+            short_event_surface,
+            cell_width,
+            ..self
+        }
+    }
+}
+
+/// A moment on the grid with the short events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridTime {
+    /// The day of the surface counted from zero.
+    pub day: u8,
+    /// The minutes from the midnight.  The value is up to [`MINUTES_PER_DAY`] inclusively.  The
+    /// last value stands for the midnight of the next day.
+    pub minutes: u16,
+}
+
+impl View {
+    /// Finds the moment under `position` which is relative to the viewport of the short events.
+    /// The minutes are rounded to the closest multiple of `step`.  The position outside of the
+    /// surface is clamped to it.
+    pub fn find_grid_time(&self, position: &FPoint, step: u16) -> GridTime {
+        let surface = &self.short_event_surface;
+        let day = ((position.x - surface.x) / self.cell_width).floor();
+        let day = day.clamp(0., self.day_count.saturating_sub(1) as f32) as u8;
+        let ratio = ((position.y - surface.y) / surface.h).clamp(0., 1.);
+        let minutes = ratio * MINUTES_PER_DAY as f32;
+        let step = step.max(1) as f32;
+        let minutes = (minutes / step).round() * step;
+        GridTime {
+            day,
+            minutes: (minutes as u16).min(MINUTES_PER_DAY),
+        }
+    }
+
+    /// Computes the rectangle which covers the time between `start` and `end` of `day`.  The
+    /// rectangle is relative to the viewport of the short events.
+    pub fn compute_time_range_rectangle(&self, day: u8, start: u16, end: u16) -> FRect {
+        let surface = &self.short_event_surface;
+        let minute_height = surface.h / MINUTES_PER_DAY as f32;
+        FRect {
+            x: surface.x + day as f32 * self.cell_width,
+            y: surface.y + start as f32 * minute_height,
+            w: self.cell_width,
+            h: end.saturating_sub(start) as f32 * minute_height,
         }
     }
 }
@@ -100,9 +165,10 @@ pub fn create_short_event_rectangles(
     short_event_surface: &FRect,
     short_events: &EventTable,
     week_start: &Date,
+    day_count: u16,
 ) -> render::Rectangles {
     let arguments = render::Arguments {
-        column_width: short_event_surface.w / 7.,
+        column_width: short_event_surface.w / day_count as f32,
         column_height: short_event_surface.h,
         offset_x: short_event_surface.x,
         offset_y: short_event_surface.y,
@@ -129,3 +195,89 @@ pub fn create_long_event_rectangles(
 
     pinned_rectangles_res.collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_grid_time() {
+        let adjustment = SurfaceAdjustment {
+            vertical_scale: 0.,
+            vertical_offset: -100.,
+        };
+        // A cell is 100x60 pixels.  Given that, a pixel is a minute.
+        let view = View::new(FPoint { x: 700., y: 1440. }, &adjustment, 7);
+        let time = view.find_grid_time(&FPoint { x: 250., y: 500. }, 15);
+        assert_eq!(
+            time,
+            GridTime {
+                day: 2,
+                minutes: 600
+            }
+        );
+
+        let time = view.find_grid_time(&FPoint { x: 250., y: 507. }, 15);
+        assert_eq!(time.minutes, 600);
+        let time = view.find_grid_time(&FPoint { x: 250., y: 508. }, 15);
+        assert_eq!(time.minutes, 615);
+
+        let time = view.find_grid_time(&FPoint { x: 900., y: 2000. }, 15);
+        assert_eq!(
+            time,
+            GridTime {
+                day: 6,
+                minutes: 1440
+            }
+        );
+
+        let rectangle = view.compute_time_range_rectangle(2, 600, 690);
+        assert_eq!(rectangle.x, 200.);
+        assert_eq!(rectangle.y, 500.);
+        assert_eq!(rectangle.h, 90.);
+    }
+
+    #[test]
+    fn test_day_count() {
+        let adjustment = SurfaceAdjustment {
+            vertical_scale: 0.,
+            vertical_offset: 0.,
+        };
+        let view = View::new(FPoint { x: 600., y: 1440. }, &adjustment, 3);
+        assert_eq!(view.cell_width, 200.);
+
+        let time = view.find_grid_time(&FPoint { x: 900., y: 60. }, 15);
+        assert_eq!(
+            time,
+            GridTime {
+                day: 2,
+                minutes: 60
+            }
+        );
+    }
+
+    #[test]
+    fn test_focus_day() {
+        let adjustment = SurfaceAdjustment {
+            vertical_scale: 0.,
+            vertical_offset: 0.,
+        };
+        // Wednesday takes the width of the viewport.
+        let view = View::new(FPoint { x: 700., y: 1440. }, &adjustment, 7).focus_day(2);
+        assert_eq!(view.cell_width, 700.);
+        assert_eq!(view.short_event_surface.x, -1400.);
+        assert_eq!(view.short_event_surface.w, 4900.);
+
+        let time = view.find_grid_time(&FPoint { x: 350., y: 600. }, 15);
+        assert_eq!(
+            time,
+            GridTime {
+                day: 2,
+                minutes: 600
+            }
+        );
+
+        let rectangle = view.compute_time_range_rectangle(2, 600, 690);
+        assert_eq!((rectangle.x, rectangle.w), (0., 700.));
+    }
+}
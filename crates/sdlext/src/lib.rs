@@ -25,6 +25,8 @@ pub enum Error {
     ProcessIsNotCreated,
     CantGetTextureCoordinates,
     CantSetClipboard,
+    MessageBoxIsNotShown,
+    ProcessIsNotRead,
 }
 
 #[derive(Debug)]
@@ -677,7 +679,7 @@ pub fn set_render_viewport<'a>(
 }
 
 pub fn set_render_clip_rect<'a>(
-    renderer: &mut sdl::SDL_Renderer,
+    renderer: &Renderer,
     rect: impl Into<Option<&'a sdl::SDL_Rect>>,
 ) -> Result<()> {
     unsafe {
@@ -685,7 +687,7 @@ pub fn set_render_clip_rect<'a>(
             .into()
             .map(|r| r as *const _)
             .unwrap_or(std::ptr::null());
-        if !sdl::SDL_SetRenderClipRect(renderer, ptr) {
+        if !sdl::SDL_SetRenderClipRect(renderer.ptr(), ptr) {
             Err(Error::ViewportIsNotSet)
         } else {
             Ok(())
@@ -708,3 +710,220 @@ pub fn get_mouse_position(_renderer: &Renderer) -> (f32, f32) {
         (x, y)
     }
 }
+
+/// The program running in the background.  Unlike `SDL_ReadProcess`, its standard output and
+/// standard error are read without blocking as they arrive.  Given that, the program never gets
+/// stuck on the full pipe, and `SDL_WaitProcess` is called once the output is over.
+pub struct Process {
+    ptr: NonNull<sdl::SDL_Process>,
+    /// The pipes of the standard output and the standard error.  They are owned by the process.
+    /// A pipe is null once its end is reached.
+    stdout_stream: *mut sdl::SDL_IOStream,
+    stderr_stream: *mut sdl::SDL_IOStream,
+    /// The standard output which is not taken by [`Process::take_line`] yet.
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    exit_code: Option<i32>,
+}
+
+impl Process {
+    /// Runs the program.  The first argument is the path to the program.  The program takes the
+    /// environment of the application along with the `variables`.
+    pub fn spawn(
+        args: &[&std::ffi::CStr],
+        variables: &[(&std::ffi::CStr, &std::ffi::CStr)],
+    ) -> Result<Self> {
+        let mut args_ptrs: Vec<*const std::ffi::c_char> =
+            args.iter().map(|arg| arg.as_ptr()).collect();
+        args_ptrs.push(std::ptr::null());
+
+        unsafe {
+            let props: sdl::SDL_PropertiesID = sdl::SDL_CreateProperties();
+            if props == 0 {
+                return Err(Error::ProcessIsNotCreated);
+            }
+
+            let inherit = true;
+            let environment: *mut sdl::SDL_Environment = sdl::SDL_CreateEnvironment(inherit);
+            let pipe = sdl::SDL_ProcessIO_SDL_PROCESS_STDIO_APP as i64;
+            let is_set = !environment.is_null()
+                && variables.iter().all(|(name, value)| {
+                    let overwrite = true;
+                    sdl::SDL_SetEnvironmentVariable(
+                        environment,
+                        name.as_ptr(),
+                        value.as_ptr(),
+                        overwrite,
+                    )
+                })
+                && sdl::SDL_SetPointerProperty(
+                    props,
+                    sdl::SDL_PROP_PROCESS_CREATE_ARGS_POINTER.as_ptr().cast(),
+                    args_ptrs.as_mut_ptr().cast(),
+                )
+                && sdl::SDL_SetPointerProperty(
+                    props,
+                    sdl::SDL_PROP_PROCESS_CREATE_ENVIRONMENT_POINTER
+                        .as_ptr()
+                        .cast(),
+                    environment.cast(),
+                )
+                && sdl::SDL_SetNumberProperty(
+                    props,
+                    sdl::SDL_PROP_PROCESS_CREATE_STDOUT_NUMBER.as_ptr().cast(),
+                    pipe,
+                )
+                && sdl::SDL_SetNumberProperty(
+                    props,
+                    sdl::SDL_PROP_PROCESS_CREATE_STDERR_NUMBER.as_ptr().cast(),
+                    pipe,
+                );
+            // The arguments and the environment are copied by SDL.
+            let ptr: *mut sdl::SDL_Process = match is_set {
+                true => sdl::SDL_CreateProcessWithProperties(props),
+                false => std::ptr::null_mut(),
+            };
+            sdl::SDL_DestroyProperties(props);
+            if !environment.is_null() {
+                sdl::SDL_DestroyEnvironment(environment);
+            }
+
+            let ptr = NonNull::new(ptr).ok_or(Error::ProcessIsNotCreated)?;
+            let stdout_stream = sdl::SDL_GetProcessOutput(ptr.as_ptr());
+            let stderr_stream: *mut sdl::SDL_IOStream = sdl::SDL_GetPointerProperty(
+                sdl::SDL_GetProcessProperties(ptr.as_ptr()),
+                sdl::SDL_PROP_PROCESS_STDERR_POINTER.as_ptr().cast(),
+                std::ptr::null_mut(),
+            )
+            .cast();
+            Ok(Self {
+                ptr,
+                stdout_stream,
+                stderr_stream,
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+                exit_code: None,
+            })
+        }
+    }
+
+    /// Reads the output which has arrived since the last call.  Returns `true` once the output is
+    /// over and the process has exited.
+    pub fn drain(&mut self) -> Result<bool> {
+        unsafe {
+            self.stdout_stream = read_available(self.stdout_stream, &mut self.stdout)?;
+            self.stderr_stream = read_available(self.stderr_stream, &mut self.stderr)?;
+            let is_output_over = self.stdout_stream.is_null() && self.stderr_stream.is_null();
+            if is_output_over && self.exit_code.is_none() {
+                let block = false;
+                let mut exit_code = 0;
+                if sdl::SDL_WaitProcess(self.ptr.as_ptr(), block, &mut exit_code) {
+                    self.exit_code = Some(exit_code);
+                }
+            }
+        }
+
+        Ok(self.exit_code.is_some())
+    }
+
+    /// Reads the output until the process exits.  Returns the exit code.  The function blocks the
+    /// thread.  Given that, it's not called in the main thread.
+    pub fn wait(&mut self) -> Result<i32> {
+        // The pipes are non-blocking.  The pause keeps the loop from taking the whole core.
+        let pause = std::time::Duration::from_millis(10);
+        while !self.drain()? {
+            std::thread::sleep(pause);
+        }
+
+        Ok(self.exit_code.unwrap_or_default())
+    }
+
+    /// Takes all the standard output read so far.
+    pub fn take_stdout(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.stdout)
+    }
+
+    /// Takes the next line of the standard output without the line break.  The last line is taken
+    /// without the line break once the process has exited.
+    pub fn take_line(&mut self) -> Option<Vec<u8>> {
+        match self.stdout.iter().position(|byte| *byte == b'\n') {
+            Some(end) => {
+                let mut line: Vec<u8> = self.stdout.drain(..=end).collect();
+                line.pop();
+                Some(line)
+            }
+            None if self.exit_code.is_some() && !self.stdout.is_empty() => {
+                Some(std::mem::take(&mut self.stdout))
+            }
+            None => None,
+        }
+    }
+
+    /// The standard error read so far.
+    pub fn stderr(&self) -> &[u8] {
+        &self.stderr
+    }
+
+    /// The exit code of the process.  It's known once [`Process::drain`] returns `true`.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    pub fn kill(&self) {
+        unsafe {
+            let force: bool = true;
+            sdl::SDL_KillProcess(self.ptr.as_ptr(), force);
+        }
+    }
+}
+
+impl Drop for Process {
+    /// Kills the process unless it has exited.  The process is waited for, so it doesn't stay a
+    /// zombie.  The wait is short since the process is killed by force.
+    fn drop(&mut self) {
+        unsafe {
+            if self.exit_code.is_none() {
+                let force = true;
+                sdl::SDL_KillProcess(self.ptr.as_ptr(), force);
+                let block = true;
+                let mut exit_code = 0;
+                sdl::SDL_WaitProcess(self.ptr.as_ptr(), block, &mut exit_code);
+            }
+            sdl::SDL_DestroyProcess(self.ptr.as_ptr());
+        }
+    }
+}
+
+/// Appends the data available in the pipe of the process to `buffer`.  Returns the pipe back
+/// unless its end is reached.
+///
+/// # Safety
+///
+/// The `stream` is either null or a pipe of the process which is not destroyed yet.
+unsafe fn read_available(
+    stream: *mut sdl::SDL_IOStream,
+    buffer: &mut Vec<u8>,
+) -> Result<*mut sdl::SDL_IOStream> {
+    if stream.is_null() {
+        return Ok(stream);
+    }
+
+    let mut chunk = [0u8; 4096];
+    loop {
+        unsafe {
+            let size = sdl::SDL_ReadIO(stream, chunk.as_mut_ptr().cast(), chunk.len());
+            buffer.extend_from_slice(&chunk[..size]);
+            if size > 0 {
+                continue;
+            }
+
+            // The pipe is non-blocking.  Nothing is read if the process hasn't written anything.
+            return match sdl::SDL_GetIOStatus(stream) {
+                sdl::SDL_IOStatus_SDL_IO_STATUS_NOT_READY
+                | sdl::SDL_IOStatus_SDL_IO_STATUS_READY => Ok(stream),
+                sdl::SDL_IOStatus_SDL_IO_STATUS_EOF => Ok(std::ptr::null_mut()),
+                _ => Err(Error::ProcessIsNotRead),
+            };
+        }
+    }
+}